@@ -0,0 +1,74 @@
+//! Conversion to Apache Arrow record batches
+
+use crate::{ColumnValues, CompactColumn, WorkbookData};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow_schema::{ArrowError, Field, Schema};
+use std::sync::Arc;
+
+/// Days from the Excel epoch (1899-12-30) to the Unix epoch
+const EXCEL_UNIX_EPOCH_DAYS: f64 = 25569.;
+const MILLIS_PER_DAY: f64 = 86_400_000.;
+
+fn to_array(column: &CompactColumn) -> ArrayRef {
+    let present = |i: usize| column.is_present(i);
+
+    match column.values() {
+        ColumnValues::Int(v) => Arc::new(
+            v.iter()
+                .enumerate()
+                .map(|(i, &x)| present(i).then_some(x))
+                .collect::<Int64Array>(),
+        ),
+        ColumnValues::Float(v) => Arc::new(
+            v.iter()
+                .enumerate()
+                .map(|(i, &x)| present(i).then_some(x))
+                .collect::<Float64Array>(),
+        ),
+        ColumnValues::DateTime(v) => Arc::new(
+            v.iter()
+                .enumerate()
+                .map(|(i, &serial)| {
+                    present(i)
+                        .then(|| ((serial - EXCEL_UNIX_EPOCH_DAYS) * MILLIS_PER_DAY).round() as i64)
+                })
+                .collect::<TimestampMillisecondArray>(),
+        ),
+        ColumnValues::Bool(v) => Arc::new(
+            v.iter()
+                .enumerate()
+                .map(|(i, &x)| present(i).then_some(x))
+                .collect::<BooleanArray>(),
+        ),
+        ColumnValues::Str { .. } => Arc::new(
+            (0..column.len())
+                .map(|i| column.get_str(i).filter(|_| present(i)))
+                .collect::<StringArray>(),
+        ),
+    }
+}
+
+impl WorkbookData {
+    /// Convert the data rows to an Arrow record batch, inferring each column's type
+    ///
+    /// Columns are typed as in `compact`: integers, floats, booleans, dates (as millisecond
+    /// timestamps) or strings. Empty cells become nulls.
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let compact = self.compact();
+
+        let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = compact
+            .columns()
+            .iter()
+            .map(|column| {
+                let array = to_array(column);
+                let field = Field::new(column.name(), array.data_type().clone(), true);
+                (field, array)
+            })
+            .unzip();
+
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+    }
+}
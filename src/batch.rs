@@ -0,0 +1,214 @@
+//! Loading a set of identically-structured files, like a folder of monthly drops
+
+use crate::{DataError, LoadError, LoadOptions, RowData, RowsIterator, WorkbookData};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Several workbooks with the same columns, loaded together
+pub struct WorkbookBatch {
+    files: Vec<(PathBuf, WorkbookData)>,
+}
+
+impl WorkbookBatch {
+    /// Get the number of files in the batch
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Get the paths of the files, in load order
+    pub fn paths(&self) -> impl Iterator<Item = &Path> + '_ {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Get each file's path and data, in load order
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &WorkbookData)> + '_ {
+        self.files.iter().map(|(path, data)| (path.as_path(), data))
+    }
+
+    /// Get the headers of the first file; every file has the same set, though maybe not in the
+    /// same order
+    pub fn headers(&self) -> impl Iterator<Item = &str> + '_ {
+        self.files
+            .iter()
+            .take(1)
+            .flat_map(|(_, data)| data.headers())
+    }
+
+    /// Iterate the rows of every file in turn
+    pub fn iter_rows(&self) -> BatchRows<'_> {
+        BatchRows {
+            files: self.files.iter(),
+            current: None,
+        }
+    }
+}
+
+/// A row from one file of a `WorkbookBatch`
+pub struct BatchRow<'a> {
+    path: &'a Path,
+    row: RowData<'a>,
+}
+
+impl<'a> BatchRow<'a> {
+    /// Get the path of the file this row came from
+    pub fn path(&self) -> &'a Path {
+        self.path
+    }
+
+    /// Get the row number of this data in its source file
+    pub fn number(&self) -> u32 {
+        self.row.number()
+    }
+
+    /// Get the value in the cell of this row with the matching column header
+    pub fn get(&self, column_header: &str) -> Result<Cow<'a, str>, DataError> {
+        self.row.get(column_header)
+    }
+
+    /// Get the row within its own file, for the full `RowData` API
+    pub fn row(&self) -> &RowData<'a> {
+        &self.row
+    }
+}
+
+/// Iterator returned by `WorkbookBatch::iter_rows`
+pub struct BatchRows<'a> {
+    files: std::slice::Iter<'a, (PathBuf, WorkbookData)>,
+    current: Option<(&'a Path, RowsIterator<'a>)>,
+}
+
+impl<'a> Iterator for BatchRows<'a> {
+    type Item = BatchRow<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((path, rows)) = &mut self.current {
+                if let Some(row) = rows.next() {
+                    return Some(BatchRow { path, row });
+                }
+            }
+
+            let (path, data) = self.files.next()?;
+            self.current = Some((path.as_path(), data.iter_rows()));
+        }
+    }
+}
+
+/// Load several files that share the same columns
+///
+/// Every file must have the same headers as the first, in any order; a file with different ones
+/// fails with `LoadError::IncompatibleHeaders`. Files are loaded in the given order.
+pub fn from_paths<I, P>(paths: I) -> Result<WorkbookBatch, LoadError>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    from_paths_with_options(paths, &LoadOptions::default())
+}
+
+/// Load several files that share the same columns, each with the given options
+pub fn from_paths_with_options<I, P>(
+    paths: I,
+    options: &LoadOptions,
+) -> Result<WorkbookBatch, LoadError>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut files: Vec<(PathBuf, WorkbookData)> = Vec::new();
+
+    for path in paths {
+        let path = path.as_ref();
+        let data = WorkbookData::from_path_with_options(path, options)?;
+
+        if let Some((_, first)) = files.first() {
+            check_headers(path, first, &data)?;
+        }
+        files.push((path.to_owned(), data));
+    }
+
+    Ok(WorkbookBatch { files })
+}
+
+/// Load every file matching a pattern like `drops/2024-*.xlsx`, in name order
+///
+/// `*` matches any run of characters and `?` any single one, within the file name only; the
+/// directory part is taken literally. Fails with `LoadError::NoMatchingFiles` if nothing matches.
+pub fn from_glob(pattern: &str) -> Result<WorkbookBatch, LoadError> {
+    from_glob_with_options(pattern, &LoadOptions::default())
+}
+
+/// Load every file matching a pattern, each with the given options
+pub fn from_glob_with_options(
+    pattern: &str,
+    options: &LoadOptions,
+) -> Result<WorkbookBatch, LoadError> {
+    let paths = glob(pattern)?;
+
+    if paths.is_empty() {
+        return Err(LoadError::NoMatchingFiles(pattern.to_owned()));
+    }
+    from_paths_with_options(paths, options)
+}
+
+fn check_headers(path: &Path, first: &WorkbookData, data: &WorkbookData) -> Result<(), LoadError> {
+    let missing: Vec<String> = first
+        .headers()
+        .filter(|h| data.column_id(h).is_none())
+        .map(String::from)
+        .collect();
+    let unexpected: Vec<String> = data
+        .headers()
+        .filter(|h| first.column_id(h).is_none())
+        .map(String::from)
+        .collect();
+
+    if missing.is_empty() && unexpected.is_empty() {
+        return Ok(());
+    }
+    Err(LoadError::IncompatibleHeaders {
+        filename: path.to_string_lossy().to_string(),
+        missing,
+        unexpected,
+    })
+}
+
+/// List the files matching a pattern with wildcards in its file name, sorted by name
+fn glob(pattern: &str) -> Result<Vec<PathBuf>, LoadError> {
+    let pattern = Path::new(pattern);
+    let name = match pattern.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => return Ok(Vec::new()),
+    };
+    let dir = match pattern.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name: Vec<char> = name.chars().collect();
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name: Vec<char> = entry.file_name().to_string_lossy().chars().collect();
+        if entry.file_type()?.is_file() && wildcard_match(&name, &file_name) {
+            paths.push(dir.join(entry.file_name()));
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Match `*` and `?` wildcards against a whole name
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| wildcard_match(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && wildcard_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard_match(rest, &name[1..]),
+    }
+}
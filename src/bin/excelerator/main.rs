@@ -0,0 +1,197 @@
+//! Command line access to excelerator's loading and header detection
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use excelerator::{LoadOptions, WorkbookData};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+
+#[cfg(feature = "tui")]
+mod view;
+
+#[derive(Parser)]
+#[command(
+    name = "excelerator",
+    version,
+    about = "Read tables from spreadsheet files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the detected headers, one per line
+    Headers {
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Convert the table to CSV, JSON or JSON Lines
+    Convert {
+        #[command(flatten)]
+        input: Input,
+
+        #[arg(short = 't', long = "to", value_enum, default_value = "csv")]
+        format: Format,
+
+        /// Output file (default: standard output)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Comma-separated headers of the columns to keep, in output order
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        /// Keep only the first N data rows
+        #[arg(long, conflicts_with = "tail")]
+        head: Option<usize>,
+
+        /// Keep only the last N data rows
+        #[arg(long)]
+        tail: Option<usize>,
+    },
+    /// Browse the table, header detection and inferred column types interactively
+    #[cfg(feature = "tui")]
+    View {
+        #[command(flatten)]
+        input: Input,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// Spreadsheet file to read
+    file: PathBuf,
+
+    /// Sheet to read (default: the first sheet with a header)
+    #[arg(short, long)]
+    sheet: Option<String>,
+
+    /// Pass over hidden sheets when looking for the first sheet with a header
+    #[arg(long, conflicts_with = "sheet")]
+    skip_hidden: bool,
+
+    /// A1-style range to read, like B4:H200
+    #[arg(short, long)]
+    range: Option<String>,
+
+    /// Defined name whose range to read, instead of a sheet and range
+    #[arg(long, conflicts_with_all = ["sheet", "range", "skip_hidden"])]
+    name: Option<String>,
+
+    /// Excel Table to read, instead of a sheet and range
+    #[arg(long, conflicts_with_all = ["sheet", "range", "skip_hidden", "name"])]
+    table: Option<String>,
+
+    /// 1-based row number of the header (default: detected)
+    #[arg(long)]
+    header_row: Option<u32>,
+
+    /// Read every row as data, naming columns A, B, C, ...
+    #[arg(long, conflicts_with = "header_row")]
+    headerless: bool,
+}
+
+impl Input {
+    fn load(&self) -> Result<WorkbookData, Box<dyn Error>> {
+        let mut options = LoadOptions::new()
+            .skip_hidden_sheets(self.skip_hidden)
+            .headerless(self.headerless);
+        if let Some(sheet) = &self.sheet {
+            options = options.sheet_name(sheet);
+        }
+        if let Some(range) = &self.range {
+            options = options.range(range);
+        }
+        if let Some(name) = &self.name {
+            options = options.defined_name(name);
+        }
+        if let Some(table) = &self.table {
+            options = options.table(table);
+        }
+        if let Some(row) = self.header_row {
+            let row = row.checked_sub(1).ok_or("header row numbers start at 1")?;
+            options = options.header_row(row);
+        }
+
+        Ok(WorkbookData::from_path_with_options(&self.file, &options)?)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+    Jsonl,
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Headers { input } => {
+            let data = input.load()?;
+            let stdout = io::stdout();
+            let mut out = stdout.lock();
+            for header in data.headers() {
+                writeln!(out, "{}", header)?;
+            }
+        }
+        Command::Convert {
+            input,
+            format,
+            output,
+            columns,
+            head,
+            tail,
+        } => {
+            let data = input.load()?;
+
+            let rows = data.iter_rows().count();
+            let skip = match tail {
+                Some(tail) => rows.saturating_sub(tail),
+                None => 0,
+            };
+            let take = head.unwrap_or(usize::MAX);
+
+            let mut index = 0;
+            let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+            let data = data.materialize_columns(&columns, |_| {
+                let keep = index >= skip && index - skip < take;
+                index += 1;
+                keep
+            })?;
+
+            let out: Box<dyn Write> = match &output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout()),
+            };
+            let mut out = BufWriter::new(out);
+
+            match format {
+                Format::Csv => data.to_csv(&mut out)?,
+                Format::Json => {
+                    serde_json::to_writer_pretty(&mut out, &data.to_json())?;
+                    writeln!(out)?;
+                }
+                Format::Jsonl => data.write_jsonl(&mut out)?,
+            }
+            out.flush()?;
+        }
+        #[cfg(feature = "tui")]
+        Command::View { input } => {
+            let data = input.load()?;
+            view::run(&input.file, &data)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("excelerator: {}", err);
+        process::exit(1);
+    }
+}
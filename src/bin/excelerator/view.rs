@@ -0,0 +1,303 @@
+//! Interactive terminal viewer for the detected table, header detection and inferred types
+
+use excelerator::{column_letter, InferredSchema, WorkbookData};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::path::Path;
+
+/// Widest a column is drawn, in characters
+const MAX_COLUMN_WIDTH: usize = 30;
+
+/// Sheet rows shown above and below the header in the header view
+const CONTEXT_ROWS: u32 = 10;
+
+const HELP: &str = "q quit | Tab switch view | arrows/hjkl scroll | PgUp/PgDn page | Home/End";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum View {
+    Data,
+    Header,
+    Types,
+}
+
+const VIEWS: [View; 3] = [View::Data, View::Header, View::Types];
+
+impl View {
+    fn title(self) -> &'static str {
+        match self {
+            View::Data => "Data",
+            View::Header => "Header detection",
+            View::Types => "Inferred types",
+        }
+    }
+}
+
+struct Viewer<'a> {
+    data: &'a WorkbookData,
+    title: String,
+    schema: InferredSchema,
+    rows: usize,
+    view: View,
+    /// First visible row of the current view
+    row: usize,
+    /// First visible column of the data and header views
+    col: usize,
+    /// Number of rows that fit on screen, from the last draw
+    page: usize,
+}
+
+/// Show `data` until the user quits
+pub fn run(path: &Path, data: &WorkbookData) -> io::Result<()> {
+    let mut viewer = Viewer::new(path, data);
+
+    let mut terminal = ratatui::init();
+    let result = viewer.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<'a> Viewer<'a> {
+    fn new(path: &Path, data: &'a WorkbookData) -> Self {
+        let title = match data.sheet_name() {
+            Some(sheet) => format!("{} [{}]", path.display(), sheet),
+            None => path.display().to_string(),
+        };
+
+        Self {
+            data,
+            title,
+            schema: data.infer_schema(),
+            rows: data.iter_rows().count(),
+            view: View::Data,
+            row: 0,
+            col: 0,
+            page: 1,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Tab => self.switch(1),
+                    KeyCode::BackTab => self.switch(VIEWS.len() - 1),
+                    KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+                    KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+                    KeyCode::PageDown => self.scroll(self.page as isize),
+                    KeyCode::PageUp => self.scroll(-(self.page as isize)),
+                    KeyCode::Home => self.row = 0,
+                    KeyCode::End => self.row = self.row_count().saturating_sub(self.page),
+                    KeyCode::Right | KeyCode::Char('l') => {
+                        self.col = (self.col + 1).min(self.schema.columns.len().saturating_sub(1))
+                    }
+                    KeyCode::Left | KeyCode::Char('h') => self.col = self.col.saturating_sub(1),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn switch(&mut self, step: usize) {
+        let i = VIEWS.iter().position(|&v| v == self.view).unwrap_or(0);
+        self.view = VIEWS[(i + step) % VIEWS.len()];
+        self.row = 0;
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        let max = self.row_count().saturating_sub(1);
+        self.row = self.row.saturating_add_signed(delta).min(max);
+    }
+
+    /// Number of scrollable rows in the current view
+    fn row_count(&self) -> usize {
+        match self.view {
+            View::Data => self.rows,
+            View::Header => self.header_rows().len(),
+            View::Types => self.schema.columns.len(),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, info_area, body_area, help_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let selected = VIEWS.iter().position(|&v| v == self.view).unwrap_or(0);
+        let tabs = Tabs::new(VIEWS.iter().map(|v| v.title()))
+            .select(selected)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_widget(tabs, tabs_area);
+
+        // Row numbers are zero-based; show them as sheet rows
+        let info = format!(
+            "{}: header row {}, {} data rows (sheet rows {}-{}), {} columns{}",
+            self.title,
+            self.data.header_row() + 1,
+            self.rows,
+            self.data.first_row + 1,
+            self.data.last_row + 1,
+            self.schema.columns.len(),
+            if self.data.is_transposed() {
+                ", transposed"
+            } else {
+                ""
+            }
+        );
+        frame.render_widget(Paragraph::new(info), info_area);
+
+        // Leave room for the border and table header
+        self.page = usize::from(body_area.height.saturating_sub(3)).max(1);
+
+        match self.view {
+            View::Data => self.draw_data(frame, body_area),
+            View::Header => self.draw_header(frame, body_area),
+            View::Types => self.draw_types(frame, body_area),
+        }
+
+        frame.render_widget(Paragraph::new(HELP), help_area);
+    }
+
+    fn draw_data(&self, frame: &mut Frame, area: Rect) {
+        let columns = &self.schema.columns[self.col.min(self.schema.columns.len())..];
+
+        let rows: Vec<Vec<String>> = self
+            .data
+            .iter_rows()
+            .skip(self.row)
+            .take(self.page)
+            .map(|row| {
+                let mut cells = vec![(row.number() + 1).to_string()];
+                cells.extend(row.values().into_iter().skip(self.col).map(String::from));
+                cells
+            })
+            .collect();
+
+        let mut header = vec![String::from("Row")];
+        header.extend(columns.iter().map(|c| c.name.clone()));
+
+        let title = format!(
+            "{} (columns from {})",
+            View::Data.title(),
+            columns.first().map_or("-", |c| c.position.as_str())
+        );
+
+        frame.render_widget(grid(header, rows, title), area);
+    }
+
+    /// Sheet rows around the header, as (absolute row, is header)
+    fn header_rows(&self) -> Vec<(u32, bool)> {
+        let header_row = self.data.header_row();
+        let start = self
+            .data
+            .as_range()
+            .start()
+            .map_or(0, |(row, _)| row)
+            .max(header_row.saturating_sub(CONTEXT_ROWS));
+        let end = header_row
+            .saturating_add(CONTEXT_ROWS)
+            .min(self.data.last_row);
+
+        (start..=end).map(|row| (row, row == header_row)).collect()
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let range = self.data.as_range();
+        let first_col = self.data.first_col.saturating_add(self.col as u32);
+        let cols = first_col..=self.data.last_col;
+
+        let mut header = vec![String::from("Row")];
+        header.extend(cols.clone().map(column_letter));
+
+        let mut highlighted = None;
+        let rows: Vec<Vec<String>> = self
+            .header_rows()
+            .into_iter()
+            .skip(self.row)
+            .take(self.page)
+            .enumerate()
+            .map(|(i, (row, is_header))| {
+                if is_header {
+                    highlighted = Some(i);
+                }
+                let mut cells = vec![(row + 1).to_string()];
+                cells.extend(cols.clone().map(|col| {
+                    range
+                        .get_value((row, col))
+                        .map(|v| v.to_string())
+                        .unwrap_or_default()
+                }));
+                cells
+            })
+            .collect();
+
+        let title = format!(
+            "{} (row {} is the header)",
+            View::Header.title(),
+            self.data.header_row() + 1
+        );
+
+        let table = grid(header, rows, title);
+        let mut state = ratatui::widgets::TableState::new().with_selected(highlighted);
+        frame.render_stateful_widget(
+            table.row_highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            area,
+            &mut state,
+        );
+    }
+
+    fn draw_types(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self
+            .schema
+            .columns
+            .iter()
+            .skip(self.row)
+            .take(self.page + 1)
+            .map(|c| {
+                let line = Line::from(c.to_string());
+                if c.is_uniform() {
+                    line
+                } else {
+                    line.style(Style::new().add_modifier(Modifier::BOLD))
+                }
+            })
+            .collect();
+
+        let block = Block::bordered().title(View::Types.title());
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Build a table sizing each column to its widest cell
+fn grid(header: Vec<String>, rows: Vec<Vec<String>>, title: String) -> Table<'static> {
+    let widths: Vec<Constraint> = (0..header.len())
+        .map(|i| {
+            let widest = std::iter::once(&header)
+                .chain(&rows)
+                .filter_map(|cells| cells.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0);
+            Constraint::Length(widest.clamp(1, MAX_COLUMN_WIDTH) as u16)
+        })
+        .collect();
+
+    Table::new(rows.into_iter().map(Row::new), widths)
+        .header(Row::new(header).style(Style::new().add_modifier(Modifier::BOLD)))
+        .block(Block::bordered().title(title))
+}
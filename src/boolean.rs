@@ -0,0 +1,101 @@
+//! Yes/no values as spreadsheets write them: `Yes`, `Y`, `1`, `TRUE`, or an `x` in a box
+
+use crate::{cell_to_str, DataError, DataType, RowData};
+
+/// Which words read as true and false, compared case-insensitively after trimming
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoolOptions {
+    truthy: Vec<String>,
+    falsy: Vec<String>,
+    /// What an empty cell means, if anything
+    empty: Option<bool>,
+}
+
+impl Default for BoolOptions {
+    /// `true/false`, `yes/no`, `y/n`, `1/0`, and `x` or an empty cell
+    fn default() -> Self {
+        BoolOptions::new()
+            .truthy(&["true", "yes", "y", "1", "x"])
+            .falsy(&["false", "no", "n", "0"])
+            .empty_as(false)
+    }
+}
+
+impl BoolOptions {
+    /// Options that accept nothing, to build up a custom set of words
+    pub fn new() -> Self {
+        Self {
+            truthy: Vec::new(),
+            falsy: Vec::new(),
+            empty: None,
+        }
+    }
+
+    pub fn truthy(mut self, words: &[&str]) -> Self {
+        self.truthy
+            .extend(words.iter().map(|w| w.trim().to_lowercase()));
+        self
+    }
+
+    pub fn falsy(mut self, words: &[&str]) -> Self {
+        self.falsy
+            .extend(words.iter().map(|w| w.trim().to_lowercase()));
+        self
+    }
+
+    /// Read empty cells as `value` instead of rejecting them
+    pub fn empty_as(mut self, value: bool) -> Self {
+        self.empty = Some(value);
+        self
+    }
+
+    /// Read a cell, accepting boolean cells as they are
+    pub fn read(&self, value: &DataType) -> Option<bool> {
+        match value {
+            DataType::Bool(b) => Some(*b),
+            DataType::Empty => self.empty,
+            value => self.parse(&cell_to_str(value)),
+        }
+    }
+
+    /// Read a word
+    pub fn parse(&self, text: &str) -> Option<bool> {
+        let text = text.trim().to_lowercase();
+        if text.is_empty() {
+            self.empty
+        } else if self.truthy.contains(&text) {
+            Some(true)
+        } else if self.falsy.contains(&text) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get a yes/no value written any of the ways `BoolOptions::default` accepts
+    pub fn get_bool_flexible(&self, column_header: &str) -> Result<bool, DataError> {
+        self.get_bool_with(column_header, &BoolOptions::default())
+    }
+
+    /// Get a yes/no value using custom truthy and falsy words
+    pub fn get_bool_with(
+        &self,
+        column_header: &str,
+        options: &BoolOptions,
+    ) -> Result<bool, DataError> {
+        let value = self.parse_source(column_header)?;
+
+        let parsed = options.read(value);
+        self.source.record_parse(column_header, parsed.is_some());
+
+        parsed.ok_or_else(|| DataError::ParseError {
+            key: column_header.into(),
+            value: self
+                .source
+                .error_text(self.row_number, column_header, value),
+            position: self.source.column_position(column_header),
+        })
+    }
+}
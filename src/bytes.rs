@@ -0,0 +1,133 @@
+//! Loading a workbook from bytes in memory, like a file dropped into a browser page
+//!
+//! This is the way to load on `wasm32-unknown-unknown`, which has no filesystem.
+
+use crate::xlsx::XlsxPackage;
+use crate::{coords, names, tables, trace, LoadError, LoadOptions, WorkbookData};
+use calamine::{Ods, Reader, Xls, Xlsb, Xlsx};
+use std::io::{Cursor, Read, Seek};
+
+/// Stands in for a file name in errors about data loaded from bytes
+const BYTES_FILENAME: &str = "<bytes>";
+
+/// Signature of an OLE compound file, the container of legacy xls workbooks
+const OLE_SIGNATURE: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+
+/// Signature of a zip archive, the container of xlsx, xlsb and ods workbooks
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Xlsx,
+    Xlsb,
+    Xls,
+    Ods,
+}
+
+/// Tell the workbook format from the content, as there is no file extension to go by
+fn detect_format(bytes: &[u8]) -> Result<Format, LoadError> {
+    if bytes.starts_with(OLE_SIGNATURE) {
+        return Ok(Format::Xls);
+    }
+    if !bytes.starts_with(ZIP_SIGNATURE) {
+        return Err(calamine::Error::Msg("Cannot detect file format").into());
+    }
+
+    let parts = XlsxPackage::new(Cursor::new(bytes))?.part_names();
+    let has_part = |name: &str| parts.iter().any(|p| p == name);
+    Ok(if has_part("xl/workbook.bin") {
+        Format::Xlsb
+    } else if has_part("xl/workbook.xml") {
+        Format::Xlsx
+    } else if has_part("content.xml") {
+        Format::Ods
+    } else {
+        return Err(LoadError::InvalidPackage(
+            "no workbook part in zip archive".into(),
+        ));
+    })
+}
+
+impl WorkbookData {
+    /// Load from the bytes of an xlsx, xlsm, xlsb, xls or ods file, detecting the format from
+    /// the content
+    pub fn from_file_bytes(bytes: &[u8]) -> Result<Self, LoadError> {
+        Self::from_file_bytes_with_options(bytes, &LoadOptions::default())
+    }
+
+    /// Load from the bytes of a workbook file with options, as `from_path_with_options` does
+    ///
+    /// Delimited text is not detected; parse it into a `Range` and use `from_range` instead.
+    /// Errors name the file as `<bytes>`.
+    pub fn from_file_bytes_with_options(
+        bytes: &[u8],
+        options: &LoadOptions,
+    ) -> Result<Self, LoadError> {
+        options.check_cancelled()?;
+
+        let format = detect_format(bytes)?;
+        trace::debug!(format = ?format, len = bytes.len(), "loading from bytes");
+
+        let open_package =
+            || (format == Format::Xlsx).then(|| XlsxPackage::new(Cursor::new(bytes)));
+        let cursor = Cursor::new(bytes);
+        match format {
+            Format::Xlsx => {
+                let workbook = Xlsx::new(cursor).map_err(calamine::Error::from)?;
+                Self::from_bytes_workbook(workbook, &open_package, options)
+            }
+            Format::Xlsb => {
+                let workbook = Xlsb::new(cursor).map_err(calamine::Error::from)?;
+                Self::from_bytes_workbook(workbook, &open_package, options)
+            }
+            Format::Xls => {
+                let workbook = Xls::new(cursor).map_err(calamine::Error::from)?;
+                Self::from_bytes_workbook(workbook, &open_package, options)
+            }
+            Format::Ods => {
+                let workbook = Ods::new(cursor).map_err(calamine::Error::from)?;
+                Self::from_bytes_workbook(workbook, &open_package, options)
+            }
+        }
+    }
+
+    /// Resolve a defined name or table in `options` to a sheet and range, then load
+    fn from_bytes_workbook<W, R>(
+        mut workbook: W,
+        open_package: &impl Fn() -> Option<Result<XlsxPackage<R>, LoadError>>,
+        options: &LoadOptions,
+    ) -> Result<Self, LoadError>
+    where
+        W: Reader,
+        calamine::Error: From<W::Error>,
+        R: Read + Seek,
+    {
+        let mut options = options.clone();
+
+        if let Some(name) = options.defined_name.take() {
+            let (sheet_name, region) =
+                names::resolve_in(workbook.defined_names(), BYTES_FILENAME, &name)?;
+            options = options
+                .sheet_name(&sheet_name)
+                .range(&coords::format_range(region));
+        }
+        if let Some(table_name) = options.table.take() {
+            let tables = match open_package() {
+                Some(package) => package?.tables()?,
+                None => Vec::new(),
+            };
+            let table = tables::find_table_in(tables, BYTES_FILENAME, &table_name)?;
+            options = options
+                .sheet_name(&table.sheet_name)
+                .range(&coords::format_range(table.data_region()));
+        }
+
+        options.region()?;
+        Self::from_workbook(
+            &mut workbook,
+            open_package,
+            BYTES_FILENAME.to_owned(),
+            &options,
+        )
+    }
+}
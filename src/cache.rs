@@ -0,0 +1,569 @@
+//! Binary snapshots of loaded data, for caching on disk or passing between processes
+
+use crate::{
+    CellErrorType, Column, CompactData, DataType, LoadError, NumberText, Range, WorkbookData,
+};
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{Decode, Encode};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Leading bytes of every snapshot, so unrelated or stale files fail fast
+const MAGIC: [u8; 4] = *b"XLRC";
+
+/// Bumped whenever the encoded layout, or the meaning of what is encoded, changes
+const FORMAT_VERSION: u32 = 10;
+
+/// Largest snapshot decoded, so a corrupted length cannot claim unbounded memory
+const MAX_SNAPSHOT_BYTES: usize = 1 << 31;
+
+const KIND_WORKBOOK: u8 = 0;
+const KIND_COMPACT: u8 = 1;
+
+fn config() -> impl bincode::config::Config {
+    bincode::config::standard().with_limit::<MAX_SNAPSHOT_BYTES>()
+}
+
+impl From<DecodeError> for LoadError {
+    fn from(err: DecodeError) -> Self {
+        LoadError::InvalidCache(err.to_string())
+    }
+}
+
+fn encode_error(err: EncodeError) -> io::Error {
+    match err {
+        EncodeError::Io { inner, .. } => inner,
+        err => io::Error::other(err.to_string()),
+    }
+}
+
+/// Snapshot header: magic bytes, format version and what kind of data follows
+#[derive(Encode, Decode)]
+struct Header {
+    magic: [u8; 4],
+    version: u32,
+    kind: u8,
+}
+
+impl Header {
+    fn new(kind: u8) -> Self {
+        Self {
+            magic: MAGIC,
+            version: FORMAT_VERSION,
+            kind,
+        }
+    }
+
+    fn check(&self, kind: u8) -> Result<(), LoadError> {
+        if self.magic != MAGIC {
+            return Err(LoadError::InvalidCache(
+                "not an excelerator snapshot".into(),
+            ));
+        }
+        if self.version != FORMAT_VERSION {
+            return Err(LoadError::InvalidCache(format!(
+                "snapshot format {} is not supported (expected {})",
+                self.version, FORMAT_VERSION
+            )));
+        }
+        if self.kind != kind {
+            return Err(LoadError::InvalidCache(
+                "snapshot holds a different type".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn error_code(err: &CellErrorType) -> u8 {
+    match err {
+        CellErrorType::Div0 => 0,
+        CellErrorType::NA => 1,
+        CellErrorType::Name => 2,
+        CellErrorType::Null => 3,
+        CellErrorType::Num => 4,
+        CellErrorType::Ref => 5,
+        CellErrorType::Value => 6,
+        CellErrorType::GettingData => 7,
+    }
+}
+
+fn error_from_code(code: u8) -> Result<CellErrorType, DecodeError> {
+    Ok(match code {
+        0 => CellErrorType::Div0,
+        1 => CellErrorType::NA,
+        2 => CellErrorType::Name,
+        3 => CellErrorType::Null,
+        4 => CellErrorType::Num,
+        5 => CellErrorType::Ref,
+        6 => CellErrorType::Value,
+        7 => CellErrorType::GettingData,
+        _ => return Err(DecodeError::Other("unknown cell error code")),
+    })
+}
+
+fn encode_cell<E: Encoder>(cell: &DataType, e: &mut E) -> Result<(), EncodeError> {
+    match cell {
+        DataType::Empty => 0u8.encode(e),
+        DataType::Int(v) => {
+            1u8.encode(e)?;
+            v.encode(e)
+        }
+        DataType::Float(v) => {
+            2u8.encode(e)?;
+            v.encode(e)
+        }
+        DataType::String(v) => {
+            3u8.encode(e)?;
+            v.encode(e)
+        }
+        DataType::Bool(v) => {
+            4u8.encode(e)?;
+            v.encode(e)
+        }
+        DataType::DateTime(v) => {
+            5u8.encode(e)?;
+            v.encode(e)
+        }
+        DataType::Error(v) => {
+            6u8.encode(e)?;
+            error_code(v).encode(e)
+        }
+    }
+}
+
+fn decode_cell<D: Decoder<Context = ()>>(d: &mut D) -> Result<DataType, DecodeError> {
+    Ok(match u8::decode(d)? {
+        0 => DataType::Empty,
+        1 => DataType::Int(i64::decode(d)?),
+        2 => DataType::Float(f64::decode(d)?),
+        3 => DataType::String(String::decode(d)?),
+        4 => DataType::Bool(bool::decode(d)?),
+        5 => DataType::DateTime(f64::decode(d)?),
+        6 => DataType::Error(error_from_code(u8::decode(d)?)?),
+        _ => return Err(DecodeError::Other("unknown cell type")),
+    })
+}
+
+fn encode_number_text<E: Encoder>(policy: NumberText, e: &mut E) -> Result<(), EncodeError> {
+    match policy {
+        NumberText::Shortest => 0u8.encode(e),
+        NumberText::IntegerPreserving => 1u8.encode(e),
+        NumberText::Fixed(places) => {
+            2u8.encode(e)?;
+            places.encode(e)
+        }
+        NumberText::Formatted => 3u8.encode(e),
+    }
+}
+
+fn decode_number_text<D: Decoder<Context = ()>>(d: &mut D) -> Result<NumberText, DecodeError> {
+    Ok(match u8::decode(d)? {
+        0 => NumberText::Shortest,
+        1 => NumberText::IntegerPreserving,
+        2 => NumberText::Fixed(u32::decode(d)?),
+        3 => NumberText::Formatted,
+        _ => return Err(DecodeError::Other("unknown number text policy")),
+    })
+}
+
+/// Encodes a `WorkbookData` without exposing bincode traits on the public type
+struct Snapshot<'a>(&'a WorkbookData);
+
+impl<'a> Encode for Snapshot<'a> {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), EncodeError> {
+        let data = self.0;
+
+        data.sheet_name.encode(e)?;
+        data.transposed.encode(e)?;
+        data.fingerprint().encode(e)?;
+        encode_number_text(data.number_text, e)?;
+
+        for cells in [
+            &data.formulas,
+            &data.hyperlinks,
+            &data.comments,
+            &data.number_formats,
+        ] {
+            let mut cells: Vec<(&(u32, u32), &String)> = cells.iter().collect();
+            cells.sort_unstable_by_key(|&(&position, _)| position);
+            cells.encode(e)?;
+        }
+
+        (data.first_row, data.last_row, data.first_col, data.last_col).encode(e)?;
+
+        let mut header: Vec<(&str, u64)> = data
+            .header
+            .iter()
+            .map(|(name, &i)| (name.as_str(), i as u64))
+            .collect();
+        header.sort_unstable_by_key(|&(_, i)| i);
+        header.encode(e)?;
+
+        (data.columns.len() as u64).encode(e)?;
+        for column in &data.columns {
+            column.name.encode(e)?;
+            column.index.encode(e)?;
+            column.populated.encode(e)?;
+            column.redacted.encode(e)?;
+            column.money_scale.encode(e)?;
+            column.round_places.encode(e)?;
+        }
+
+        data.range.start().encode(e)?;
+        data.range.end().encode(e)?;
+        for cell in data.range.cells().map(|(_, _, cell)| cell) {
+            encode_cell(cell, e)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes what `Snapshot` encodes
+struct Decoded(WorkbookData);
+
+impl Decode<()> for Decoded {
+    fn decode<D: Decoder<Context = ()>>(d: &mut D) -> Result<Self, DecodeError> {
+        let sheet_name = Option::<String>::decode(d)?;
+        let transposed = bool::decode(d)?;
+        let fingerprint = Option::<String>::decode(d)?;
+        let number_text = decode_number_text(d)?;
+        let formulas = Vec::<((u32, u32), String)>::decode(d)?
+            .into_iter()
+            .collect();
+        let hyperlinks = Vec::<((u32, u32), String)>::decode(d)?
+            .into_iter()
+            .collect();
+        let comments = Vec::<((u32, u32), String)>::decode(d)?
+            .into_iter()
+            .collect();
+        let number_formats = Vec::<((u32, u32), String)>::decode(d)?
+            .into_iter()
+            .collect();
+        let (first_row, last_row, first_col, last_col) = <(u32, u32, u32, u32)>::decode(d)?;
+
+        let header_entries = Vec::<(String, u64)>::decode(d)?;
+
+        let column_count = u64::decode(d)?;
+        let mut columns = Vec::new();
+        for _ in 0..column_count {
+            columns.push(Column {
+                name: String::decode(d)?,
+                index: u32::decode(d)?,
+                populated: bool::decode(d)?,
+                redacted: bool::decode(d)?,
+                money_scale: Option::<u32>::decode(d)?,
+                round_places: Option::<u32>::decode(d)?,
+                counters: Default::default(),
+            });
+        }
+
+        let start = Option::<(u32, u32)>::decode(d)?;
+        let end = Option::<(u32, u32)>::decode(d)?;
+        let range = match (start, end) {
+            (Some(start), Some(end)) if start.0 <= end.0 && start.1 <= end.1 => {
+                let cells = (end.0 - start.0)
+                    .checked_add(1)
+                    .zip((end.1 - start.1).checked_add(1))
+                    .and_then(|(height, width)| height.checked_mul(width))
+                    .ok_or(DecodeError::Other("invalid range bounds"))?;
+                // Claim the cells before allocating them, so the limit rejects bounds a
+                // corrupted snapshot has no data for
+                d.claim_container_read::<DataType>(cells as usize)?;
+                d.unclaim_bytes_read(cells as usize * std::mem::size_of::<DataType>());
+                let mut range = Range::new(start, end);
+                for row in start.0..=end.0 {
+                    for col in start.1..=end.1 {
+                        range.set_value((row, col), decode_cell(d)?);
+                    }
+                }
+                range
+            }
+            (None, None) => Range::empty(),
+            _ => return Err(DecodeError::Other("invalid range bounds")),
+        };
+
+        let header_map: HashMap<String, usize> = header_entries
+            .into_iter()
+            .map(|(name, i)| (name, i as usize))
+            .collect();
+
+        if header_map.values().any(|&i| i >= columns.len()) {
+            return Err(DecodeError::Other("header refers to a missing column"));
+        }
+        if last_row == u32::MAX
+            || last_col == u32::MAX
+            || first_row > last_row + 1
+            || first_col > last_col + 1
+        {
+            return Err(DecodeError::Other("invalid row or column bounds"));
+        }
+
+        Ok(Decoded(WorkbookData {
+            header: header_map,
+            columns,
+            range,
+            sheet_name,
+            transposed,
+            fingerprint: OnceLock::from(fingerprint),
+            formulas,
+            hyperlinks,
+            comments,
+            number_formats,
+            progress: None,
+            cancel: None,
+            source: None,
+            number_text,
+            counters: Default::default(),
+            first_row,
+            last_row,
+            first_col,
+            last_col,
+        }))
+    }
+}
+
+/// Decode a snapshot header and then its contents, checking the header first so foreign or
+/// stale files are reported as such rather than as garbled data
+fn decode_slice<T: Decode<()>>(bytes: &[u8], kind: u8) -> Result<T, LoadError> {
+    let (header, read): (Header, _) = bincode::decode_from_slice(bytes, config())?;
+    header.check(kind)?;
+    let (value, _) = bincode::decode_from_slice(&bytes[read..], config())?;
+    Ok(value)
+}
+
+fn decode_file<T: Decode<()>>(path: &Path, kind: u8) -> Result<T, LoadError> {
+    let file = File::open(path).map_err(|err| LoadError::InvalidCache(err.to_string()))?;
+    let mut reader = BufReader::new(file);
+    let header: Header = bincode::decode_from_std_read(&mut reader, config())?;
+    header.check(kind)?;
+    Ok(bincode::decode_from_std_read(&mut reader, config())?)
+}
+
+fn encode_file<T: Encode>(path: &Path, kind: u8, value: T) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    bincode::encode_into_std_write((Header::new(kind), value), &mut file, config())
+        .map_err(encode_error)?;
+    file.flush()
+}
+
+impl WorkbookData {
+    /// Encode a snapshot of this data, including its header, column settings and cell values
+    ///
+    /// Import statistics are not included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::encode_to_vec((Header::new(KIND_WORKBOOK), Snapshot(self)), config())
+            .expect("encoding to memory does not fail")
+    }
+
+    /// Decode a snapshot made by `to_bytes` or `write_cache`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LoadError> {
+        decode_slice::<Decoded>(bytes, KIND_WORKBOOK).map(|decoded| decoded.0)
+    }
+
+    /// Write a snapshot to a file, to be loaded with `from_cache_path` instead of re-parsing
+    /// the source workbook
+    pub fn write_cache<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        encode_file(path.as_ref(), KIND_WORKBOOK, Snapshot(self))
+    }
+
+    pub fn from_cache_path<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        decode_file::<Decoded>(path.as_ref(), KIND_WORKBOOK).map(|decoded| decoded.0)
+    }
+}
+
+impl CompactData {
+    /// Encode a snapshot of this table
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::encode_to_vec((Header::new(KIND_COMPACT), self), config())
+            .expect("encoding to memory does not fail")
+    }
+
+    /// Decode a snapshot made by `to_bytes` or `write_cache`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LoadError> {
+        decode_slice(bytes, KIND_COMPACT).and_then(checked)
+    }
+
+    pub fn write_cache<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        encode_file(path.as_ref(), KIND_COMPACT, self)
+    }
+
+    pub fn from_cache_path<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        decode_file(path.as_ref(), KIND_COMPACT).and_then(checked)
+    }
+}
+
+fn checked(data: CompactData) -> Result<CompactData, LoadError> {
+    data.check()
+        .map_err(|err| LoadError::InvalidCache(err.into()))?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LoadOptions;
+
+    fn people() -> WorkbookData {
+        let mut range = Range::new((0, 0), (3, 1));
+        for (row, cells) in [["Name", "Age"], ["Ann", "30"], ["Bob", "41"], ["Cho", "25"]]
+            .iter()
+            .enumerate()
+        {
+            for (col, cell) in cells.iter().enumerate() {
+                range.set_value((row as u32, col as u32), DataType::String(cell.to_string()));
+            }
+        }
+        WorkbookData::from_range(range, &LoadOptions::new()).unwrap()
+    }
+
+    fn replace(bytes: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+        let at = bytes
+            .windows(from.len())
+            .position(|w| w == from)
+            .expect("pattern in snapshot");
+        [&bytes[..at], to, &bytes[at + from.len()..]].concat()
+    }
+
+    #[test]
+    fn snapshots_round_trip() {
+        let data = people();
+
+        let decoded = WorkbookData::from_bytes(&data.to_bytes()).unwrap();
+        assert_eq!(decoded.headers().collect::<Vec<_>>(), ["Name", "Age"]);
+        assert_eq!(decoded.get(2, "Name").as_deref(), Some("Bob"));
+
+        let compact = CompactData::from_bytes(&data.compact().to_bytes()).unwrap();
+        assert_eq!(compact.len(), 3);
+        assert_eq!(compact.get(3, "Name").as_deref(), Some("Cho"));
+    }
+
+    #[test]
+    fn keeps_column_settings_through_a_cache_file() {
+        let mut range = Range::new((0, 0), (1, 1));
+        range.set_value((0, 0), DataType::String("Price".into()));
+        range.set_value((0, 1), DataType::String("Amount".into()));
+        range.set_value((1, 0), DataType::Float(2.345));
+        range.set_value((1, 1), DataType::Float(1.5));
+        let options = LoadOptions::new().number_text(NumberText::Fixed(3));
+        let mut data = WorkbookData::from_range(range, &options).unwrap();
+        data.set_rounding("Price", 2);
+        data.set_money_scale("Amount", 2);
+
+        let path = crate::testing::TempPath::new("cache");
+        data.write_cache(&path).unwrap();
+        let cached = WorkbookData::from_cache_path(&path).unwrap();
+
+        let row = cached.rows().next().unwrap();
+        assert_eq!(row.get("Price").unwrap(), "2.350");
+        assert_eq!(row.get_money("Amount").unwrap(), 150);
+        assert!(matches!(
+            WorkbookData::from_cache_path(path.with_extension("missing")),
+            Err(LoadError::InvalidCache(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_foreign_and_truncated_bytes() {
+        assert!(matches!(
+            WorkbookData::from_bytes(b"not a snapshot"),
+            Err(LoadError::InvalidCache(_))
+        ));
+
+        let bytes = people().to_bytes();
+        assert!(matches!(
+            WorkbookData::from_bytes(&bytes[..bytes.len() - 3]),
+            Err(LoadError::InvalidCache(_))
+        ));
+        assert!(matches!(
+            CompactData::from_bytes(&bytes),
+            Err(LoadError::InvalidCache(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_range_bounds_without_cells() {
+        let bytes = people().to_bytes();
+        // Range start (0, 0) and end (3, 1), then the first cell, a string
+        let bounds = [1, 0, 0, 1, 3, 1, 3];
+        let huge = [
+            1, 0, 0, 1, 252, 255, 255, 255, 255, 252, 255, 255, 255, 255, 3,
+        ];
+        let wide = [1, 0, 0, 1, 3, 252, 0, 0, 0, 1, 3];
+
+        for patched in [&huge[..], &wide[..]] {
+            assert!(matches!(
+                WorkbookData::from_bytes(&replace(&bytes, &bounds, patched)),
+                Err(LoadError::InvalidCache(_))
+            ));
+        }
+    }
+
+    /// The layout `CompactData` derives, to encode snapshots that break its invariants
+    #[derive(Encode)]
+    struct RawCompact {
+        header: Vec<(String, u64)>,
+        columns: Vec<RawColumn>,
+        first_row: u32,
+        last_row: u32,
+    }
+
+    #[derive(Encode)]
+    struct RawColumn {
+        name: String,
+        variant: u32,
+        arena: String,
+        ends: Vec<u32>,
+        validity: Vec<u64>,
+        text: Option<String>,
+    }
+
+    fn raw_compact(arena: &str, ends: Vec<u32>, first_row: u32, last_row: u32) -> Vec<u8> {
+        // Variant 4 of `ColumnValues` is `Str`
+        let raw = RawCompact {
+            header: vec![("Name".into(), 0)],
+            columns: vec![RawColumn {
+                name: "Name".into(),
+                variant: 4,
+                arena: arena.into(),
+                ends,
+                validity: vec![u64::MAX],
+                text: None,
+            }],
+            first_row,
+            last_row,
+        };
+        bincode::encode_to_vec((Header::new(KIND_COMPACT), raw), config()).unwrap()
+    }
+
+    #[test]
+    fn rejects_compact_data_breaking_invariants() {
+        let valid = raw_compact("AnnBob", vec![3, 6], 1, 2);
+        assert_eq!(
+            CompactData::from_bytes(&valid)
+                .unwrap()
+                .get(2, "Name")
+                .as_deref(),
+            Some("Bob")
+        );
+
+        for bytes in [
+            raw_compact("AnnBob", vec![3, 60], 1, 2),
+            raw_compact("AnnBob", vec![4, 3], 1, 2),
+            raw_compact("Zoë", vec![3, 4], 1, 2),
+            raw_compact("AnnBob", vec![3, 6], 3, 1),
+            raw_compact("AnnBob", vec![3, 6], 1, 5),
+        ] {
+            assert!(matches!(
+                CompactData::from_bytes(&bytes),
+                Err(LoadError::InvalidCache(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Typed cell values, including the error values of broken formulas like `#DIV/0!`
+
+use crate::{CellErrorType, DataError, DataType, RowData, WorkbookData};
+use std::fmt;
+
+/// An error value a formula evaluated to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellErrorKind {
+    /// `#DIV/0!`
+    DivideByZero,
+    /// `#N/A`
+    NotAvailable,
+    /// `#NAME?`
+    Name,
+    /// `#NULL!`
+    Null,
+    /// `#NUM!`
+    Num,
+    /// `#REF!`
+    Ref,
+    /// `#VALUE!`
+    Value,
+    /// `#DATA!`, a value still being fetched when the file was saved
+    GettingData,
+}
+
+impl CellErrorKind {
+    /// The text a spreadsheet shows for this error, like `"#N/A"`
+    pub fn code(self) -> &'static str {
+        match self {
+            CellErrorKind::DivideByZero => "#DIV/0!",
+            CellErrorKind::NotAvailable => "#N/A",
+            CellErrorKind::Name => "#NAME?",
+            CellErrorKind::Null => "#NULL!",
+            CellErrorKind::Num => "#NUM!",
+            CellErrorKind::Ref => "#REF!",
+            CellErrorKind::Value => "#VALUE!",
+            CellErrorKind::GettingData => "#DATA!",
+        }
+    }
+
+    /// Recognize the text of an error, as found in delimited files exported from a spreadsheet
+    pub fn from_code(code: &str) -> Option<Self> {
+        Some(match code {
+            "#DIV/0!" => CellErrorKind::DivideByZero,
+            "#N/A" => CellErrorKind::NotAvailable,
+            "#NAME?" => CellErrorKind::Name,
+            "#NULL!" => CellErrorKind::Null,
+            "#NUM!" => CellErrorKind::Num,
+            "#REF!" => CellErrorKind::Ref,
+            "#VALUE!" => CellErrorKind::Value,
+            "#DATA!" | "#GETTING_DATA" => CellErrorKind::GettingData,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for CellErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl From<&CellErrorType> for CellErrorKind {
+    fn from(err: &CellErrorType) -> Self {
+        match err {
+            CellErrorType::Div0 => CellErrorKind::DivideByZero,
+            CellErrorType::NA => CellErrorKind::NotAvailable,
+            CellErrorType::Name => CellErrorKind::Name,
+            CellErrorType::Null => CellErrorKind::Null,
+            CellErrorType::Num => CellErrorKind::Num,
+            CellErrorType::Ref => CellErrorKind::Ref,
+            CellErrorType::Value => CellErrorKind::Value,
+            CellErrorType::GettingData => CellErrorKind::GettingData,
+        }
+    }
+}
+
+impl From<CellErrorKind> for CellErrorType {
+    fn from(kind: CellErrorKind) -> Self {
+        match kind {
+            CellErrorKind::DivideByZero => CellErrorType::Div0,
+            CellErrorKind::NotAvailable => CellErrorType::NA,
+            CellErrorKind::Name => CellErrorType::Name,
+            CellErrorKind::Null => CellErrorType::Null,
+            CellErrorKind::Num => CellErrorType::Num,
+            CellErrorKind::Ref => CellErrorType::Ref,
+            CellErrorKind::Value => CellErrorType::Value,
+            CellErrorKind::GettingData => CellErrorType::GettingData,
+        }
+    }
+}
+
+/// A cell's value with its type, borrowing text from the data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellValue<'a> {
+    Empty,
+    Text(&'a str),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// An Excel serial date number
+    DateTime(f64),
+    Error(CellErrorKind),
+}
+
+impl<'a> CellValue<'a> {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, CellValue::Empty | CellValue::Text(""))
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, CellValue::Error(_))
+    }
+}
+
+impl<'a> From<&'a DataType> for CellValue<'a> {
+    fn from(value: &'a DataType) -> Self {
+        match value {
+            DataType::Empty => CellValue::Empty,
+            DataType::String(s) => CellValue::Text(s),
+            DataType::Int(i) => CellValue::Int(*i),
+            DataType::Float(f) => CellValue::Float(*f),
+            DataType::Bool(b) => CellValue::Bool(*b),
+            DataType::DateTime(d) => CellValue::DateTime(*d),
+            DataType::Error(e) => CellValue::Error(e.into()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for CellValue<'a> {
+    /// The cell's own text, with numbers in their shortest form and errors as a spreadsheet
+    /// shows them
+    ///
+    /// This is what `get` returns only under the default `NumberText::Shortest` policy; use
+    /// `get` for text that follows `LoadOptions::number_text`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Empty => Ok(()),
+            CellValue::Text(s) => f.write_str(s),
+            CellValue::Int(i) => write!(f, "{}", i),
+            CellValue::Float(v) | CellValue::DateTime(v) => write!(f, "{}", v),
+            CellValue::Bool(b) => write!(f, "{}", b),
+            CellValue::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl WorkbookData {
+    /// Get a cell with its type, with the column's rounding (if any) applied
+    pub fn get_value(&self, row_number: u32, column_header: &str) -> Option<CellValue<'_>> {
+        let column = &self.columns[self.column_id(column_header)?.0];
+        let value = self.get_cell(row_number, column_header)?;
+
+        Some(match column.round(value) {
+            Some(DataType::Float(f)) => CellValue::Float(f),
+            _ => value.into(),
+        })
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get the value of a cell with its type, so error cells can be told apart from text
+    pub fn get_value(&self, column_header: &str) -> Result<CellValue<'a>, DataError> {
+        self.source
+            .get_value(self.row_number, column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))
+    }
+
+    /// Get a cell to be parsed, failing on missing columns and on error values
+    ///
+    /// Error values count as failed parses in the import stats.
+    pub(crate) fn parse_source(&self, column_header: &str) -> Result<&'a DataType, DataError> {
+        let value = self
+            .source
+            .get_cell(self.row_number, column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        match value {
+            DataType::Error(err) => {
+                self.source.record_parse(column_header, false);
+                Err(DataError::CellError {
+                    key: column_header.into(),
+                    kind: err.into(),
+                    position: self.source.column_position(column_header),
+                })
+            }
+            value => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_range, LoadOptions, NumberText, Range};
+
+    fn values() -> Range<DataType> {
+        let mut range = Range::new((0, 0), (1, 2));
+        range.set_value((0, 0), DataType::String("Qty".into()));
+        range.set_value((0, 1), DataType::String("Price".into()));
+        range.set_value((0, 2), DataType::String("Check".into()));
+        range.set_value((1, 0), DataType::Int(3));
+        range.set_value((1, 1), DataType::Float(2.345));
+        range.set_value((1, 2), DataType::Error(CellErrorType::Div0));
+        range
+    }
+
+    #[test]
+    fn displays_the_cells_own_text() {
+        let options = LoadOptions::new().round("Price", 2);
+        let data = from_range(values(), &options).unwrap();
+
+        let qty = data.get_value(1, "Qty").unwrap();
+        assert_eq!(qty, CellValue::Int(3));
+        assert_eq!(qty.to_string(), "3");
+        assert_eq!(data.get_value(1, "Price").unwrap().to_string(), "2.35");
+
+        let check = data.get_value(1, "Check").unwrap();
+        assert_eq!(check, CellValue::Error(CellErrorKind::DivideByZero));
+        assert_eq!(check.to_string(), "#DIV/0!");
+    }
+
+    #[test]
+    fn ignores_the_number_text_policy() {
+        let options = LoadOptions::new().number_text(NumberText::Fixed(2));
+        let data = from_range(values(), &options).unwrap();
+
+        assert_eq!(data.get(1, "Qty").as_deref(), Some("3.00"));
+        assert_eq!(data.get_value(1, "Qty").unwrap().to_string(), "3");
+    }
+}
@@ -0,0 +1,127 @@
+//! Cell comments (notes), the review annotations people leave on workbooks
+
+use crate::coords;
+use crate::xlsx::{self, XlsxPackage};
+use crate::{LoadError, RowData, WorkbookData};
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+/// Relationship type of a sheet's comments part
+const COMMENTS_REL: &str = "/comments";
+
+/// A comment attached to a cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// Absolute (row, column)
+    pub cell: (u32, u32),
+    pub author: Option<String>,
+    pub text: String,
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// List the comments of a sheet in document order
+    pub fn comments(&mut self, sheet_name: &str) -> Result<Vec<Comment>, LoadError> {
+        let path = match self.sheet_path(sheet_name)? {
+            Some(path) => path,
+            None => return Ok(Vec::new()),
+        };
+
+        let part = self
+            .relationships(&path)?
+            .into_iter()
+            .find(|r| r.kind.ends_with(COMMENTS_REL));
+        let xml = match part {
+            Some(part) => self.read_part(&part.target)?.unwrap_or_default(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = quick_xml::Reader::from_reader(xml.as_slice());
+        let mut buf = Vec::new();
+        let mut authors = Vec::new();
+        let mut comments = Vec::new();
+
+        // Text of the author or comment being read, and the comment's cell and author id
+        let mut text: Option<String> = None;
+        let mut comment: Option<((u32, u32), Option<usize>)> = None;
+
+        loop {
+            match reader.read_event(&mut buf)? {
+                Event::Start(ref e) if e.local_name() == b"author" => {
+                    text = Some(String::new());
+                }
+                Event::Start(ref e) if e.local_name() == b"comment" => {
+                    let cell = xlsx::attr(e, b"ref")
+                        .as_deref()
+                        .and_then(coords::parse_cell);
+                    let author = xlsx::attr(e, b"authorId").and_then(|id| id.parse().ok());
+                    comment = cell.map(|cell| (cell, author));
+                    text = Some(String::new());
+                }
+                Event::Text(ref t) => {
+                    if let Some(text) = &mut text {
+                        text.push_str(&String::from_utf8_lossy(&t.unescaped()?));
+                    }
+                }
+                Event::End(ref e) if e.local_name() == b"author" => {
+                    authors.push(text.take().unwrap_or_default());
+                }
+                Event::End(ref e) if e.local_name() == b"comment" => {
+                    if let (Some((cell, author)), Some(text)) = (comment.take(), text.take()) {
+                        comments.push(Comment {
+                            cell,
+                            author: author.and_then(|i: usize| authors.get(i).cloned()),
+                            text: text.trim().to_owned(),
+                        });
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(comments)
+    }
+}
+
+/// Read the comments of a sheet by absolute (row, column), swapping the two when `transposed`
+pub(crate) fn read_comments<R: Read + Seek>(
+    package: &mut XlsxPackage<R>,
+    sheet_name: &str,
+    transposed: bool,
+) -> Result<HashMap<(u32, u32), String>, LoadError> {
+    Ok(package
+        .comments(sheet_name)?
+        .into_iter()
+        .map(|c| {
+            let (row, col) = c.cell;
+            let position = if transposed { (col, row) } else { (row, col) };
+            (position, c.text)
+        })
+        .collect())
+}
+
+impl WorkbookData {
+    /// Get the comment on a cell
+    ///
+    /// Always `None` unless the data was loaded with `LoadOptions::comments` (xlsx only).
+    pub fn get_comment(&self, row_number: u32, column_header: &str) -> Option<&str> {
+        let column = &self.columns[self.column_id(column_header)?.0];
+        self.comments
+            .get(&(row_number, column.index))
+            .map(String::as_str)
+    }
+
+    /// Check if any comments were loaded
+    pub fn has_comments(&self) -> bool {
+        !self.comments.is_empty()
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get the comment on a cell of this row
+    pub fn get_comment(&self, column_header: &str) -> Option<&'a str> {
+        self.source.get_comment(self.row_number, column_header)
+    }
+}
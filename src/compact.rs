@@ -0,0 +1,426 @@
+use crate::stats::Timer;
+use crate::{cell_is_empty, round_to, DataType, NumberText, WorkbookData};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Bit-packed presence flags, one per row
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+struct Validity(Vec<u64>);
+
+impl Validity {
+    fn with_len(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn set(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.0.get(i / 64).is_some_and(|w| w & (1 << (i % 64)) != 0)
+    }
+}
+
+/// Values of a single compacted column, stored as the narrowest type that fits every cell
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub enum ColumnValues {
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+    DateTime(Vec<f64>),
+    Bool(Vec<bool>),
+    /// All strings concatenated in one buffer, with the end offset of each value
+    Str {
+        arena: String,
+        ends: Vec<u32>,
+    },
+}
+
+impl ColumnValues {
+    /// Store every cell as text, present or not
+    fn strings<'a, I>(
+        cells: I,
+        len: usize,
+        text: impl Fn(usize, &'a DataType) -> Cow<'a, str>,
+    ) -> Self
+    where
+        I: Iterator<Item = Option<&'a DataType>>,
+    {
+        let mut arena = String::new();
+        let mut ends = Vec::with_capacity(len);
+        for (i, cell) in cells.enumerate() {
+            if let Some(value) = cell {
+                arena.push_str(&text(i, value));
+            }
+            ends.push(arena.len() as u32);
+        }
+        arena.shrink_to_fit();
+        ColumnValues::Str { arena, ends }
+    }
+
+    /// Get a string value, if these are strings
+    fn str_at(&self, index: usize) -> Option<&str> {
+        match self {
+            ColumnValues::Str { arena, ends } => {
+                let start = if index == 0 { 0 } else { ends[index - 1] };
+                Some(&arena[start as usize..ends[index] as usize])
+            }
+            _ => None,
+        }
+    }
+
+    /// Check that every string value lies within the arena on character boundaries
+    #[cfg(feature = "cache")]
+    fn check(&self) -> Result<(), &'static str> {
+        if let ColumnValues::Str { arena, ends } = self {
+            let mut start = 0;
+            for &end in ends {
+                let end = end as usize;
+                if end < start || end > arena.len() || !arena.is_char_boundary(end) {
+                    return Err("string offsets out of bounds");
+                }
+                start = end;
+            }
+            if start != arena.len() {
+                return Err("string offsets out of bounds");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A column of a `CompactData` table
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct CompactColumn {
+    name: String,
+    values: ColumnValues,
+    validity: Validity,
+    /// Numbers as text under the source's `NumberText` policy, unless it is the default
+    text: Option<ColumnValues>,
+}
+
+impl CompactColumn {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn values(&self) -> &ColumnValues {
+        &self.values
+    }
+
+    /// Check whether the value at a row index (relative to the first data row) is present
+    pub fn is_present(&self, index: usize) -> bool {
+        self.validity.get(index)
+    }
+
+    pub fn get_i64(&self, index: usize) -> Option<i64> {
+        if !self.is_present(index) {
+            return None;
+        }
+
+        match &self.values {
+            ColumnValues::Int(v) => v.get(index).copied(),
+            _ => None,
+        }
+    }
+
+    /// Get a numeric value, converting integers and date serials to `f64`
+    pub fn get_f64(&self, index: usize) -> Option<f64> {
+        if !self.is_present(index) {
+            return None;
+        }
+
+        match &self.values {
+            ColumnValues::Int(v) => v.get(index).map(|&x| x as f64),
+            ColumnValues::Float(v) | ColumnValues::DateTime(v) => v.get(index).copied(),
+            _ => None,
+        }
+    }
+
+    pub fn get_bool(&self, index: usize) -> Option<bool> {
+        if !self.is_present(index) {
+            return None;
+        }
+
+        match &self.values {
+            ColumnValues::Bool(v) => v.get(index).copied(),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a string, formatted the same way as `WorkbookData::get`, including
+    /// its `NumberText` policy
+    pub fn get_str(&self, index: usize) -> Option<Cow<'_, str>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        if !self.is_present(index) {
+            return Some(Cow::Borrowed(""));
+        }
+
+        if let Some(text) = self.text.as_ref().and_then(|t| t.str_at(index)) {
+            return Some(Cow::Borrowed(text));
+        }
+        Some(match &self.values {
+            ColumnValues::Int(v) => Cow::Owned(v[index].to_string()),
+            ColumnValues::Float(v) | ColumnValues::DateTime(v) => Cow::Owned(v[index].to_string()),
+            ColumnValues::Bool(v) => Cow::Owned(v[index].to_string()),
+            values => Cow::Borrowed(values.str_at(index).unwrap_or_default()),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.values {
+            ColumnValues::Int(v) => v.len(),
+            ColumnValues::Float(v) | ColumnValues::DateTime(v) => v.len(),
+            ColumnValues::Bool(v) => v.len(),
+            ColumnValues::Str { ends, .. } => ends.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compact a column's cells, writing any text with `text` (the cell's index and value)
+    ///
+    /// Numeric columns also keep their text when `keep_text` is set, for a number text policy
+    /// other than the default.
+    fn from_cells<'a, I, F>(name: String, cells: I, len: usize, text: F, keep_text: bool) -> Self
+    where
+        I: Iterator<Item = Option<&'a DataType>> + Clone,
+        F: Fn(usize, &'a DataType) -> Cow<'a, str>,
+    {
+        let mut validity = Validity::with_len(len);
+        for (i, cell) in cells.clone().enumerate() {
+            if cell.is_some_and(|v| !cell_is_empty(v)) {
+                validity.set(i);
+            }
+        }
+
+        let present = || cells.clone().flatten().filter(|v| !cell_is_empty(v));
+
+        let numeric = present().all(|v| {
+            matches!(
+                v,
+                DataType::Int(_) | DataType::Float(_) | DataType::DateTime(_)
+            )
+        });
+        let text_values = match numeric && keep_text {
+            true => Some(ColumnValues::strings(cells.clone(), len, &text)),
+            false => None,
+        };
+
+        let values = if present().all(|v| v.is_int()) {
+            ColumnValues::Int(
+                cells
+                    .map(|c| c.and_then(|v| v.get_int()).unwrap_or(0))
+                    .collect(),
+            )
+        } else if present().all(|v| matches!(v, DataType::DateTime(_))) {
+            ColumnValues::DateTime(
+                cells
+                    .map(|c| match c {
+                        Some(DataType::DateTime(x)) => *x,
+                        _ => 0.,
+                    })
+                    .collect(),
+            )
+        } else if present().all(|v| v.is_int() || v.is_float()) {
+            ColumnValues::Float(
+                cells
+                    .map(|c| match c {
+                        Some(DataType::Int(x)) => *x as f64,
+                        Some(DataType::Float(x)) => *x,
+                        _ => 0.,
+                    })
+                    .collect(),
+            )
+        } else if present().all(|v| v.is_bool()) {
+            ColumnValues::Bool(
+                cells
+                    .map(|c| c.and_then(|v| v.get_bool()).unwrap_or(false))
+                    .collect(),
+            )
+        } else {
+            ColumnValues::strings(cells, len, text)
+        };
+
+        Self {
+            name,
+            values,
+            validity,
+            text: text_values,
+        }
+    }
+}
+
+/// A read-only, column-oriented copy of a `WorkbookData` with typed column storage
+///
+/// Row numbers match the source data, so `get(row_number, header)` works the same on both.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct CompactData {
+    header: HashMap<String, usize>,
+    columns: Vec<CompactColumn>,
+    pub first_row: u32,
+    pub last_row: u32,
+}
+
+impl CompactData {
+    /// Check the invariants accessors rely on, so a corrupted snapshot is rejected instead of
+    /// panicking when read
+    #[cfg(feature = "cache")]
+    pub(crate) fn check(&self) -> Result<(), &'static str> {
+        if self.last_row == u32::MAX || self.first_row > self.last_row + 1 {
+            return Err("invalid row bounds");
+        }
+        if self.header.values().any(|&i| i >= self.columns.len()) {
+            return Err("header refers to a missing column");
+        }
+        for column in &self.columns {
+            if column.len() != self.len() {
+                return Err("column length does not match the rows");
+            }
+            column.values.check()?;
+            if let Some(text) = &column.text {
+                let matches = match text {
+                    ColumnValues::Str { ends, .. } => ends.len() == column.len(),
+                    _ => false,
+                };
+                if !matches {
+                    return Err("column text does not match its values");
+                }
+                text.check()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn headers(&self) -> impl Iterator<Item = &str> + '_ {
+        self.columns.iter().map(|c| c.name())
+    }
+
+    pub fn columns(&self) -> &[CompactColumn] {
+        &self.columns
+    }
+
+    pub fn column(&self, column_header: &str) -> Option<&CompactColumn> {
+        self.columns.get(*self.header.get(column_header)?)
+    }
+
+    /// Number of data rows
+    pub fn len(&self) -> usize {
+        (self.last_row + 1 - self.first_row) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn index(&self, row_number: u32) -> Option<usize> {
+        if row_number < self.first_row || row_number > self.last_row {
+            return None;
+        }
+
+        Some((row_number - self.first_row) as usize)
+    }
+
+    pub fn get(&self, row_number: u32, column_header: &str) -> Option<Cow<'_, str>> {
+        self.column(column_header)?.get_str(self.index(row_number)?)
+    }
+
+    pub fn get_i64(&self, row_number: u32, column_header: &str) -> Option<i64> {
+        self.column(column_header)?.get_i64(self.index(row_number)?)
+    }
+
+    pub fn get_f64(&self, row_number: u32, column_header: &str) -> Option<f64> {
+        self.column(column_header)?.get_f64(self.index(row_number)?)
+    }
+
+    pub fn get_bool(&self, row_number: u32, column_header: &str) -> Option<bool> {
+        self.column(column_header)?
+            .get_bool(self.index(row_number)?)
+    }
+}
+
+impl WorkbookData {
+    /// Convert to column-oriented typed storage, inferring each column's type from its values
+    pub fn compact(&self) -> CompactData {
+        let start = Timer::start();
+        let len = (self.last_row + 1).saturating_sub(self.first_row) as usize;
+
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| {
+                let column_start = Timer::start();
+                let cells =
+                    (self.first_row..=self.last_row).map(|r| self.range.get_value((r, c.index)));
+                let text = |i: usize, value| self.cell_text(self.first_row + i as u32, c, value);
+                let keep_text = self.number_text != NumberText::Shortest;
+                let mut compacted =
+                    CompactColumn::from_cells(c.name.clone(), cells, len, text, keep_text);
+                if let (Some(places), ColumnValues::Float(values)) =
+                    (c.round_places, &mut compacted.values)
+                {
+                    values.iter_mut().for_each(|v| *v = round_to(*v, places));
+                }
+                c.counters.record_pass(len as u64, column_start);
+                compacted
+            })
+            .collect();
+
+        self.counters.record_pass(self.data_cells(), start);
+
+        CompactData {
+            header: self.header.clone(),
+            columns,
+            first_row: self.first_row,
+            last_row: self.last_row,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_range, LoadOptions, Range};
+
+    fn prices() -> Range<DataType> {
+        let mut range = Range::new((0, 0), (2, 1));
+        range.set_value((0, 0), DataType::String("Price".into()));
+        range.set_value((0, 1), DataType::String("Note".into()));
+        range.set_value((1, 0), DataType::Float(2.5));
+        range.set_value((1, 1), DataType::Float(0.125));
+        range.set_value((2, 0), DataType::Int(3));
+        range.set_value((2, 1), DataType::String("n/a".into()));
+        range
+    }
+
+    #[test]
+    fn reads_text_like_the_source() {
+        let options = LoadOptions::new().number_text(NumberText::Fixed(2));
+        let data = from_range(prices(), &options).unwrap();
+        let compact = data.compact();
+
+        for row in 1..=2 {
+            for header in ["Price", "Note"] {
+                assert_eq!(compact.get(row, header), data.get(row, header));
+            }
+        }
+        assert_eq!(compact.get(1, "Note").unwrap(), "0.13");
+        assert_eq!(compact.get_f64(1, "Price"), Some(2.5));
+    }
+
+    #[test]
+    fn keeps_no_text_for_the_default_policy() {
+        let compact = from_range(prices(), &LoadOptions::new()).unwrap().compact();
+        assert!(compact.column("Price").unwrap().text.is_none());
+        assert_eq!(compact.get(1, "Price").unwrap(), "2.5");
+        assert_eq!(compact.get(2, "Price").unwrap(), "3");
+    }
+}
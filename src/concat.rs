@@ -0,0 +1,208 @@
+//! Iterating rows across several files with differing column sets
+
+use crate::{ColumnId, LoadError, LoadOptions, WorkbookData};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// How to choose the output columns when files have different headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAlign {
+    /// Every column found in any file, in first-seen order; missing columns are `None`
+    UnionWithNulls,
+    /// Only the columns found in every file
+    Intersection,
+    /// The columns of the first file; extra columns in later files are dropped
+    First,
+}
+
+/// A row from one of several concatenated files, aligned to the combined columns
+#[derive(Debug, Clone)]
+pub struct ConcatRow {
+    columns: Arc<[String]>,
+    values: Vec<Option<String>>,
+    source: usize,
+    number: u32,
+}
+
+impl ConcatRow {
+    /// Get the index of the file this row came from
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    /// Get the row number this data came from in its source file
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// Get a value, or `None` if the column is not in this row's file (or not a known column)
+    pub fn get(&self, column_header: &str) -> Option<&str> {
+        let i = self.columns.iter().position(|c| c == column_header)?;
+        self.values[i].as_deref()
+    }
+
+    pub fn headers(&self) -> impl Iterator<Item = &str> + '_ {
+        self.columns.iter().map(|c| c.as_str())
+    }
+
+    /// Get the values in column order
+    pub fn values(&self) -> &[Option<String>] {
+        &self.values
+    }
+}
+
+/// Iterator over the rows of several files, loading one file at a time
+pub struct ConcatRows {
+    paths: Vec<PathBuf>,
+    options: LoadOptions,
+    columns: Arc<[String]>,
+    /// Other headers that map onto each column, from header reconciliation
+    aliases: Vec<Vec<String>>,
+    next_file: usize,
+    current: Option<CurrentFile>,
+}
+
+struct CurrentFile {
+    data: WorkbookData,
+    ids: Vec<Option<ColumnId>>,
+    row: u32,
+}
+
+impl ConcatRows {
+    /// Get the combined columns every row is aligned to
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    fn open_next(&mut self) -> Option<Result<(), LoadError>> {
+        let path = self.paths.get(self.next_file)?;
+        self.next_file += 1;
+
+        Some(
+            WorkbookData::from_path_with_options(path, &self.options).map(|data| {
+                let ids = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        data.column_id(c).or_else(|| {
+                            let aliases = self.aliases.get(i)?;
+                            aliases.iter().find_map(|a| data.column_id(a))
+                        })
+                    })
+                    .collect();
+                let row = data.first_row;
+                self.current = Some(CurrentFile { data, ids, row });
+            }),
+        )
+    }
+}
+
+impl Iterator for ConcatRows {
+    type Item = Result<ConcatRow, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(current) = &mut self.current {
+                if current.row <= current.data.last_row {
+                    let number = current.row;
+                    current.row += 1;
+
+                    let values = current
+                        .ids
+                        .iter()
+                        .map(|id| {
+                            id.map(|id| {
+                                current
+                                    .data
+                                    .get_by_id(number, id)
+                                    .unwrap_or_default()
+                                    .into_owned()
+                            })
+                        })
+                        .collect();
+
+                    return Some(Ok(ConcatRow {
+                        columns: Arc::clone(&self.columns),
+                        values,
+                        source: self.next_file - 1,
+                        number,
+                    }));
+                }
+                self.current = None;
+            }
+
+            if let Err(err) = self.open_next()? {
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+/// Iterate the rows of several files in order, aligning columns by header
+///
+/// Only one file is held in memory at a time. `UnionWithNulls` and `Intersection` need every
+/// file's headers up front, so each file is read twice.
+pub fn concat_streaming<P: AsRef<Path>>(
+    paths: &[P],
+    align: ColumnAlign,
+) -> Result<ConcatRows, LoadError> {
+    concat_streaming_with_options(paths, align, &LoadOptions::default())
+}
+
+pub fn concat_streaming_with_options<P: AsRef<Path>>(
+    paths: &[P],
+    align: ColumnAlign,
+    options: &LoadOptions,
+) -> Result<ConcatRows, LoadError> {
+    let paths: Vec<PathBuf> = paths.iter().map(|p| p.as_ref().to_owned()).collect();
+
+    let header_files = match align {
+        ColumnAlign::First => &paths[..paths.len().min(1)],
+        ColumnAlign::UnionWithNulls | ColumnAlign::Intersection => &paths[..],
+    };
+
+    let mut columns: Vec<String> = Vec::new();
+    for (i, path) in header_files.iter().enumerate() {
+        let data = WorkbookData::from_path_with_options(path, options)?;
+
+        if i == 0 {
+            columns = data.headers().map(String::from).collect();
+        } else if align == ColumnAlign::Intersection {
+            columns.retain(|c| data.column_id(c).is_some());
+        } else {
+            for header in data.headers() {
+                if !columns.iter().any(|c| c == header) {
+                    columns.push(header.to_owned());
+                }
+            }
+        }
+    }
+
+    Ok(ConcatRows {
+        paths,
+        options: options.clone(),
+        columns: columns.into(),
+        aliases: Vec::new(),
+        next_file: 0,
+        current: None,
+    })
+}
+
+impl ConcatRows {
+    pub(crate) fn with_aliases(
+        paths: Vec<PathBuf>,
+        options: LoadOptions,
+        columns: Vec<String>,
+        aliases: Vec<Vec<String>>,
+    ) -> Self {
+        Self {
+            paths,
+            options,
+            columns: columns.into(),
+            aliases,
+            next_file: 0,
+            current: None,
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Conversion between A1-style cell references and zero-based `(row, col)` positions
+//!
+//! Positions are absolute within the sheet, as used by `RowData::number` and
+//! `LoadOptions::range`.
+
+use std::convert::TryFrom;
+
+/// Absolute zero-based `(start, end)` positions of a rectangular cell range, inclusive
+pub type Span = ((u32, u32), (u32, u32));
+
+/// Last zero-based row of an Excel sheet, which whole-column ranges like `"A:F"` extend to
+pub const MAX_ROW: u32 = 1_048_575;
+
+/// Parse a column name like `"AB"` into a zero-based column number
+pub fn parse_column(name: &str) -> Option<u32> {
+    if name.is_empty() {
+        return None;
+    }
+
+    name.bytes()
+        .try_fold(0u32, |acc, b| {
+            let digit = match b {
+                b'A'..=b'Z' => b - b'A',
+                b'a'..=b'z' => b - b'a',
+                _ => return None,
+            };
+            acc.checked_mul(26)?.checked_add(u32::from(digit) + 1)
+        })
+        .map(|n| n - 1)
+}
+
+/// Format a zero-based column number as an Excel column letter like `"AB"`
+pub fn column_letter(col: u32) -> String {
+    let mut n = u64::from(col) + 1;
+    let mut name = Vec::new();
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        name.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).expect("column names are ASCII")
+}
+
+/// Parse an A1-style cell reference (optionally with `$` markers) into zero-based `(row, col)`
+pub fn parse_cell(reference: &str) -> Option<(u32, u32)> {
+    let reference = reference.replace('$', "");
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (col, row) = reference.split_at(split);
+
+    let col = parse_column(col)?;
+    let row: u32 = row.parse().ok()?;
+
+    Some((row.checked_sub(1)?, col))
+}
+
+/// Format a zero-based `(row, col)` position as an A1-style cell reference like `"B4"`
+pub fn format_cell((row, col): (u32, u32)) -> String {
+    format!("{}{}", column_letter(col), u64::from(row) + 1)
+}
+
+/// Parse an A1-style range like `"B4:H200"` (or a single cell) into zero-based `(start, end)`
+///
+/// A whole-column range like `"$A:$F"` spans every row of the sheet. Whole-row ranges like
+/// `"1:5"` are not supported.
+pub fn parse_range(reference: &str) -> Option<Span> {
+    match reference.split_once(':') {
+        Some((start, end)) => {
+            let (start, end) = match (parse_cell(start), parse_cell(end)) {
+                (Some(start), Some(end)) => (start, end),
+                (None, None) => (
+                    (0, parse_column(&start.replace('$', ""))?),
+                    (MAX_ROW, parse_column(&end.replace('$', ""))?),
+                ),
+                _ => return None,
+            };
+            Some((
+                (start.0.min(end.0), start.1.min(end.1)),
+                (start.0.max(end.0), start.1.max(end.1)),
+            ))
+        }
+        None => parse_cell(reference).map(|cell| (cell, cell)),
+    }
+}
+
+/// Parse a range on a named sheet like `"Sheet1!$B$4:$H$200"` or `"'Q1 Sales'!A1:C9"`, as
+/// defined names refer to them
+///
+/// Returns the unquoted sheet name and the range.
+pub fn parse_sheet_range(reference: &str) -> Option<(String, Span)> {
+    let reference = reference.trim().trim_start_matches('=');
+    let (sheet, range) = reference.rsplit_once('!')?;
+
+    let sheet = match sheet.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet.to_owned(),
+    };
+
+    if sheet.is_empty() {
+        return None;
+    }
+    Some((sheet, parse_range(range)?))
+}
+
+/// Format a zero-based `(start, end)` span as an A1-style range like `"B4:H200"`
+///
+/// A single-cell span is formatted as just the cell, e.g. `"B4"`.
+pub fn format_range((start, end): Span) -> String {
+    if start == end {
+        format_cell(start)
+    } else {
+        format!("{}:{}", format_cell(start), format_cell(end))
+    }
+}
+
+/// Move a `(row, col)` position by the given number of rows and columns
+///
+/// Returns `None` if the result would fall outside the sheet (above row 1 or left of column A).
+pub fn offset((row, col): (u32, u32), rows: i64, cols: i64) -> Option<(u32, u32)> {
+    let row = u32::try_from(i64::from(row) + rows).ok()?;
+    let col = u32::try_from(i64::from(col) + cols).ok()?;
+    Some((row, col))
+}
+
+/// Move a whole span by the given number of rows and columns, keeping its size
+pub fn offset_range((start, end): Span, rows: i64, cols: i64) -> Option<Span> {
+    Some((offset(start, rows, cols)?, offset(end, rows, cols)?))
+}
+
+/// Get the `(rows, columns)` size of a span whose start is above and left of its end
+pub fn span_size(((r0, c0), (r1, c1)): Span) -> (u32, u32) {
+    (r1 - r0 + 1, c1 - c0 + 1)
+}
+
+/// Get the part of span `a` that lies within span `b`, if they share any cell
+pub fn intersect(a: Span, b: Span) -> Option<Span> {
+    let start = ((a.0).0.max((b.0).0), (a.0).1.max((b.0).1));
+    let end = ((a.1).0.min((b.1).0), (a.1).1.min((b.1).1));
+    (start.0 <= end.0 && start.1 <= end.1).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("B4:H200"), Some(((3, 1), (199, 7))));
+        assert_eq!(parse_range("$H$200:$B$4"), Some(((3, 1), (199, 7))));
+        assert_eq!(parse_range("C3"), Some(((2, 2), (2, 2))));
+        assert_eq!(parse_range("$A:$F"), Some(((0, 0), (MAX_ROW, 5))));
+        assert_eq!(parse_range("1:5"), None);
+        assert_eq!(parse_range("A:F5"), None);
+        assert_eq!(parse_range("A0"), None);
+    }
+
+    #[test]
+    fn parses_sheet_ranges() {
+        assert_eq!(
+            parse_sheet_range("='Q1 ''Sales'''!$A$1:$C$9"),
+            Some(("Q1 'Sales'".to_owned(), ((0, 0), (8, 2))))
+        );
+        assert_eq!(
+            parse_sheet_range("Data!$B:$C"),
+            Some(("Data".to_owned(), ((0, 1), (MAX_ROW, 2))))
+        );
+        assert_eq!(parse_sheet_range("!A1"), None);
+        assert_eq!(parse_sheet_range("1"), None);
+    }
+
+    #[test]
+    fn intersects_spans() {
+        let used = ((2, 1), (10, 4));
+        assert_eq!(
+            intersect(((0, 0), (MAX_ROW, 2)), used),
+            Some(((2, 1), (10, 2)))
+        );
+        assert_eq!(intersect(((11, 0), (20, 9)), used), None);
+        assert_eq!(intersect(used, used), Some(used));
+    }
+
+    #[test]
+    fn formats_cells_and_columns() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(27), "AB");
+        assert_eq!(column_letter(16_383), "XFD");
+        assert_eq!(parse_column("XFD"), Some(16_383));
+        assert_eq!(format_range(((3, 1), (199, 7))), "B4:H200");
+        assert_eq!(format_range(((3, 1), (3, 1))), "B4");
+        assert_eq!(offset((0, 0), -1, 0), None);
+    }
+}
@@ -0,0 +1,72 @@
+//! Conversion to Polars data frames
+
+use crate::{ColumnValues, CompactColumn, WorkbookData};
+use polars::prelude::{DataFrame, DataType, NamedFrom, PolarsResult, Series, TimeUnit};
+
+/// Days from the Excel epoch (1899-12-30) to the Unix epoch
+const EXCEL_UNIX_EPOCH_DAYS: f64 = 25569.;
+const MILLIS_PER_DAY: f64 = 86_400_000.;
+
+fn to_series(column: &CompactColumn) -> PolarsResult<Series> {
+    let name = column.name().into();
+    let present = |i: usize| column.is_present(i);
+
+    Ok(match column.values() {
+        ColumnValues::Int(v) => Series::new(
+            name,
+            v.iter()
+                .enumerate()
+                .map(|(i, &x)| present(i).then_some(x))
+                .collect::<Vec<_>>(),
+        ),
+        ColumnValues::Float(v) => Series::new(
+            name,
+            v.iter()
+                .enumerate()
+                .map(|(i, &x)| present(i).then_some(x))
+                .collect::<Vec<_>>(),
+        ),
+        ColumnValues::DateTime(v) => Series::new(
+            name,
+            v.iter()
+                .enumerate()
+                .map(|(i, &serial)| {
+                    present(i)
+                        .then(|| ((serial - EXCEL_UNIX_EPOCH_DAYS) * MILLIS_PER_DAY).round() as i64)
+                })
+                .collect::<Vec<_>>(),
+        )
+        .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+        ColumnValues::Bool(v) => Series::new(
+            name,
+            v.iter()
+                .enumerate()
+                .map(|(i, &x)| present(i).then_some(x))
+                .collect::<Vec<_>>(),
+        ),
+        ColumnValues::Str { .. } => Series::new(
+            name,
+            (0..column.len())
+                .map(|i| column.get_str(i).filter(|_| present(i)))
+                .collect::<Vec<_>>(),
+        ),
+    })
+}
+
+impl WorkbookData {
+    /// Convert the data rows to a Polars data frame, inferring each column's type
+    ///
+    /// Columns are typed as in `compact`, with dates as millisecond datetimes and empty cells
+    /// as nulls. Fails if headers repeat, since Polars requires unique column names.
+    pub fn to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let compact = self.compact();
+
+        let columns = compact
+            .columns()
+            .iter()
+            .map(|c| to_series(c).map(Into::into))
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        DataFrame::new(compact.len(), columns)
+    }
+}
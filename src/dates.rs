@@ -0,0 +1,71 @@
+use crate::{DataError, DataType, RowData, WorkbookData};
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// Formats accepted for dates stored as text
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"];
+const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"];
+
+pub(crate) fn parse_datetime(value: &DataType) -> Option<NaiveDateTime> {
+    match value {
+        DataType::String(s) => {
+            let s = s.trim();
+            DATETIME_FORMATS
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+                .or_else(|| {
+                    DATE_FORMATS
+                        .iter()
+                        .find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                })
+        }
+        value => value.as_datetime(),
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get a date/time value, from a date cell, an Excel serial number or ISO-formatted text
+    pub fn get_datetime(&self, column_header: &str) -> Result<NaiveDateTime, DataError> {
+        let value = self.parse_source(column_header)?;
+
+        let parsed = parse_datetime(value);
+        self.source.record_parse(column_header, parsed.is_some());
+
+        parsed.ok_or_else(|| DataError::ParseError {
+            key: column_header.into(),
+            value: self
+                .source
+                .error_text(self.row_number, column_header, value),
+            position: self.source.column_position(column_header),
+        })
+    }
+
+    /// Get a date value, dropping any time of day
+    pub fn get_date(&self, column_header: &str) -> Result<NaiveDate, DataError> {
+        self.get_datetime(column_header).map(|dt| dt.date())
+    }
+}
+
+impl WorkbookData {
+    /// Get the rows whose date in `column_header` is within `start..=end`
+    ///
+    /// Rows with an empty or unreadable date are skipped.
+    pub fn rows_between(
+        &self,
+        column_header: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<RowData<'_>>, DataError> {
+        if self.column_id(column_header).is_none() {
+            return Err(DataError::NoValue(column_header.into()));
+        }
+
+        Ok(self
+            .rows()
+            .filter(|row| {
+                row.get_date(column_header)
+                    .is_ok_and(|date| start <= date && date <= end)
+            })
+            .collect())
+    }
+}
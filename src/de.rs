@@ -0,0 +1,138 @@
+//! Deserializing rows with serde
+//!
+//! A row is a map from header to cell value, visited in sheet column order, so ordered targets
+//! (`Vec<(String, String)>`, `#[serde(flatten)]` maps with ordered map types) keep the layout.
+
+use crate::{cell_to_str, DataError, DataType, RowData};
+use serde::de::value::{Error, MapDeserializer};
+use serde::de::{DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+
+/// Deserializes a single cell, parsing text for numeric and boolean targets
+#[derive(Clone, Copy)]
+struct Cell<'a>(&'a DataType);
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0 {
+                    DataType::String(s) => match s.trim().parse::<$ty>() {
+                        Ok(v) => visitor.$visit(v),
+                        Err(_) => self.visit_native(visitor),
+                    },
+                    _ => self.visit_native(visitor),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! deserialize_integer {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0 {
+                    // xlsx stores every number as a float
+                    DataType::Float(f) if f.fract() == 0. => visitor.visit_i64(*f as i64),
+                    DataType::String(s) => match s.trim().parse::<$ty>() {
+                        Ok(v) => visitor.$visit(v),
+                        Err(_) => self.visit_native(visitor),
+                    },
+                    _ => self.visit_native(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'a> Cell<'a> {
+    /// Visit the value with its stored type
+    fn visit_native<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            DataType::Int(i) => visitor.visit_i64(*i),
+            DataType::Float(f) | DataType::DateTime(f) => visitor.visit_f64(*f),
+            DataType::String(s) => visitor.visit_str(s),
+            DataType::Bool(b) => visitor.visit_bool(*b),
+            DataType::Error(e) => visitor.visit_string(e.to_string()),
+            DataType::Empty => visitor.visit_str(""),
+        }
+    }
+}
+
+impl<'de, 'a> Deserializer<'de> for Cell<'a> {
+    type Error = Error;
+
+    /// Untyped targets (including `#[serde(flatten)]` maps) get the same text as `get`
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            DataType::Empty => visitor.visit_none(),
+            DataType::String(s) if s.is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(cell_to_str(self.0).into_owned())
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    deserialize_integer! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+    }
+
+    deserialize_parsed! {
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+        deserialize_bool => visit_bool: bool,
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, 'a> IntoDeserializer<'de, Error> for Cell<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+const EMPTY: DataType = DataType::Empty;
+
+impl<'a> RowData<'a> {
+    /// Deserialize this row as a map from header to value, in sheet column order
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, DataError> {
+        let source = self.source;
+        let cells = source.columns.iter().map(|c| {
+            let value = source
+                .range
+                .get_value((self.row_number, c.index))
+                .unwrap_or(&EMPTY);
+            (c.name.as_str(), Cell(value))
+        });
+
+        T::deserialize(MapDeserializer::<_, Error>::new(cells)).map_err(|e| {
+            DataError::DeserializeError {
+                row: self.row_number,
+                message: e.to_string(),
+            }
+        })
+    }
+}
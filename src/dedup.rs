@@ -0,0 +1,54 @@
+use crate::{DataError, RowData, WorkbookData};
+use std::collections::HashSet;
+
+/// Which row `WorkbookData::dedup_by` keeps from each set of rows sharing a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeepPolicy {
+    #[default]
+    First,
+    Last,
+}
+
+impl WorkbookData {
+    /// Get the groups of rows that share a value in a key column, in order of first appearance
+    ///
+    /// Keys appearing only once are left out, as are rows with an empty key.
+    pub fn duplicates_by(
+        &self,
+        column_header: &str,
+    ) -> Result<Vec<(String, Vec<RowData<'_>>)>, DataError> {
+        Ok(self
+            .rows()
+            .group_by_column(column_header)?
+            .filter(|(key, rows)| !key.is_empty() && rows.len() > 1)
+            .collect())
+    }
+
+    /// Get the rows with one row kept per key value, in their original order
+    ///
+    /// Rows with an empty key are always kept.
+    pub fn dedup_by(
+        &self,
+        column_header: &str,
+        keep: KeepPolicy,
+    ) -> Result<Vec<RowData<'_>>, DataError> {
+        let id = self
+            .column_id(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        let mut seen = HashSet::new();
+        let mut keep_row = |row: &RowData| match row.get_by_id(id) {
+            Some(key) if !key.is_empty() => seen.insert(key.into_owned()),
+            _ => true,
+        };
+
+        Ok(match keep {
+            KeepPolicy::First => self.rows().filter(|row| keep_row(row)).collect(),
+            KeepPolicy::Last => {
+                let mut rows: Vec<_> = self.rows().rev().filter(|row| keep_row(row)).collect();
+                rows.reverse();
+                rows
+            }
+        })
+    }
+}
@@ -0,0 +1,209 @@
+//! CSV and TSV files, loaded as a single sheet named after the file
+
+use crate::{CellErrorKind, DataType, LoadError, LoadOptions, Range, WorkbookData};
+use std::path::Path;
+
+impl From<csv::Error> for LoadError {
+    fn from(err: csv::Error) -> Self {
+        LoadError::CsvError(err.to_string())
+    }
+}
+
+pub(crate) fn delimiter(path: &Path) -> Option<u8> {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("csv") => Some(b','),
+        Some("tsv") | Some("tab") => Some(b'\t'),
+        _ => None,
+    }
+}
+
+/// Check for a number written with leading zeros, like `"007"` but not `"0"` or `"0.5"`
+fn has_leading_zero(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    digits.len() > 1 && digits.starts_with('0') && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Type a text field the way a spreadsheet application would on import
+pub(crate) fn parse_field(field: &str) -> DataType {
+    let trimmed = field.trim();
+
+    if trimmed.is_empty() {
+        DataType::Empty
+    } else if has_leading_zero(trimmed) {
+        // Padded codes like "000123" are identifiers, and would lose their zeros as numbers
+        DataType::String(field.to_owned())
+    } else if let Ok(i) = trimmed.parse::<i64>() {
+        DataType::Int(i)
+    } else if let Ok(f) = trimmed.parse::<f64>() {
+        // Leave "inf", "NaN" and the like as text
+        if f.is_finite() {
+            DataType::Float(f)
+        } else {
+            DataType::String(field.to_owned())
+        }
+    } else if trimmed.eq_ignore_ascii_case("true") {
+        DataType::Bool(true)
+    } else if trimmed.eq_ignore_ascii_case("false") {
+        DataType::Bool(false)
+    } else if let Some(err) = CellErrorKind::from_code(trimmed) {
+        DataType::Error(err.into())
+    } else {
+        DataType::String(field.to_owned())
+    }
+}
+
+/// Read a delimited file into a range, allowing rows of different lengths
+pub(crate) fn read_range(
+    path: &Path,
+    delimiter: u8,
+    options: &LoadOptions,
+) -> Result<Range<DataType>, LoadError> {
+    read_records(path, delimiter, usize::MAX, Some(options))
+}
+
+/// Read the first `max_rows` records of a delimited file into a range
+pub(crate) fn read_preview(
+    path: &Path,
+    delimiter: u8,
+    max_rows: usize,
+) -> Result<Range<DataType>, LoadError> {
+    read_records(path, delimiter, max_rows, None)
+}
+
+fn read_records(
+    path: &Path,
+    delimiter: u8,
+    max_rows: usize,
+    options: Option<&LoadOptions>,
+) -> Result<Range<DataType>, LoadError> {
+    let progress = options.and_then(|o| o.progress.as_ref());
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)?;
+
+    let mut rows = Vec::new();
+    for record in reader.records().take(max_rows) {
+        rows.push(record?);
+        if let Some(options) = options {
+            options.check_cancelled()?;
+        }
+        if let Some(progress) = progress {
+            progress.report_row(rows.len(), None);
+        }
+    }
+    if let Some(progress) = progress {
+        progress.report(rows.len(), Some(rows.len()));
+    }
+
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    if rows.is_empty() || width == 0 {
+        return Ok(Range::empty());
+    }
+
+    let mut range = Range::new((0, 0), (rows.len() as u32 - 1, width as u32 - 1));
+    for (r, record) in rows.iter().enumerate() {
+        for (c, field) in record.iter().enumerate() {
+            range.set_value((r as u32, c as u32), parse_field(field));
+        }
+    }
+    Ok(range)
+}
+
+/// The name of the single sheet of a delimited file: its file name without the extension
+pub(crate) fn sheet_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+impl WorkbookData {
+    pub(crate) fn from_delimited_path(
+        path: &Path,
+        delimiter: u8,
+        options: &LoadOptions,
+    ) -> Result<Self, LoadError> {
+        let filename = path.to_string_lossy().to_string();
+        let sheet_name = sheet_name(path);
+
+        if let Some(requested) = &options.sheet_name {
+            if *requested != sheet_name {
+                return Err(LoadError::EmptySheet {
+                    filename,
+                    sheet_name: requested.clone(),
+                });
+            }
+        }
+
+        let range = read_range(path, delimiter, options)?;
+
+        let mut data =
+            Self::from_range_inner(range, options, &[]).ok_or(LoadError::Empty { filename })?;
+        data.sheet_name = Some(sheet_name);
+        data.apply_ragged_policy(options.ragged_rows)?;
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempPath;
+
+    #[test]
+    fn types_fields_like_a_spreadsheet() {
+        assert_eq!(parse_field(" 42 "), DataType::Int(42));
+        assert_eq!(parse_field("-1.5"), DataType::Float(-1.5));
+        assert_eq!(parse_field("TRUE"), DataType::Bool(true));
+        assert_eq!(parse_field("NaN"), DataType::String("NaN".into()));
+        assert_eq!(parse_field("  "), DataType::Empty);
+        assert!(matches!(parse_field("#N/A"), DataType::Error(_)));
+    }
+
+    #[test]
+    fn keeps_leading_zero_ids_as_text() {
+        assert_eq!(parse_field("007"), DataType::String("007".into()));
+        assert_eq!(parse_field("-0012"), DataType::String("-0012".into()));
+        assert_eq!(parse_field("0"), DataType::Int(0));
+        assert_eq!(parse_field("0.5"), DataType::Float(0.5));
+
+        let csv = TempPath::with_text(
+            "csv",
+            "Id,Zip
+000123,02134
+45,90210
+",
+        );
+        let data = WorkbookData::from_path(&csv).unwrap();
+        assert_eq!(data.get(1, "Id").as_deref(), Some("000123"));
+        assert_eq!(data.get(1, "Zip").as_deref(), Some("02134"));
+        assert_eq!(data.get(2, "Id").as_deref(), Some("45"));
+    }
+
+    #[test]
+    fn loads_csv_and_tsv_files_as_one_sheet() {
+        let csv = TempPath::with_text("csv", "Name,Qty\n\"Pen, blue\",3\nInk,\n");
+        let data = WorkbookData::from_path(&csv).unwrap();
+        assert_eq!(data.sheet_name(), csv.file_stem().and_then(|s| s.to_str()));
+        assert_eq!(data.get(1, "Name").as_deref(), Some("Pen, blue"));
+        assert_eq!(data.get(1, "Qty").as_deref(), Some("3"));
+        assert_eq!(data.get(2, "Qty").as_deref(), Some(""));
+
+        let tsv = TempPath::with_text("TSV", "Name\tQty\nPad\t7\n");
+        let data = WorkbookData::from_path(&tsv).unwrap();
+        assert_eq!(data.get(1, "Qty").as_deref(), Some("7"));
+
+        let options = LoadOptions::new().sheet_name("Other");
+        assert!(matches!(
+            WorkbookData::from_path_with_options(&csv, &options),
+            Err(LoadError::EmptySheet { .. })
+        ));
+    }
+}
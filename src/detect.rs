@@ -0,0 +1,79 @@
+//! Pluggable header row detection
+
+use crate::{cell_is_empty, trace, DataType, HeaderPredicate, LoadOptions, Range};
+
+/// A strategy for finding the header row of a sheet
+///
+/// Detection runs after the range has been trimmed (and transposed, if requested), and before
+/// merged cells are expanded. Closures taking the range and returning the row also implement
+/// this trait.
+pub trait HeaderDetector: Send + Sync {
+    /// Get the absolute row number of the header, or `None` if there is no header
+    fn detect(&self, range: &Range<DataType>) -> Option<u32>;
+}
+
+impl<F> HeaderDetector for F
+where
+    F: Fn(&Range<DataType>) -> Option<u32> + Send + Sync,
+{
+    fn detect(&self, range: &Range<DataType>) -> Option<u32> {
+        self(range)
+    }
+}
+
+/// The built-in detector: the first row with enough non-empty cells, or matching a predicate
+#[derive(Debug, Clone, Default)]
+pub struct HeuristicDetector {
+    /// Defaults to the full width of the range
+    pub min_header_cells: Option<usize>,
+    pub max_scan_rows: Option<usize>,
+    /// Used instead of counting cells, if set
+    pub predicate: Option<HeaderPredicate>,
+}
+
+impl HeuristicDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build from the header detection settings of `LoadOptions`
+    pub fn from_options(options: &LoadOptions) -> Self {
+        Self {
+            min_header_cells: options.min_header_cells,
+            max_scan_rows: options.max_header_scan_rows,
+            predicate: options.header_predicate.clone(),
+        }
+    }
+}
+
+impl HeaderDetector for HeuristicDetector {
+    fn detect(&self, range: &Range<DataType>) -> Option<u32> {
+        let (start_row, first_col) = range.start()?;
+        let (_, last_col) = range.end()?;
+
+        let width = (last_col - first_col + 1) as usize;
+        let min_cols = self.min_header_cells.unwrap_or(width).clamp(1, width);
+        let max_rows = self.max_scan_rows.unwrap_or(usize::MAX);
+
+        let found = range
+            .rows()
+            .take(max_rows)
+            .position(|row| match &self.predicate {
+                Some(predicate) => {
+                    let cells: Vec<String> = row.iter().map(|c| c.to_string()).collect();
+                    predicate.matches(&cells)
+                }
+                None => row.iter().filter(|x| !cell_is_empty(x)).count() >= min_cols,
+            })
+            .map(|i| start_row + i as u32);
+
+        trace::debug!(
+            header_row = ?found,
+            rule = if self.predicate.is_some() { "predicate" } else { "non-empty cells" },
+            min_cells = min_cols,
+            scanned_rows = range.height().min(max_rows),
+            "heuristic header detection"
+        );
+        found
+    }
+}
@@ -0,0 +1,198 @@
+//! Differences between two versions of a sheet, matching rows by a key column
+
+use crate::{
+    cell_is_empty, cell_to_str, CellChange, DataError, DuplicateKeyPolicy, OwnedRow, RowData,
+    WorkbookData,
+};
+use std::fmt;
+
+/// A row present in both versions whose values differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChange {
+    pub key: String,
+    /// Row number in the old data
+    pub old_row: u32,
+    /// Row number in the new data
+    pub new_row: u32,
+    pub changes: Vec<CellChange>,
+}
+
+impl fmt::Display for RowChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' (row {})", self.key, self.new_row + 1)?;
+        for (i, change) in self.changes.iter().enumerate() {
+            write!(f, "{} {}", if i == 0 { ":" } else { ";" }, change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything that changed between two versions of a sheet, from `diff`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkbookDiff {
+    pub key_column: String,
+    /// Columns only in the new data
+    pub added_columns: Vec<String>,
+    /// Columns only in the old data
+    pub removed_columns: Vec<String>,
+    /// Rows whose key is only in the new data, in new order
+    pub added: Vec<OwnedRow>,
+    /// Rows whose key is only in the old data, in old order
+    pub removed: Vec<OwnedRow>,
+    /// Rows with differing values in shared columns, in old order
+    pub changed: Vec<RowChange>,
+}
+
+impl WorkbookDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for WorkbookDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} added, {} removed, {} changed rows",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )?;
+
+        if !self.added_columns.is_empty() {
+            write!(
+                f,
+                "\n  Added columns: '{}'",
+                self.added_columns.join("', '")
+            )?;
+        }
+
+        if !self.removed_columns.is_empty() {
+            write!(
+                f,
+                "\n  Removed columns: '{}'",
+                self.removed_columns.join("', '")
+            )?;
+        }
+
+        let key = |row: &OwnedRow| row.get(&self.key_column).unwrap_or_default().to_owned();
+
+        for row in &self.added {
+            write!(f, "\n  Added '{}' (row {})", key(row), row.number() + 1)?;
+        }
+
+        for row in &self.removed {
+            write!(f, "\n  Removed '{}' (row {})", key(row), row.number() + 1)?;
+        }
+
+        for change in &self.changed {
+            write!(f, "\n  Changed {}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare two versions of a sheet, matching rows by the value in `key_column`
+///
+/// Cells are compared as `get` returns them, in the columns both versions share. Rows with an
+/// empty key are ignored, and a key repeated in either version is a `DataError::DuplicateKey`.
+pub fn diff(
+    old: &WorkbookData,
+    new: &WorkbookData,
+    key_column: &str,
+) -> Result<WorkbookDiff, DataError> {
+    let old_index = old.index_by_with_policy(key_column, DuplicateKeyPolicy::Error)?;
+    let new_index = new.index_by_with_policy(key_column, DuplicateKeyPolicy::Error)?;
+
+    let shared: Vec<&str> = old
+        .headers()
+        .filter(|h| new.column_id(h).is_some())
+        .collect();
+
+    // Read keys the same way `index_by` does, so every key is found in its own index
+    let keyed_rows = |data: &'_ WorkbookData| -> Vec<(String, u32)> {
+        (data.first_row..=data.last_row)
+            .filter_map(|row_number| match data.get_cell(row_number, key_column) {
+                Some(value) if !cell_is_empty(value) => {
+                    Some((cell_to_str(value).into_owned(), row_number))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+
+    for (key, row_number) in keyed_rows(old) {
+        let old_row = RowData {
+            source: old,
+            row_number,
+        };
+
+        let new_row = match new_index.get_row(&key) {
+            Some(new_row) => new_row,
+            None => {
+                removed.push(old_row.to_owned_row());
+                continue;
+            }
+        };
+
+        let changes: Vec<CellChange> = shared
+            .iter()
+            .filter_map(|&header| {
+                let old_value = old_row.get(header).unwrap_or_default();
+                let new_value = new_row.get(header).unwrap_or_default();
+
+                (old_value != new_value).then(|| CellChange {
+                    header: header.into(),
+                    old: Some(old_value.into_owned()),
+                    new: Some(new_value.into_owned()),
+                })
+            })
+            .collect();
+
+        if !changes.is_empty() {
+            changed.push(RowChange {
+                key,
+                old_row: row_number,
+                new_row: new_row.number(),
+                changes,
+            });
+        }
+    }
+
+    let added = keyed_rows(new)
+        .into_iter()
+        .filter(|(key, _)| !old_index.contains_key(key))
+        .map(|(_, row_number)| {
+            RowData {
+                source: new,
+                row_number,
+            }
+            .to_owned_row()
+        })
+        .collect();
+
+    Ok(WorkbookDiff {
+        key_column: key_column.into(),
+        added_columns: new
+            .headers()
+            .filter(|h| old.column_id(h).is_none())
+            .map(String::from)
+            .collect(),
+        removed_columns: old
+            .headers()
+            .filter(|h| new.column_id(h).is_none())
+            .map(String::from)
+            .collect(),
+        added,
+        removed,
+        changed,
+    })
+}
@@ -0,0 +1,185 @@
+use crate::{DataType, RowData, WorkbookData};
+use std::fmt;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Number of rows included in `WorkbookData`'s `Debug` output
+const DEBUG_SAMPLE_ROWS: usize = 3;
+
+/// Number of rows in `WorkbookData`'s `Display` output, unless a precision is given
+const DISPLAY_ROWS: usize = 10;
+
+/// Cells longer than this many characters are cut short in tables
+const MAX_CELL_WIDTH: usize = 30;
+
+impl WorkbookData {
+    /// Hide (or show again) a column's values in `Debug`/`Display` output
+    ///
+    /// Returns `false` if no column has this header.
+    pub fn set_redacted(&mut self, column_header: &str, redacted: bool) -> bool {
+        let mut found = false;
+        for column in self.columns.iter_mut().filter(|c| c.name == column_header) {
+            column.redacted = redacted;
+            found = true;
+        }
+        found
+    }
+
+    pub fn is_redacted(&self, column_header: &str) -> bool {
+        self.column_id(column_header)
+            .is_some_and(|c| self.columns[c.0].redacted)
+    }
+
+    /// Render the headers and first `max_rows` rows as an aligned text table
+    ///
+    /// The first column is the sheet row number as Excel shows it, to check which rows were
+    /// taken as the header and data. Long values are cut short and redacted columns hidden.
+    pub fn to_table_string(&self, max_rows: usize) -> String {
+        let mut table = vec![std::iter::once("row".to_owned())
+            .chain(self.headers().map(table_cell))
+            .collect::<Vec<_>>()];
+        let mut numeric = vec![true; self.columns.len() + 1];
+
+        for row in self.rows().take(max_rows) {
+            let mut cells = vec![(u64::from(row.number()) + 1).to_string()];
+            for (i, column) in self.columns.iter().enumerate() {
+                let value = self.range.get_value((row.number(), column.index));
+                if column.redacted {
+                    numeric[i + 1] = false;
+                    cells.push(REDACTED.to_owned());
+                    continue;
+                }
+                if !matches!(
+                    value,
+                    None | Some(DataType::Int(_))
+                        | Some(DataType::Float(_))
+                        | Some(DataType::Empty)
+                ) {
+                    numeric[i + 1] = false;
+                }
+                cells.push(
+                    value
+                        .map(|v| table_cell(&self.cell_text(row.number(), column, v)))
+                        .unwrap_or_default(),
+                );
+            }
+            table.push(cells);
+        }
+
+        let widths: Vec<usize> = (0..numeric.len())
+            .map(|i| {
+                table
+                    .iter()
+                    .map(|cells| cells[i].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut lines = Vec::with_capacity(table.len() + 2);
+        for (r, cells) in table.iter().enumerate() {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .zip(&numeric)
+                .map(|((cell, &width), &numeric)| {
+                    let pad = " ".repeat(width - cell.chars().count());
+                    if numeric && r > 0 {
+                        format!("{}{}", pad, cell)
+                    } else {
+                        format!("{}{}", cell, pad)
+                    }
+                })
+                .collect();
+            lines.push(line.join(" | ").trim_end().to_owned());
+
+            if r == 0 {
+                let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+                lines.push(rule.join("-+-"));
+            }
+        }
+
+        let rows = (self.last_row + 1).saturating_sub(self.first_row) as usize;
+        if rows > max_rows {
+            lines.push(format!("... {} more rows", rows - max_rows));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Put a value on one line, cutting it short if it is too long for a table
+fn table_cell(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+
+    if value.chars().count() > MAX_CELL_WIDTH {
+        let cut: String = value.chars().take(MAX_CELL_WIDTH - 3).collect();
+        format!("{}...", cut)
+    } else {
+        value
+    }
+}
+
+impl fmt::Debug for WorkbookData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = (self.last_row + 1).saturating_sub(self.first_row);
+        let sample: Vec<_> = self.rows().take(DEBUG_SAMPLE_ROWS).collect();
+
+        f.debug_struct("WorkbookData")
+            .field("sheet_name", &self.sheet_name)
+            .field("header_row", &self.header_row())
+            .field("rows", &rows)
+            .field("columns", &self.columns.len())
+            .field("headers", &self.headers().collect::<Vec<_>>())
+            .field("sample", &sample)
+            .finish()
+    }
+}
+
+/// Formats as an aligned table of the first rows; a precision sets the number of rows, as in
+/// `format!("{:.50}", data)`
+impl fmt::Display for WorkbookData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table_string(f.precision().unwrap_or(DISPLAY_ROWS)))
+    }
+}
+
+impl<'a> RowData<'a> {
+    fn fmt_pairs(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, column) in self.source.columns.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            if column.redacted {
+                write!(f, "{}={}", column.name, REDACTED)?;
+            } else {
+                let value = self
+                    .source
+                    .range
+                    .get_value((self.row_number, column.index))
+                    .map(|v| self.source.cell_text(self.row_number, column, v))
+                    .unwrap_or_default();
+                write!(f, "{}={}", column.name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats as `header=value` pairs, hiding redacted columns
+impl<'a> fmt::Display for RowData<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_pairs(f)
+    }
+}
+
+impl<'a> fmt::Debug for RowData<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Row {} {{ ", self.row_number)?;
+        self.fmt_pairs(f)?;
+        write!(f, " }}")
+    }
+}
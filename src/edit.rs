@@ -0,0 +1,699 @@
+//! Updating cells of a loaded xlsx sheet and saving the workbook back
+
+use crate::coords::{self, Span};
+use crate::xlsx::{self, XlsxPackage};
+use crate::{DataError, DataType, LoadError, WorkbookData, WriteError, WritePlan};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Relationship type of the workbook's formula calculation order
+const CALC_CHAIN_REL: &str = "/calcChain";
+
+/// Pending cell updates to the sheet a `WorkbookData` was loaded from, from
+/// `WorkbookData::edit`
+///
+/// Cells are addressed like reads, by row number and header, so the data can be iterated while
+/// edits are made. Nothing is written until `save` or `save_as`; every other sheet and part of
+/// the workbook is copied unchanged. `plan_save` and `plan_save_as` check the edits without
+/// writing anything.
+pub struct SheetEditor<'a> {
+    source: &'a WorkbookData,
+    path: PathBuf,
+    sheet_name: String,
+    /// Columns added with `add_column`, with their absolute column numbers
+    added: Vec<(String, u32)>,
+    /// New values by absolute sheet position
+    edits: BTreeMap<(u32, u32), CellEdit>,
+    /// Row number `append` writes to next
+    next_row: u32,
+    /// Styles for appended cells by column number, as when filling a template
+    column_styles: HashMap<u32, String>,
+}
+
+struct CellEdit {
+    value: DataType,
+    /// Style index to set, instead of keeping the cell's own
+    style: Option<String>,
+}
+
+impl From<DataType> for CellEdit {
+    fn from(value: DataType) -> Self {
+        Self { value, style: None }
+    }
+}
+
+impl WorkbookData {
+    /// Start editing the sheet this data was loaded from
+    ///
+    /// Only data loaded from an xlsx file can be edited, and the file should not have changed
+    /// since (see `is_stale`).
+    pub fn edit(&self) -> Result<SheetEditor<'_>, WriteError> {
+        let path = self
+            .source_path()
+            .filter(|p| xlsx::is_xlsx_path(p))
+            .ok_or_else(|| WriteError::NotEditable("not loaded from an xlsx file".into()))?;
+        let sheet_name = self
+            .sheet_name
+            .clone()
+            .ok_or_else(|| WriteError::NotEditable("no sheet name".into()))?;
+
+        Ok(SheetEditor {
+            source: self,
+            path: path.to_owned(),
+            sheet_name,
+            added: Vec::new(),
+            edits: BTreeMap::new(),
+            next_row: self.last_row + 1,
+            column_styles: HashMap::new(),
+        })
+    }
+}
+
+impl<'a> SheetEditor<'a> {
+    /// Get the absolute sheet position of a cell, swapping axes for transposed data
+    fn position(&self, row_number: u32, index: u32) -> (u32, u32) {
+        if self.source.transposed {
+            (index, row_number)
+        } else {
+            (row_number, index)
+        }
+    }
+
+    fn column_index(&self, column_header: &str) -> Option<u32> {
+        match self.source.column_id(column_header) {
+            Some(id) => Some(self.source.columns[id.0].index),
+            None => self
+                .added
+                .iter()
+                .find(|(header, _)| header == column_header)
+                .map(|&(_, index)| index),
+        }
+    }
+
+    /// Add a column after the last one, writing its header; does nothing if it already exists
+    pub fn add_column(&mut self, column_header: &str) {
+        if self.column_index(column_header).is_some() {
+            return;
+        }
+
+        let index = self
+            .added
+            .last()
+            .map_or(self.source.last_col, |&(_, index)| index)
+            + 1;
+        let position = self.position(self.source.header_row(), index);
+        self.edits
+            .insert(position, DataType::from(column_header).into());
+        self.added.push((column_header.to_owned(), index));
+    }
+
+    /// Set a cell by row number (from `RowData::number`) and column header
+    ///
+    /// Setting `DataType::Empty` clears the cell. The cell keeps its style, but loses any
+    /// formula.
+    pub fn set<V: Into<DataType>>(
+        &mut self,
+        row_number: u32,
+        column_header: &str,
+        value: V,
+    ) -> Result<(), DataError> {
+        let index = self
+            .column_index(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        let position = self.position(row_number, index);
+        self.edits.insert(position, value.into().into());
+        Ok(())
+    }
+
+    /// Add a row below the last data row (or the last appended one), returning its row number
+    ///
+    /// Columns not given are left empty. Fails without adding the row if a header is not a
+    /// column of the sheet.
+    pub fn append<R, H, V>(&mut self, cells: R) -> Result<u32, DataError>
+    where
+        R: IntoIterator<Item = (H, V)>,
+        H: AsRef<str>,
+        V: Into<DataType>,
+    {
+        let row_number = self.next_row;
+
+        let mut row = Vec::new();
+        for (column_header, value) in cells {
+            let column_header = column_header.as_ref();
+            let index = self
+                .column_index(column_header)
+                .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+            let edit = CellEdit {
+                value: value.into(),
+                style: self.column_styles.get(&index).cloned(),
+            };
+            row.push((self.position(row_number, index), edit));
+        }
+
+        self.edits.extend(row);
+        self.next_row += 1;
+        Ok(row_number)
+    }
+
+    /// Append from `row_number` on, styling cells like the given columns
+    pub(crate) fn append_from(&mut self, row_number: u32, column_styles: HashMap<u32, String>) {
+        self.next_row = row_number;
+        self.column_styles = column_styles;
+    }
+
+    /// Get the number of cells that will be written
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Write the edits back to the file the data was loaded from
+    pub fn save(&self) -> Result<(), WriteError> {
+        self.save_as(&self.path)
+    }
+
+    /// Write a copy of the source workbook with the edits applied
+    ///
+    /// The file is written under a temporary name and then renamed, so `path` can be the
+    /// source file itself.
+    pub fn save_as<P: AsRef<Path>>(&self, path: P) -> Result<(), WriteError> {
+        self.save_with_parts(path.as_ref(), HashMap::new(), false)?;
+        Ok(())
+    }
+
+    /// Do a dry run of `save`, patching the sheet without writing the file
+    pub fn plan_save(&self) -> Result<WritePlan, WriteError> {
+        self.plan_save_as(&self.path)
+    }
+
+    /// Do a dry run of `save_as`, patching the sheet without writing the file
+    pub fn plan_save_as<P: AsRef<Path>>(&self, path: P) -> Result<WritePlan, WriteError> {
+        self.save_with_parts(path.as_ref(), HashMap::new(), true)
+    }
+
+    /// Get the number of data rows with at least one edit, leaving out the header
+    fn edited_rows(&self) -> usize {
+        let header_row = self.source.header_row();
+        let rows: BTreeSet<u32> = self
+            .edits
+            .keys()
+            .map(|&(row, col)| if self.source.transposed { col } else { row })
+            .filter(|&row| row != header_row)
+            .collect();
+        rows.len()
+    }
+
+    /// Save, also replacing (or with `None`, dropping) other parts of the package
+    ///
+    /// With `dry_run`, everything is prepared but the file is not written.
+    pub(crate) fn save_with_parts(
+        &self,
+        path: &Path,
+        mut replaced: HashMap<String, Option<Vec<u8>>>,
+        dry_run: bool,
+    ) -> Result<WritePlan, WriteError> {
+        let mut package = XlsxPackage::open(&self.path)?;
+
+        let sheet_part = package.sheet_path(&self.sheet_name)?.ok_or_else(|| {
+            WriteError::NotEditable(format!("no sheet '{}' in the workbook", self.sheet_name))
+        })?;
+        let xml = package.read_part(&sheet_part)?.unwrap_or_default();
+        let patched = patch_sheet(&xml, &self.edits)?;
+
+        if patched.removed_formula {
+            // Excel rebuilds the calculation chain, but reports a corrupt file if it lists a
+            // cell that no longer has a formula
+            replaced.extend(without_calc_chain(&mut package)?);
+        }
+        replaced.insert(sheet_part, Some(patched.xml));
+
+        let plan = WritePlan::new(Some(path), &self.sheet_name, self.edited_rows(), self.len());
+        if dry_run {
+            return Ok(plan);
+        }
+
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+        let written = File::create(&temp_path)
+            .map_err(WriteError::from)
+            .and_then(|file| {
+                let out = package.rewrite(BufWriter::new(file), &replaced)?;
+                out.into_inner().map_err(|e| e.into_error())?;
+                Ok(())
+            });
+
+        match written {
+            Ok(()) => {
+                fs::rename(&temp_path, path)?;
+                Ok(plan)
+            }
+            Err(err) => {
+                let _ = fs::remove_file(&temp_path);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Add rows below the last data row of a sheet, matching values to its header by name
+///
+/// Each row is a list of `(header, value)` pairs, like `[("Date", "2024-05-01"), ("Count", 3)]`
+/// or the cells of an `OwnedRow`. Rows are only written if all of them match the header.
+/// Returns the number of rows added.
+pub fn append_rows<P, I, R, H, V>(path: P, sheet_name: &str, rows: I) -> Result<usize, WriteError>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = (H, V)>,
+    H: AsRef<str>,
+    V: Into<DataType>,
+{
+    let plan = append(path.as_ref(), sheet_name, rows, false)?;
+    Ok(plan.rows())
+}
+
+/// Do a dry run of `append_rows`, matching the rows to the header without writing the file
+pub fn plan_append_rows<P, I, R, H, V>(
+    path: P,
+    sheet_name: &str,
+    rows: I,
+) -> Result<WritePlan, WriteError>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = (H, V)>,
+    H: AsRef<str>,
+    V: Into<DataType>,
+{
+    append(path.as_ref(), sheet_name, rows, true)
+}
+
+fn append<I, R, H, V>(
+    path: &Path,
+    sheet_name: &str,
+    rows: I,
+    dry_run: bool,
+) -> Result<WritePlan, WriteError>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = (H, V)>,
+    H: AsRef<str>,
+    V: Into<DataType>,
+{
+    let data = WorkbookData::from_path_with_sheet_name(path, sheet_name)?;
+    let mut editor = data.edit()?;
+
+    let mut count = 0;
+    for row in rows {
+        editor.append(row)?;
+        count += 1;
+    }
+
+    if count == 0 {
+        return Ok(WritePlan {
+            path: Some(path.to_owned()),
+            sheets: Vec::new(),
+        });
+    }
+
+    let mut plan = editor.save_with_parts(path, HashMap::new(), dry_run)?;
+    plan.sheets[0].rows = count;
+    Ok(plan)
+}
+
+/// The replacement parts that remove the calculation chain from a package
+fn without_calc_chain<R: std::io::Read + std::io::Seek>(
+    package: &mut XlsxPackage<R>,
+) -> Result<HashMap<String, Option<Vec<u8>>>, LoadError> {
+    let mut replaced = HashMap::new();
+    let part = match package
+        .relationships("xl/workbook.xml")?
+        .into_iter()
+        .find(|r| r.kind.ends_with(CALC_CHAIN_REL))
+    {
+        Some(rel) => rel.target,
+        None => return Ok(replaced),
+    };
+
+    let rels = package.read_part("xl/_rels/workbook.xml.rels")?;
+    let types = package.read_part("[Content_Types].xml")?;
+    if let (Some(rels), Some(types)) = (rels, types) {
+        let rels = remove_elements(&rels, |e| {
+            e.local_name() == b"Relationship"
+                && xlsx::attr(e, b"Type").is_some_and(|t| t.ends_with(CALC_CHAIN_REL))
+        })?;
+        let part_name = format!("/{}", part);
+        let types = remove_elements(&types, |e| {
+            e.local_name() == b"Override"
+                && xlsx::attr(e, b"PartName").as_deref() == Some(part_name.as_str())
+        })?;
+
+        replaced.insert("xl/_rels/workbook.xml.rels".to_owned(), Some(rels));
+        replaced.insert("[Content_Types].xml".to_owned(), Some(types));
+        replaced.insert(part, None);
+    }
+    Ok(replaced)
+}
+
+/// Copy an XML document without the empty elements matching `remove`
+fn remove_elements<F>(xml: &[u8], remove: F) -> Result<Vec<u8>, LoadError>
+where
+    F: Fn(&BytesStart) -> bool,
+{
+    let mut reader = Reader::from_reader(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        match reader.read_event(&mut buf)? {
+            Event::Empty(ref e) if remove(e) => {}
+            Event::Eof => return Ok(writer.into_inner()),
+            event => writer.write_event(event)?,
+        }
+    }
+}
+
+struct PatchedSheet {
+    xml: Vec<u8>,
+    /// Whether a replaced cell had a formula
+    removed_formula: bool,
+}
+
+/// Rewrite a sheet's XML with cells replaced, added or cleared, keeping everything else as is
+fn patch_sheet(
+    xml: &[u8],
+    edits: &BTreeMap<(u32, u32), CellEdit>,
+) -> Result<PatchedSheet, LoadError> {
+    let mut rows: BTreeMap<u32, BTreeMap<u32, &CellEdit>> = BTreeMap::new();
+    for (&(row, col), value) in edits {
+        rows.entry(row).or_default().insert(col, value);
+    }
+
+    let mut reader = Reader::from_reader(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut buf = Vec::new();
+
+    let mut row_edits = BTreeMap::new();
+    let (mut current_row, mut next_row, mut next_col) = (0, 0, 0);
+    // Depth inside a replaced cell whose original content is being dropped
+    let mut skip_depth = 0usize;
+    let mut removed_formula = false;
+
+    loop {
+        buf.clear();
+        let event = reader.read_event(&mut buf)?;
+
+        if skip_depth > 0 {
+            match event {
+                Event::Start(ref e) => {
+                    removed_formula |= e.local_name() == b"f";
+                    skip_depth += 1;
+                }
+                Event::Empty(ref e) => removed_formula |= e.local_name() == b"f",
+                Event::End(_) => skip_depth -= 1,
+                Event::Eof => break,
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Empty(ref e) if e.local_name() == b"dimension" => {
+                let declared = xlsx::attr(e, b"ref")
+                    .as_deref()
+                    .and_then(coords::parse_range);
+                let extent = edits
+                    .keys()
+                    .fold(declared, |extent, &cell| Some(extend(extent, cell)));
+                let mut dimension = BytesStart::owned_name(e.name().to_vec());
+                if let Some(extent) = extent {
+                    dimension.push_attribute(("ref", coords::format_range(extent).as_str()));
+                }
+                writer.write_event(Event::Empty(dimension))?;
+            }
+            Event::Empty(ref e) if e.local_name() == b"sheetData" => {
+                let name = e.name().to_vec();
+                writer.write_event(Event::Start(e.to_owned()))?;
+                write_rows(&mut writer, &mut rows, None)?;
+                writer.write_event(Event::End(BytesEnd::owned(name)))?;
+            }
+            Event::End(ref e) if e.local_name() == b"sheetData" => {
+                write_rows(&mut writer, &mut rows, None)?;
+                writer.write_event(Event::End(e.to_owned()))?;
+            }
+            Event::Start(ref e) | Event::Empty(ref e) if e.local_name() == b"row" => {
+                current_row = xlsx::attr(e, b"r")
+                    .and_then(|r| r.trim().parse::<u32>().ok())
+                    .and_then(|r| r.checked_sub(1))
+                    .unwrap_or(next_row);
+                next_row = current_row + 1;
+                next_col = 0;
+
+                write_rows(&mut writer, &mut rows, Some(current_row))?;
+                row_edits = rows.remove(&current_row).unwrap_or_default();
+
+                let is_empty = matches!(event, Event::Empty(_));
+                if row_edits.is_empty() {
+                    writer.write_event(event)?;
+                } else {
+                    // The optional span hint may no longer cover the row's cells
+                    let start = BytesStart::owned_name(e.name().to_vec())
+                        .with_attributes(e.attributes().flatten().filter(|a| a.key != b"spans"));
+                    writer.write_event(Event::Start(start))?;
+                    if is_empty {
+                        write_cells(&mut writer, current_row, &mut row_edits, None)?;
+                        writer.write_event(Event::End(BytesEnd::owned(e.name().to_vec())))?;
+                    }
+                }
+            }
+            Event::End(ref e) if e.local_name() == b"row" => {
+                write_cells(&mut writer, current_row, &mut row_edits, None)?;
+                writer.write_event(Event::End(e.to_owned()))?;
+            }
+            Event::Start(ref e) | Event::Empty(ref e) if e.local_name() == b"c" => {
+                let position = xlsx::attr(e, b"r")
+                    .as_deref()
+                    .and_then(coords::parse_cell)
+                    .unwrap_or((current_row, next_col));
+                next_col = position.1 + 1;
+
+                write_cells(&mut writer, current_row, &mut row_edits, Some(position.1))?;
+                match row_edits.remove(&position.1) {
+                    Some(edit) => {
+                        let style = edit.style.clone().or_else(|| xlsx::attr(e, b"s"));
+                        writer
+                            .write(cell_xml(position, &edit.value, style.as_deref()).as_bytes())?;
+                        if let Event::Start(_) = event {
+                            skip_depth = 1;
+                        }
+                    }
+                    None => writer.write_event(event)?,
+                }
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    Ok(PatchedSheet {
+        xml: writer.into_inner(),
+        removed_formula,
+    })
+}
+
+/// Grow a span to include a cell
+fn extend(extent: Option<Span>, (row, col): (u32, u32)) -> Span {
+    match extent {
+        Some(((r0, c0), (r1, c1))) => ((r0.min(row), c0.min(col)), (r1.max(row), c1.max(col))),
+        None => ((row, col), (row, col)),
+    }
+}
+
+/// Write the edited rows before `before` (or all of them) as new rows
+fn write_rows(
+    writer: &mut Writer<Vec<u8>>,
+    rows: &mut BTreeMap<u32, BTreeMap<u32, &CellEdit>>,
+    before: Option<u32>,
+) -> Result<(), LoadError> {
+    while let Some(&row) = rows.keys().next() {
+        if before.is_some_and(|before| row >= before) {
+            break;
+        }
+        let mut cells = rows.remove(&row).unwrap_or_default();
+        if cells
+            .values()
+            .all(|edit| matches!(edit.value, DataType::Empty) && edit.style.is_none())
+        {
+            continue;
+        }
+
+        writer.write(format!(r#"<row r="{}">"#, u64::from(row) + 1).as_bytes())?;
+        write_cells(writer, row, &mut cells, None)?;
+        writer.write(b"</row>")?;
+    }
+    Ok(())
+}
+
+/// Write the edited cells of a row left of column `before` (or all of them) as new cells
+fn write_cells(
+    writer: &mut Writer<Vec<u8>>,
+    row: u32,
+    cells: &mut BTreeMap<u32, &CellEdit>,
+    before: Option<u32>,
+) -> Result<(), LoadError> {
+    while let Some(&col) = cells.keys().next() {
+        if before.is_some_and(|before| col >= before) {
+            break;
+        }
+        if let Some(edit) = cells.remove(&col) {
+            let xml = cell_xml((row, col), &edit.value, edit.style.as_deref());
+            writer.write(xml.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Format a cell element, with strings written inline so the shared strings are left as is
+///
+/// An empty value without a style writes nothing.
+fn cell_xml(position: (u32, u32), value: &DataType, style: Option<&str>) -> String {
+    let mut attributes = format!(r#"r="{}""#, coords::format_cell(position));
+    if let Some(style) = style {
+        attributes.push_str(&format!(r#" s="{}""#, escape(style)));
+    }
+
+    match value {
+        DataType::Empty if style.is_none() => String::new(),
+        DataType::Empty => format!("<c {}/>", attributes),
+        DataType::String(s) => format!(
+            r#"<c {} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+            attributes,
+            escape(s)
+        ),
+        DataType::Int(i) => format!("<c {}><v>{}</v></c>", attributes, i),
+        DataType::Float(f) | DataType::DateTime(f) if f.is_finite() => {
+            format!("<c {}><v>{}</v></c>", attributes, f)
+        }
+        DataType::Float(_) | DataType::DateTime(_) => {
+            format!(r#"<c {} t="e"><v>#NUM!</v></c>"#, attributes)
+        }
+        DataType::Bool(b) => format!(r#"<c {} t="b"><v>{}</v></c>"#, attributes, u8::from(*b)),
+        DataType::Error(e) => format!(
+            r#"<c {} t="e"><v>{}</v></c>"#,
+            attributes,
+            escape(&e.to_string())
+        ),
+    }
+}
+
+fn escape(text: &str) -> Cow<'_, str> {
+    match quick_xml::escape::escape(text.as_bytes()) {
+        Cow::Borrowed(_) => Cow::Borrowed(text),
+        Cow::Owned(escaped) => Cow::Owned(String::from_utf8_lossy(&escaped).into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempPath, TestXlsx};
+    use crate::SheetPlan;
+
+    fn orders() -> TempPath {
+        TestXlsx::new()
+            .sheet("Orders", &[&["Item", "Qty"], &["Pen", "3"], &["Ink", "1"]])
+            .write()
+    }
+
+    #[test]
+    fn saves_edits_to_a_copy() {
+        let path = orders();
+        let copy = TempPath::new("xlsx");
+        let data = WorkbookData::from_path(&path).unwrap();
+        let mut editor = data.edit().unwrap();
+        editor.set(1, "Qty", 5).unwrap();
+        editor.add_column("Note");
+        editor.set(2, "Note", "late").unwrap();
+        assert!(editor.set(1, "Colour", "red").is_err());
+        editor.save_as(&copy).unwrap();
+
+        let edited = WorkbookData::from_path(&copy).unwrap();
+        assert_eq!(edited.get(1, "Qty").as_deref(), Some("5"));
+        assert_eq!(edited.get(2, "Note").as_deref(), Some("late"));
+        assert_eq!(data.get(1, "Qty").as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn saves_edits_in_place() {
+        let path = orders();
+        let data = WorkbookData::from_path(&path).unwrap();
+        let mut editor = data.edit().unwrap();
+        editor.set(2, "Item", DataType::Empty).unwrap();
+        editor.save().unwrap();
+
+        let edited = WorkbookData::from_path(&path).unwrap();
+        assert_eq!(edited.get(2, "Item").as_deref(), Some(""));
+        assert_eq!(edited.get(2, "Qty").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn appends_rows_below_the_data() {
+        let path = orders();
+        let rows = vec![vec![("Item", "Pad"), ("Qty", "2")], vec![("Qty", "4")]];
+        assert_eq!(append_rows(&path, "Orders", rows).unwrap(), 2);
+
+        let data = WorkbookData::from_path(&path).unwrap();
+        assert_eq!(data.get(3, "Item").as_deref(), Some("Pad"));
+        assert_eq!(data.get(3, "Qty").as_deref(), Some("2"));
+        assert_eq!(data.get(4, "Qty").as_deref(), Some("4"));
+        assert_eq!(data.last_row, 4);
+    }
+
+    #[test]
+    fn rejects_rows_with_unknown_headers() {
+        let path = orders();
+        let before = fs::read(&path).unwrap();
+        let rows = vec![vec![("Item", "Pad")], vec![("Colour", "red")]];
+        assert!(append_rows(&path, "Orders", rows).is_err());
+        assert_eq!(fs::read(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn plans_writes_without_changing_the_file() {
+        let path = orders();
+        let before = fs::read(&path).unwrap();
+
+        let rows = vec![vec![("Item", "Pad"), ("Qty", "2")], vec![("Item", "Cap")]];
+        let plan = plan_append_rows(&path, "Orders", rows).unwrap();
+        assert_eq!(plan.path.as_deref(), Some(path.as_ref()));
+        let sheet = SheetPlan {
+            name: "Orders".into(),
+            rows: 2,
+            cells: 3,
+        };
+        assert_eq!(plan.sheets, [sheet]);
+        let rows = vec![vec![("Colour", "red")]];
+        assert!(plan_append_rows(&path, "Orders", rows).is_err());
+
+        let data = WorkbookData::from_path(&path).unwrap();
+        let mut editor = data.edit().unwrap();
+        editor.set(1, "Qty", 5).unwrap();
+        editor.add_column("Note");
+        editor.set(1, "Note", "late").unwrap();
+        editor.set(2, "Note", "ok").unwrap();
+        let plan = editor.plan_save().unwrap();
+        assert_eq!((plan.rows(), plan.sheets[0].cells), (2, 4));
+
+        assert_eq!(fs::read(&path).unwrap(), before);
+    }
+}
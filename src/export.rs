@@ -0,0 +1,184 @@
+use crate::stats::Timer;
+use crate::{Column, RowData, WorkbookData};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+type DeriveFn = dyn Fn(&RowData) -> String + Send + Sync;
+
+/// A column computed from each row when exporting
+#[derive(Clone)]
+struct DerivedColumn {
+    name: String,
+    compute: Arc<DeriveFn>,
+}
+
+impl fmt::Debug for DerivedColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DerivedColumn({:?})", self.name)
+    }
+}
+
+/// Options controlling how a `WorkbookData` is exported
+///
+/// Derived columns are computed row by row as they are written, so reshaping does not need
+/// a transformed copy of the data.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    derived: Vec<DerivedColumn>,
+}
+
+impl ExportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a column computed from each row
+    ///
+    /// If the data already has a column with this header, it is replaced in place;
+    /// otherwise the column is added after the existing ones.
+    pub fn derive<F>(mut self, column_header: &str, compute: F) -> Self
+    where
+        F: Fn(&RowData) -> String + Send + Sync + 'static,
+    {
+        self.derived.retain(|d| d.name != column_header);
+        self.derived.push(DerivedColumn {
+            name: column_header.to_owned(),
+            compute: Arc::new(compute),
+        });
+        self
+    }
+
+    /// Add a column joining the non-empty values of other columns with `separator`,
+    /// like `concat("Name", &["First", "Last"], " ")`
+    pub fn concat(self, column_header: &str, sources: &[&str], separator: &str) -> Self {
+        let sources: Vec<String> = sources.iter().map(|&s| s.to_owned()).collect();
+        let separator = separator.to_owned();
+
+        self.derive(column_header, move |row| {
+            sources
+                .iter()
+                .filter_map(|s| row.get(s).ok())
+                .filter(|v| !v.is_empty())
+                .collect::<Vec<_>>()
+                .join(&separator)
+        })
+    }
+
+    /// Write a column's dates in ISO 8601 format, like `2021-03-04` or `2021-03-04T12:30:00`
+    ///
+    /// Values that are not dates are written unchanged.
+    #[cfg(feature = "dates")]
+    pub fn iso_date(self, column_header: &str, source: &str) -> Self {
+        use chrono::Timelike;
+
+        let source = source.to_owned();
+
+        self.derive(column_header, move |row| match row.get_datetime(&source) {
+            Ok(dt) if dt.num_seconds_from_midnight() == 0 => dt.format("%Y-%m-%d").to_string(),
+            Ok(dt) => dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            Err(_) => row.get(&source).unwrap_or_default().into_owned(),
+        })
+    }
+}
+
+/// Where an exported column's values come from
+enum Output<'a> {
+    Column(&'a Column),
+    Derived(&'a DerivedColumn),
+}
+
+impl<'a> Output<'a> {
+    fn name(&self) -> &'a str {
+        match self {
+            Output::Column(c) => &c.name,
+            Output::Derived(d) => &d.name,
+        }
+    }
+}
+
+/// Quote a field if it contains a delimiter, quote or line break, doubling any quotes
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Join quoted fields into a CSV record, quoting a lone empty field so the line is not blank,
+/// as readers skip blank lines
+fn csv_record<S: AsRef<str>>(fields: &[S]) -> Cow<'static, str> {
+    match fields {
+        [field] if field.as_ref().is_empty() => Cow::Borrowed("\"\""),
+        fields => Cow::Owned(
+            fields
+                .iter()
+                .map(AsRef::as_ref)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    }
+}
+
+impl WorkbookData {
+    /// Write the header row and data rows as CSV
+    ///
+    /// Rows above the header and columns outside the data are not written. Values are
+    /// formatted the same way as `get`.
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.to_csv_with_options(writer, &ExportOptions::default())
+    }
+
+    /// Write CSV, adding or replacing the derived columns in `options`
+    pub fn to_csv_with_options<W: Write>(
+        &self,
+        mut writer: W,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let start = Timer::start();
+
+        let derived = |name: &str| options.derived.iter().find(|d| d.name == name);
+        let outputs: Vec<Output> = self
+            .columns
+            .iter()
+            .map(|c| derived(&c.name).map_or(Output::Column(c), Output::Derived))
+            .chain(
+                options
+                    .derived
+                    .iter()
+                    .filter(|d| self.column_id(&d.name).is_none())
+                    .map(Output::Derived),
+            )
+            .collect();
+
+        let headers: Vec<Cow<str>> = outputs.iter().map(|o| csv_field(o.name())).collect();
+        writeln!(writer, "{}", csv_record(&headers))?;
+
+        let mut fields = Vec::with_capacity(outputs.len());
+        for row in self.first_row..=self.last_row {
+            let row_data = RowData {
+                source: self,
+                row_number: row,
+            };
+
+            fields.clear();
+            fields.extend(outputs.iter().map(|o| {
+                match o {
+                    Output::Column(c) => self
+                        .range
+                        .get_value((row, c.index))
+                        .map(|v| csv_field(&self.cell_text(row, c, v)).into_owned())
+                        .unwrap_or_default(),
+                    Output::Derived(d) => csv_field(&(d.compute)(&row_data)).into_owned(),
+                }
+            }));
+            writeln!(writer, "{}", csv_record(&fields))?;
+        }
+
+        writer.flush()?;
+        self.counters.record_pass(self.data_cells(), start);
+        Ok(())
+    }
+}
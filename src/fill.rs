@@ -0,0 +1,33 @@
+use crate::{cell_is_empty, ColumnId, DataError, WorkbookData};
+
+impl WorkbookData {
+    /// Fill blank cells in a column with the last non-empty value above them
+    ///
+    /// Rows that are entirely empty are left alone, so spacer rows stay blank.
+    pub fn fill_down(&mut self, column_header: &str) -> Result<(), DataError> {
+        let column = self
+            .column_id(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        self.fill_down_id(column);
+        Ok(())
+    }
+
+    pub(crate) fn fill_down_id(&mut self, column: ColumnId) {
+        let index = self.columns[column.0].index;
+        let mut last = None;
+
+        for row in self.first_row..=self.last_row {
+            match self.range.get_value((row, index)) {
+                Some(value) if !cell_is_empty(value) => last = Some(value.clone()),
+                _ => {
+                    if let Some(value) = &last {
+                        if !self.is_row_empty(row) {
+                            self.range.set_value((row, index), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{DataError, RowData, WorkbookData};
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Equals(String),
+    Contains(String),
+    GreaterThan(f64),
+    AtLeast(f64),
+    LessThan(f64),
+    AtMost(f64),
+}
+
+impl Condition {
+    fn matches(&self, row: &RowData, column_header: &str) -> bool {
+        let number = || row.parse::<f64>(column_header).ok();
+
+        match self {
+            Condition::Equals(value) => row.get(column_header).is_ok_and(|v| v == *value),
+            Condition::Contains(value) => row
+                .get(column_header)
+                .is_ok_and(|v| v.contains(value.as_str())),
+            Condition::GreaterThan(limit) => number().is_some_and(|n| n > *limit),
+            Condition::AtLeast(limit) => number().is_some_and(|n| n >= *limit),
+            Condition::LessThan(limit) => number().is_some_and(|n| n < *limit),
+            Condition::AtMost(limit) => number().is_some_and(|n| n <= *limit),
+        }
+    }
+}
+
+/// Conditions on column values that a row must all meet, for `WorkbookData::rows_matching`
+///
+/// Numeric comparisons never match cells that do not parse as a number.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    conditions: Vec<(String, Condition)>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the column's text to equal `value`
+    pub fn equals(self, column_header: &str, value: &str) -> Self {
+        self.with(column_header, Condition::Equals(value.to_owned()))
+    }
+
+    /// Require the column's text to contain `value`
+    pub fn contains(self, column_header: &str, value: &str) -> Self {
+        self.with(column_header, Condition::Contains(value.to_owned()))
+    }
+
+    pub fn greater_than(self, column_header: &str, limit: f64) -> Self {
+        self.with(column_header, Condition::GreaterThan(limit))
+    }
+
+    pub fn at_least(self, column_header: &str, limit: f64) -> Self {
+        self.with(column_header, Condition::AtLeast(limit))
+    }
+
+    pub fn less_than(self, column_header: &str, limit: f64) -> Self {
+        self.with(column_header, Condition::LessThan(limit))
+    }
+
+    pub fn at_most(self, column_header: &str, limit: f64) -> Self {
+        self.with(column_header, Condition::AtMost(limit))
+    }
+
+    fn with(mut self, column_header: &str, condition: Condition) -> Self {
+        self.conditions.push((column_header.to_owned(), condition));
+        self
+    }
+
+    /// Check whether a row meets every condition
+    pub fn matches(&self, row: &RowData) -> bool {
+        self.conditions
+            .iter()
+            .all(|(header, condition)| condition.matches(row, header))
+    }
+}
+
+impl WorkbookData {
+    /// Get the rows whose value in `column_header` satisfies `predicate`
+    pub fn rows_where<F>(
+        &self,
+        column_header: &str,
+        mut predicate: F,
+    ) -> Result<Vec<RowData<'_>>, DataError>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let id = self
+            .column_id(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        Ok(self
+            .rows()
+            .filter(|row| row.get_by_id(id).is_some_and(|v| predicate(&v)))
+            .collect())
+    }
+
+    /// Get the rows that meet every condition of `filter`
+    pub fn rows_matching(&self, filter: &Filter) -> Result<Vec<RowData<'_>>, DataError> {
+        if let Some((header, _)) = filter
+            .conditions
+            .iter()
+            .find(|(header, _)| self.column_id(header).is_none())
+        {
+            return Err(DataError::NoValue(header.clone()));
+        }
+
+        Ok(self.rows().filter(|row| filter.matches(row)).collect())
+    }
+}
@@ -0,0 +1,70 @@
+//! Cell formulas, to tell computed cells from values typed in by hand
+
+use crate::{LoadError, RowData, WorkbookData};
+use calamine::Reader;
+use std::collections::HashMap;
+
+/// Read the formulas of a sheet by absolute (row, column), swapping the two when `transposed`
+pub(crate) fn read_formulas<W>(
+    workbook: &mut W,
+    sheet_name: &str,
+    transposed: bool,
+) -> Result<HashMap<(u32, u32), String>, LoadError>
+where
+    W: Reader,
+    calamine::Error: From<W::Error>,
+{
+    let range = match workbook.worksheet_formula(sheet_name) {
+        Some(range) => range.map_err(calamine::Error::from)?,
+        None => return Ok(HashMap::new()),
+    };
+
+    let (start_row, start_col) = match range.start() {
+        Some(start) => start,
+        None => return Ok(HashMap::new()),
+    };
+
+    Ok(range
+        .cells()
+        .filter(|(_, _, formula)| !formula.is_empty())
+        .map(|(r, c, formula)| {
+            let position = (start_row + r as u32, start_col + c as u32);
+            let position = if transposed {
+                (position.1, position.0)
+            } else {
+                position
+            };
+            let formula = formula.strip_prefix('=').unwrap_or(formula);
+            (position, formula.to_owned())
+        })
+        .collect())
+}
+
+impl WorkbookData {
+    /// Check if any formulas were loaded, which requires `LoadOptions::formulas`
+    pub fn has_formulas(&self) -> bool {
+        !self.formulas.is_empty()
+    }
+
+    /// Get the formula of a cell, without the leading `=`
+    pub fn get_formula(&self, row_number: u32, column_header: &str) -> Option<&str> {
+        let column = &self.columns[self.column_id(column_header)?.0];
+        self.formulas
+            .get(&(row_number, column.index))
+            .map(String::as_str)
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get the formula of a cell, without the leading `=`, if its value is computed
+    ///
+    /// Always `None` unless the data was loaded with `LoadOptions::formulas`.
+    pub fn get_formula(&self, column_header: &str) -> Option<&'a str> {
+        self.source.get_formula(self.row_number, column_header)
+    }
+
+    /// Check if a cell's value is computed by a formula rather than entered by hand
+    pub fn is_formula(&self, column_header: &str) -> bool {
+        self.get_formula(column_header).is_some()
+    }
+}
@@ -0,0 +1,50 @@
+//! A standard trait for mapping rows to project types
+
+use crate::{DataError, OwnedRow, RowData, WorkbookData};
+use std::collections::HashMap;
+
+/// Build a value from one row, for `WorkbookData::rows_as`
+///
+/// Implementations should read cells with `RowData::get` and `RowData::parse`, so errors name
+/// the column and its position like other typed reads. Tuples of `FromRow` types build each
+/// part from the same row, to combine mappings written separately.
+pub trait FromRow: Sized {
+    fn from_row(row: &RowData<'_>) -> Result<Self, DataError>;
+}
+
+impl FromRow for OwnedRow {
+    fn from_row(row: &RowData<'_>) -> Result<Self, DataError> {
+        Ok(row.to_owned_row())
+    }
+}
+
+impl FromRow for HashMap<String, String> {
+    fn from_row(row: &RowData<'_>) -> Result<Self, DataError> {
+        Ok(row.to_map())
+    }
+}
+
+macro_rules! tuple_from_row {
+    ($($name:ident),+) => {
+        impl<$($name: FromRow),+> FromRow for ($($name,)+) {
+            fn from_row(row: &RowData<'_>) -> Result<Self, DataError> {
+                Ok(($($name::from_row(row)?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_row!(A);
+tuple_from_row!(A, B);
+tuple_from_row!(A, B, C);
+tuple_from_row!(A, B, C, D);
+
+impl WorkbookData {
+    /// Convert each data row with `T::from_row`, in order
+    ///
+    /// A row that fails to convert yields its error and iteration continues, so callers can
+    /// stop at the first error with `collect::<Result<Vec<_>, _>>()` or gather them all.
+    pub fn rows_as<T: FromRow>(&self) -> impl Iterator<Item = Result<T, DataError>> + '_ {
+        self.iter_rows().map(|row| T::from_row(&row))
+    }
+}
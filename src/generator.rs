@@ -0,0 +1,241 @@
+//! Randomized in-memory workbooks for fuzzing header detection and benchmarking
+//!
+//! Generation is deterministic for a given seed, so failing cases can be reproduced.
+
+use crate::{DataType, Range};
+
+/// Small deterministic PRNG (SplitMix64), good enough for fixtures
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[low, high)`
+    fn range(&mut self, low: u32, high: u32) -> u32 {
+        low + (self.next_u64() % u64::from(high - low)) as u32
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Int,
+    Float,
+    Bool,
+    Text,
+    DateTime,
+}
+
+impl ColumnKind {
+    const ALL: [ColumnKind; 5] = [
+        ColumnKind::Int,
+        ColumnKind::Float,
+        ColumnKind::Bool,
+        ColumnKind::Text,
+        ColumnKind::DateTime,
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub struct ColumnSpec {
+    pub name: String,
+    pub kind: ColumnKind,
+    /// Probability in `[0, 1]` that a data cell is left empty
+    pub null_rate: f64,
+}
+
+/// Describes the shape of a generated workbook
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    pub rows: u32,
+    pub columns: Vec<ColumnSpec>,
+    /// Title rows above the header, each with a single populated cell
+    pub banner_rows: u32,
+    /// Number of vertical merged regions to simulate in the data
+    pub merged_regions: u32,
+    /// Absolute position of the top-left cell
+    pub origin: (u32, u32),
+}
+
+/// A generated sheet along with what a correct loader should find in it
+#[derive(Debug, Clone)]
+pub struct GeneratedWorkbook {
+    pub range: Range<DataType>,
+    pub header_row: u32,
+    pub headers: Vec<String>,
+    /// Simulated merged regions as absolute `(start, end)` positions. Like Excel reports them,
+    /// only the top-left cell of each region holds a value.
+    pub merged_regions: Vec<((u32, u32), (u32, u32))>,
+}
+
+impl GeneratorConfig {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rows: 10,
+            columns: Vec::new(),
+            banner_rows: 0,
+            merged_regions: 0,
+            origin: (0, 0),
+        }
+    }
+
+    /// Build a config with a random shape: column count and types, null rates and messiness
+    pub fn random(seed: u64) -> Self {
+        let mut rng = Rng(seed);
+
+        let columns = (0..rng.range(1, 12))
+            .map(|i| ColumnSpec {
+                name: format!("Column {}", i + 1),
+                kind: ColumnKind::ALL[rng.range(0, ColumnKind::ALL.len() as u32) as usize],
+                null_rate: if rng.chance(0.5) {
+                    0.
+                } else {
+                    rng.next_f64() * 0.5
+                },
+            })
+            .collect();
+
+        Self {
+            seed,
+            rows: rng.range(0, 200),
+            columns,
+            banner_rows: if rng.chance(0.3) { rng.range(1, 4) } else { 0 },
+            merged_regions: if rng.chance(0.3) { rng.range(1, 4) } else { 0 },
+            origin: (rng.range(0, 5), rng.range(0, 5)),
+        }
+    }
+
+    pub fn rows(mut self, rows: u32) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn column(mut self, name: &str, kind: ColumnKind, null_rate: f64) -> Self {
+        self.columns.push(ColumnSpec {
+            name: name.to_owned(),
+            kind,
+            null_rate,
+        });
+        self
+    }
+
+    pub fn banner_rows(mut self, banner_rows: u32) -> Self {
+        self.banner_rows = banner_rows;
+        self
+    }
+
+    pub fn merged_regions(mut self, merged_regions: u32) -> Self {
+        self.merged_regions = merged_regions;
+        self
+    }
+
+    pub fn origin(mut self, row: u32, col: u32) -> Self {
+        self.origin = (row, col);
+        self
+    }
+
+    pub fn generate(&self) -> GeneratedWorkbook {
+        let mut rng = Rng(self.seed ^ 0x5EED);
+
+        let (top, left) = self.origin;
+        let width = self.columns.len().max(1) as u32;
+
+        // A banner in a single-column sheet is indistinguishable from a header
+        let banner_rows = if width > 1 { self.banner_rows } else { 0 };
+        let header_row = top + banner_rows;
+        let last_row = header_row + self.rows;
+
+        let mut range = Range::new((top, left), (last_row, left + width - 1));
+
+        for r in 0..banner_rows {
+            let col = left + rng.range(0, width);
+            range.set_value(
+                (top + r, col),
+                DataType::String(format!("Report {}", r + 1)),
+            );
+        }
+
+        let headers: Vec<String> = self.columns.iter().map(|c| c.name.clone()).collect();
+
+        for (c, spec) in self.columns.iter().enumerate() {
+            let col = left + c as u32;
+
+            range.set_value((header_row, col), DataType::String(spec.name.clone()));
+
+            for row in header_row + 1..=last_row {
+                if !rng.chance(spec.null_rate) {
+                    range.set_value((row, col), random_value(&mut rng, spec.kind));
+                }
+            }
+        }
+
+        let mut merged_regions: Vec<((u32, u32), (u32, u32))> = Vec::new();
+
+        if self.rows >= 2 && !self.columns.is_empty() {
+            for _ in 0..self.merged_regions {
+                let col = left + rng.range(0, width);
+                let start = rng.range(header_row + 1, last_row);
+                let end = rng.range(start + 1, last_row + 1);
+
+                let overlaps = merged_regions
+                    .iter()
+                    .any(|&((s, c), (e, _))| c == col && start <= e && s <= end);
+                if overlaps {
+                    continue;
+                }
+
+                let kind = self.columns[(col - left) as usize].kind;
+                range.set_value((start, col), random_value(&mut rng, kind));
+                for row in start + 1..=end {
+                    range.set_value((row, col), DataType::Empty);
+                }
+
+                merged_regions.push(((start, col), (end, col)));
+            }
+        }
+
+        GeneratedWorkbook {
+            range,
+            header_row,
+            headers,
+            merged_regions,
+        }
+    }
+}
+
+fn random_value(rng: &mut Rng, kind: ColumnKind) -> DataType {
+    match kind {
+        ColumnKind::Int => DataType::Int(rng.range(0, 100_000) as i64 - 50_000),
+        ColumnKind::Float => DataType::Float((rng.next_f64() - 0.5) * 1e6),
+        ColumnKind::Bool => DataType::Bool(rng.chance(0.5)),
+        ColumnKind::Text => {
+            const WORDS: [&str; 8] = [
+                "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel",
+            ];
+            let words = rng.range(1, 4);
+            let text: Vec<_> = (0..words)
+                .map(|_| WORDS[rng.range(0, WORDS.len() as u32) as usize])
+                .collect();
+            DataType::String(text.join(" "))
+        }
+        // Excel serials between 2000-01-01 and 2030-01-01
+        ColumnKind::DateTime => DataType::DateTime(f64::from(rng.range(36526, 47484))),
+    }
+}
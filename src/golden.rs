@@ -0,0 +1,226 @@
+//! Comparing data against a checked-in "golden" workbook, for regression tests
+//!
+//! `check_matches_golden` collects every difference; `assert_matches_golden` panics with them.
+
+use crate::WorkbookData;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of differing cells listed in a `GoldenMismatch` message
+const MAX_LISTED_CELLS: usize = 20;
+
+/// A cell whose value differs from the golden workbook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellMismatch {
+    /// Row number in the golden workbook
+    pub row: u32,
+    pub header: String,
+    /// Where the column is in the golden workbook, like `"Column AJ"`
+    pub position: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for CellMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Row numbers are zero-based; show them as sheet rows
+        write!(
+            f,
+            "Row {}, {} / '{}': expected '{}', found '{}'",
+            self.row + 1,
+            self.position,
+            self.header,
+            self.expected,
+            self.found
+        )
+    }
+}
+
+/// Every difference between some data and a golden table
+#[derive(Debug, Default, thiserror::Error)]
+pub struct GoldenMismatch {
+    pub missing: Vec<String>,
+    pub unexpected: Vec<String>,
+    /// Expected and found number of data rows, if they differ
+    pub row_count: Option<(usize, usize)>,
+    pub cells: Vec<CellMismatch>,
+}
+
+impl GoldenMismatch {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty()
+            && self.unexpected.is_empty()
+            && self.row_count.is_none()
+            && self.cells.is_empty()
+    }
+
+    /// Write one indented line per difference
+    fn fmt_details(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.missing.is_empty() {
+            write!(f, "\n  Missing columns: '{}'", self.missing.join("', '"))?;
+        }
+
+        if !self.unexpected.is_empty() {
+            write!(
+                f,
+                "\n  Unexpected columns: '{}'",
+                self.unexpected.join("', '")
+            )?;
+        }
+
+        if let Some((expected, found)) = self.row_count {
+            write!(f, "\n  Expected {} rows, found {}", expected, found)?;
+        }
+
+        for cell in self.cells.iter().take(MAX_LISTED_CELLS) {
+            write!(f, "\n  {}", cell)?;
+        }
+
+        if self.cells.len() > MAX_LISTED_CELLS {
+            write!(
+                f,
+                "\n  and {} more differing cells",
+                self.cells.len() - MAX_LISTED_CELLS
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Data does not match the golden data")?;
+        self.fmt_details(f)
+    }
+}
+
+/// Failure to match a golden workbook file
+#[derive(Debug, thiserror::Error)]
+pub enum GoldenError {
+    Load {
+        path: PathBuf,
+        message: String,
+    },
+
+    Mismatch {
+        path: PathBuf,
+        mismatch: GoldenMismatch,
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Load { path, message } => write!(
+                f,
+                "Could not load golden file '{}': {}",
+                path.display(),
+                message
+            ),
+            GoldenError::Mismatch { path, mismatch } => {
+                write!(f, "Data does not match golden file '{}'", path.display())?;
+                mismatch.fmt_details(f)
+            }
+        }
+    }
+}
+
+/// Check if two cell strings match, comparing numbers within `tolerance`
+fn values_match(expected: &str, found: &str, tolerance: f64) -> bool {
+    if expected == found {
+        return true;
+    }
+
+    match (expected.trim().parse::<f64>(), found.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => (a - b).abs() <= tolerance,
+        _ => false,
+    }
+}
+
+/// Compare data to an already loaded golden table
+///
+/// Columns are matched by header and rows by position, so row offsets may differ. Numbers
+/// (including numbers stored as text) match if they differ by at most `tolerance`.
+pub fn check_matches(
+    data: &WorkbookData,
+    golden: &WorkbookData,
+    tolerance: f64,
+) -> Result<(), GoldenMismatch> {
+    let expected_rows = golden.rows().count();
+    let found_rows = data.rows().count();
+
+    let mut mismatch = GoldenMismatch {
+        missing: golden
+            .headers()
+            .filter(|h| data.column_id(h).is_none())
+            .map(String::from)
+            .collect(),
+        unexpected: data
+            .headers()
+            .filter(|h| golden.column_id(h).is_none())
+            .map(String::from)
+            .collect(),
+        row_count: Some((expected_rows, found_rows)).filter(|(e, f)| e != f),
+        cells: Vec::new(),
+    };
+
+    let shared: Vec<&str> = golden
+        .headers()
+        .filter(|h| data.column_id(h).is_some())
+        .collect();
+
+    for (expected_row, found_row) in golden.rows().zip(data.rows()) {
+        for &header in &shared {
+            let expected = expected_row.get(header).unwrap_or_default();
+            let found = found_row.get(header).unwrap_or_default();
+
+            if !values_match(&expected, &found, tolerance) {
+                mismatch.cells.push(CellMismatch {
+                    row: expected_row.number(),
+                    header: header.into(),
+                    position: golden.column_position(header),
+                    expected: expected.into_owned(),
+                    found: found.into_owned(),
+                });
+            }
+        }
+    }
+
+    if mismatch.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatch)
+    }
+}
+
+/// Compare data to the golden workbook at `path`, loaded with default options
+pub fn check_matches_golden<P: AsRef<Path>>(
+    data: &WorkbookData,
+    path: P,
+    tolerance: f64,
+) -> Result<(), GoldenError> {
+    let path = path.as_ref();
+
+    let golden = WorkbookData::from_path(path).map_err(|err| GoldenError::Load {
+        path: path.to_owned(),
+        message: err.to_string(),
+    })?;
+
+    check_matches(data, &golden, tolerance).map_err(|mismatch| GoldenError::Mismatch {
+        path: path.to_owned(),
+        mismatch,
+    })
+}
+
+pub fn assert_matches(data: &WorkbookData, golden: &WorkbookData, tolerance: f64) {
+    if let Err(err) = check_matches(data, golden, tolerance) {
+        panic!("{}", err);
+    }
+}
+
+pub fn assert_matches_golden<P: AsRef<Path>>(data: &WorkbookData, path: P, tolerance: f64) {
+    if let Err(err) = check_matches_golden(data, path, tolerance) {
+        panic!("{}", err);
+    }
+}
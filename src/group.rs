@@ -0,0 +1,153 @@
+use crate::{DataError, RowData, RowsIterator};
+use std::collections::HashMap;
+use std::iter::Sum;
+use std::str::FromStr;
+use std::vec;
+
+/// Rows grouped by the value of a key column, from `RowsIterator::group_by_column`
+///
+/// Yields `(key, rows)` pairs with groups in order of each key's first row.
+pub struct Groups<'a> {
+    groups: vec::IntoIter<(String, Vec<RowData<'a>>)>,
+}
+
+impl<'a> Groups<'a> {
+    /// Count the rows in each group
+    pub fn counts(self) -> Vec<(String, usize)> {
+        self.map(|(key, rows)| (key, rows.len())).collect()
+    }
+
+    /// Sum a parsed column in each group, skipping empty cells
+    pub fn sums<T>(self, column_header: &str) -> Result<Vec<(String, T)>, DataError>
+    where
+        T: FromStr + Sum<T>,
+    {
+        self.map(|(key, rows)| Ok((key, sum_column(&rows, column_header)?)))
+            .collect()
+    }
+}
+
+impl<'a> Iterator for Groups<'a> {
+    type Item = (String, Vec<RowData<'a>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.groups.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.groups.size_hint()
+    }
+}
+
+/// Sum a parsed column over some rows, skipping empty cells
+pub fn sum_column<T>(rows: &[RowData<'_>], column_header: &str) -> Result<T, DataError>
+where
+    T: FromStr + Sum<T>,
+{
+    if rows
+        .first()
+        .is_some_and(|row| row.source.column_id(column_header).is_none())
+    {
+        return Err(DataError::NoValue(column_header.into()));
+    }
+
+    rows.iter()
+        .filter(|row| !row.is_cell_empty(column_header))
+        .map(|row| row.parse::<T>(column_header))
+        .sum()
+}
+
+impl<'a> RowsIterator<'a> {
+    /// Group the remaining rows by the value in a key column
+    ///
+    /// Keys are the text `get` returns, so the column's rounding and the number text policy
+    /// apply. Rows with an empty key are grouped under `""`.
+    pub fn group_by_column(self, column_header: &str) -> Result<Groups<'a>, DataError> {
+        let source = self.source;
+        let id = source
+            .column_id(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut groups: Vec<(String, Vec<RowData<'a>>)> = Vec::new();
+
+        for row in self {
+            let key = source.get_by_id(row.row_number, id).unwrap_or_default();
+
+            match positions.get(key.as_ref()) {
+                Some(&i) => groups[i].1.push(row),
+                None => {
+                    positions.insert(key.clone().into_owned(), groups.len());
+                    groups.push((key.into_owned(), vec![row]));
+                }
+            }
+        }
+
+        Ok(Groups {
+            groups: groups.into_iter(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_range, DataType, KeepPolicy, LoadOptions, Range, WorkbookData};
+
+    fn prices() -> WorkbookData {
+        let prices = [
+            DataType::Float(1.001),
+            DataType::Float(2.5),
+            DataType::Float(1.0),
+            DataType::Empty,
+        ];
+        let mut range = Range::new((0, 0), (4, 1));
+        range.set_value((0, 0), DataType::String("Price".into()));
+        range.set_value((0, 1), DataType::String("Qty".into()));
+        for (i, price) in prices.iter().enumerate() {
+            range.set_value((i as u32 + 1, 0), price.clone());
+            range.set_value((i as u32 + 1, 1), DataType::Int(i as i64 + 1));
+        }
+        from_range(range, &LoadOptions::new().round("Price", 2)).unwrap()
+    }
+
+    #[test]
+    fn groups_by_the_text_get_returns() {
+        let data = prices();
+        let counts = data.iter_rows().group_by_column("Price").unwrap().counts();
+        assert_eq!(counts[0].1, 2);
+        assert_eq!(counts[0].0, data.get(1, "Price").unwrap());
+        assert_eq!(counts[0].0, data.get(3, "Price").unwrap());
+        assert_eq!(counts[2], (String::new(), 1));
+
+        let sums: Vec<(String, i64)> = data
+            .iter_rows()
+            .group_by_column("Price")
+            .unwrap()
+            .sums("Qty")
+            .unwrap();
+        assert_eq!(sums[0].1, 4);
+    }
+
+    #[test]
+    fn duplicates_and_dedup_share_keys() {
+        let data = prices();
+        let duplicates = data.duplicates_by("Price").unwrap();
+        assert_eq!(duplicates.len(), 1);
+        let rows: Vec<_> = duplicates[0].1.iter().map(|r| r.number()).collect();
+        assert_eq!(rows, [1, 3]);
+
+        let kept: Vec<_> = data
+            .dedup_by("Price", KeepPolicy::First)
+            .unwrap()
+            .iter()
+            .map(|r| r.number())
+            .collect();
+        assert_eq!(kept, [1, 2, 4]);
+    }
+
+    #[test]
+    fn rejects_unknown_key_columns() {
+        let data = prices();
+        assert!(data.iter_rows().group_by_column("Cost").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Hyperlink targets, which are often the real data behind "click here" cells
+
+use crate::coords::{self, Span};
+use crate::xlsx::{self, XlsxPackage};
+use crate::{LoadError, RowData, WorkbookData};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+/// A hyperlink on a cell or region of a sheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// Absolute ((first row, first column), (last row, last column))
+    pub region: Span,
+    /// The URL, or `#` and a location like `#Sheet2!A1` for links within the workbook
+    pub target: String,
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// List the hyperlinks of a sheet, resolving their relationship ids to URLs
+    pub fn hyperlinks(&mut self, sheet_name: &str) -> Result<Vec<Hyperlink>, LoadError> {
+        let path = match self.sheet_path(sheet_name)? {
+            Some(path) => path,
+            None => return Ok(Vec::new()),
+        };
+        let xml = self.read_part(&path)?.unwrap_or_default();
+        let rels = self.relationships(&path)?;
+
+        let mut links = Vec::new();
+        xlsx::for_each_element(&xml, |e| {
+            if e.local_name() != b"hyperlink" {
+                return;
+            }
+
+            let region = match xlsx::attr(e, b"ref")
+                .as_deref()
+                .and_then(coords::parse_range)
+            {
+                Some(region) => region,
+                None => return,
+            };
+
+            let url = xlsx::attr(e, b"id")
+                .and_then(|id| rels.iter().find(|r| r.id == id))
+                .map(|r| r.target.clone());
+            let location = xlsx::attr(e, b"location").map(|l| format!("#{}", l));
+
+            let target = match (url, location) {
+                (Some(url), Some(location)) => url + &location,
+                (Some(target), None) | (None, Some(target)) => target,
+                (None, None) => return,
+            };
+
+            links.push(Hyperlink { region, target });
+        })?;
+        Ok(links)
+    }
+}
+
+/// Read the hyperlinks of a sheet by absolute (row, column), swapping the two when
+/// `transposed`, limited to `bounds` so a link on a whole column does not cover every row
+pub(crate) fn read_hyperlinks<R: Read + Seek>(
+    package: &mut XlsxPackage<R>,
+    sheet_name: &str,
+    bounds: Span,
+    transposed: bool,
+) -> Result<HashMap<(u32, u32), String>, LoadError> {
+    let ((top, left), (bottom, right)) = bounds;
+    let mut targets = HashMap::new();
+
+    for link in package.hyperlinks(sheet_name)? {
+        let ((r0, c0), (r1, c1)) = link.region;
+        for row in r0.max(top)..=r1.min(bottom) {
+            for col in c0.max(left)..=c1.min(right) {
+                let position = if transposed { (col, row) } else { (row, col) };
+                targets.insert(position, link.target.clone());
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+/// Get the URL of a `HYPERLINK("url", "text")` formula, if it is given as a literal
+fn formula_target(formula: &str) -> Option<String> {
+    let args = formula.trim();
+    let args = args
+        .get(..10)
+        .filter(|name| name.eq_ignore_ascii_case("HYPERLINK("))
+        .map(|_| args[10..].trim_start())?;
+
+    let mut chars = args.strip_prefix('"')?.chars().peekable();
+    let mut target = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                chars.next();
+                target.push('"');
+            }
+            '"' => return Some(target),
+            c => target.push(c),
+        }
+    }
+    None
+}
+
+impl WorkbookData {
+    /// Get the hyperlink target of a cell
+    pub fn get_hyperlink(&self, row_number: u32, column_header: &str) -> Option<Cow<'_, str>> {
+        let column = &self.columns[self.column_id(column_header)?.0];
+
+        match self.hyperlinks.get(&(row_number, column.index)) {
+            Some(target) => Some(Cow::Borrowed(target)),
+            None => self
+                .get_formula(row_number, column_header)
+                .and_then(formula_target)
+                .map(Cow::Owned),
+        }
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get the target of a cell's hyperlink, rather than the text it displays
+    ///
+    /// Needs `LoadOptions::hyperlinks` (xlsx only). Links made with the `HYPERLINK` function
+    /// are also found when loading with `LoadOptions::formulas`.
+    pub fn get_hyperlink(&self, column_header: &str) -> Option<Cow<'a, str>> {
+        self.source.get_hyperlink(self.row_number, column_header)
+    }
+}
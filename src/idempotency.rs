@@ -0,0 +1,63 @@
+use crate::manifest::{to_hex, RECORD_SEPARATOR, UNIT_SEPARATOR};
+use crate::{DataError, RowData};
+use sha2::{Digest, Sha256};
+
+impl<'a> RowData<'a> {
+    /// Get a stable key for this row, so re-running an import can skip records already written
+    ///
+    /// The key is a SHA-256 hex digest of the source fingerprint, sheet name and the values (as
+    /// returned by `get`) of `columns`, in the order given, including every column of a
+    /// repeated header. Pick columns that identify the record. Data not loaded from a file has no fingerprint unless one is set with
+    /// `WorkbookData::set_fingerprint`.
+    pub fn idempotency_key(&self, columns: &[&str]) -> Result<String, DataError> {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.source.fingerprint().unwrap_or_default().as_bytes());
+        hasher.update(UNIT_SEPARATOR);
+        hasher.update(self.source.sheet_name().unwrap_or_default().as_bytes());
+        hasher.update(RECORD_SEPARATOR);
+
+        for &header in columns {
+            let mut values = self.iter().filter(|(h, _)| *h == header).peekable();
+            if values.peek().is_none() {
+                return Err(DataError::NoValue(header.into()));
+            }
+            for (_, value) in values {
+                hasher.update(header.as_bytes());
+                hasher.update(UNIT_SEPARATOR);
+                hasher.update(value.as_bytes());
+                hasher.update(UNIT_SEPARATOR);
+            }
+        }
+
+        Ok(to_hex(&hasher.finalize()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_range, DataType, LoadOptions, Range};
+
+    #[test]
+    fn keys_cover_every_column_of_a_repeated_header() {
+        let mut range = Range::new((0, 0), (2, 1));
+        for (row, cells) in [["Id", "Id"], ["1", "a"], ["1", "b"]].iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                range.set_value((row as u32, col as u32), DataType::String(cell.to_string()));
+            }
+        }
+        let data = from_range(range, &LoadOptions::new()).unwrap();
+        let keys: Vec<_> = data
+            .rows()
+            .map(|row| row.idempotency_key(&["Id"]).unwrap())
+            .collect();
+        assert_ne!(keys[0], keys[1]);
+
+        let row = data.rows().next().unwrap();
+        assert!(row.idempotency_key(&["Missing"]).is_err());
+        assert_ne!(
+            data.manifest().column_hashes[0],
+            data.manifest().column_hashes[1]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{cell_is_empty, cell_to_str, DataError, RowData, WorkbookData};
+use std::collections::HashMap;
+
+/// Which row an index keeps when a key appears more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    #[default]
+    First,
+    Last,
+    /// Fail with `DataError::DuplicateKey`
+    Error,
+}
+
+/// Hash lookup of rows by the value of a key column, from `WorkbookData::index_by`
+#[derive(Debug, Clone)]
+pub struct RowIndex<'a> {
+    source: &'a WorkbookData,
+    rows: HashMap<String, u32>,
+}
+
+impl<'a> RowIndex<'a> {
+    /// Get the row with this key
+    pub fn get_row(&self, key: &str) -> Option<RowData<'a>> {
+        self.rows.get(key).map(|&row_number| RowData {
+            source: self.source,
+            row_number,
+        })
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.rows.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.rows.keys().map(|k| k.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl WorkbookData {
+    /// Index rows by the value in a key column, keeping the first row for repeated keys
+    ///
+    /// Rows with an empty key are not indexed.
+    pub fn index_by(&self, column_header: &str) -> Result<RowIndex<'_>, DataError> {
+        self.index_by_with_policy(column_header, DuplicateKeyPolicy::default())
+    }
+
+    pub fn index_by_with_policy(
+        &self,
+        column_header: &str,
+        policy: DuplicateKeyPolicy,
+    ) -> Result<RowIndex<'_>, DataError> {
+        let id = self
+            .column_id(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+
+        let mut rows: HashMap<String, u32> = HashMap::new();
+
+        for row_number in self.first_row..=self.last_row {
+            let key = match self.get_cell_by_id(row_number, id) {
+                Some(value) if !cell_is_empty(value) => cell_to_str(value).into_owned(),
+                _ => continue,
+            };
+
+            match (rows.get(&key), policy) {
+                (None, _) | (Some(_), DuplicateKeyPolicy::Last) => {
+                    rows.insert(key, row_number);
+                }
+                (Some(_), DuplicateKeyPolicy::First) => {}
+                (Some(&first), DuplicateKeyPolicy::Error) => {
+                    return Err(DataError::DuplicateKey {
+                        key: column_header.into(),
+                        value: key,
+                        rows: (first, row_number),
+                        position: self.column_position(column_header),
+                    });
+                }
+            }
+        }
+
+        Ok(RowIndex { source: self, rows })
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{cell_is_empty, cell_to_str, ColumnType, DataType, Schema, WorkbookData};
+use std::fmt;
+
+/// Maximum number of offending values kept per column in an `InferredColumn`
+const MAX_EXAMPLES: usize = 5;
+
+/// The inferred type of one column, from `WorkbookData::infer_schema`
+#[derive(Debug, Clone)]
+pub struct InferredColumn {
+    pub name: String,
+    /// Where the column is in the sheet, like `"Column AJ"`
+    pub position: String,
+    /// The type most values can be read as (`String` for empty columns)
+    pub column_type: ColumnType,
+    /// Number of non-empty values
+    pub values: usize,
+    /// Number of empty cells
+    pub nulls: usize,
+    /// Number of values that cannot be read as `column_type`
+    pub offending: usize,
+    /// The first few values that cannot be read as `column_type`
+    pub examples: Vec<String>,
+}
+
+impl InferredColumn {
+    /// Check whether every non-empty value can be read as the inferred type
+    pub fn is_uniform(&self) -> bool {
+        self.offending == 0
+    }
+}
+
+impl fmt::Display for InferredColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} / '{}': {} ({} values, {} empty",
+            self.position, self.name, self.column_type, self.values, self.nulls
+        )?;
+
+        if self.offending > 0 {
+            write!(
+                f,
+                ", {} not {}: '{}'",
+                self.offending,
+                self.column_type,
+                self.examples.join("', '")
+            )?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+/// Inferred column types of a sheet, for previewing data before importing it
+#[derive(Debug, Clone)]
+pub struct InferredSchema {
+    pub columns: Vec<InferredColumn>,
+}
+
+impl InferredSchema {
+    pub fn column(&self, name: &str) -> Option<&InferredColumn> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Build a `Schema` requiring every column with its inferred type
+    pub fn to_schema(&self) -> Schema {
+        self.columns.iter().fold(Schema::new(), |schema, c| {
+            schema.column(&c.name, c.column_type)
+        })
+    }
+}
+
+impl fmt::Display for InferredSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", column)?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the type a cell can be read as, looking inside text for numbers, booleans and dates
+///
+/// Returns `None` for error values.
+fn infer_cell(value: &DataType) -> Option<ColumnType> {
+    match value {
+        DataType::String(s) => {
+            let s = s.trim();
+            Some(if s.parse::<i64>().is_ok() {
+                ColumnType::Integer
+            } else if s.parse::<f64>().is_ok() {
+                ColumnType::Float
+            } else if s.parse::<bool>().is_ok() {
+                ColumnType::Bool
+            } else if is_date_text(value) {
+                ColumnType::DateTime
+            } else {
+                ColumnType::String
+            })
+        }
+        value => ColumnType::of(value),
+    }
+}
+
+#[cfg(feature = "dates")]
+fn is_date_text(value: &DataType) -> bool {
+    crate::dates::parse_datetime(value).is_some()
+}
+
+#[cfg(not(feature = "dates"))]
+fn is_date_text(_value: &DataType) -> bool {
+    false
+}
+
+/// Check if values of type `found` can be read as `expected`
+fn compatible(expected: ColumnType, found: ColumnType) -> bool {
+    expected == found
+        || expected == ColumnType::String
+        || (expected == ColumnType::Float && found == ColumnType::Integer)
+}
+
+/// Candidate types narrower than `String`
+const NARROW_TYPES: [ColumnType; 4] = [
+    ColumnType::Integer,
+    ColumnType::Float,
+    ColumnType::Bool,
+    ColumnType::DateTime,
+];
+
+impl WorkbookData {
+    /// Infer the dominant type of each column, counting empty cells and collecting examples
+    /// of values that do not fit
+    ///
+    /// Integers count towards a float column, and text holding numbers, booleans or (with the
+    /// `dates` feature) dates counts as that type.
+    pub fn infer_schema(&self) -> InferredSchema {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut types = Vec::new();
+                let mut nulls = 0;
+
+                for row in self.first_row..=self.last_row {
+                    match self.range.get_value((row, column.index)) {
+                        Some(value) if !cell_is_empty(value) => {
+                            types.push((infer_cell(value), value))
+                        }
+                        _ => nulls += 1,
+                    }
+                }
+
+                let count = |t: ColumnType| {
+                    types
+                        .iter()
+                        .filter(|(found, _)| found.is_some_and(|found| compatible(t, found)))
+                        .count()
+                };
+
+                // Pick the type fitting most values if it fits a majority, preferring integers
+                // over floats on a tie; otherwise fall back to `String`, which fits everything
+                let column_type = NARROW_TYPES
+                    .iter()
+                    .copied()
+                    .map(|t| (t, count(t)))
+                    .filter(|&(_, n)| n > 0 && n * 2 > types.len())
+                    .max_by_key(|&(t, n)| (n, t == ColumnType::Integer))
+                    .map_or(ColumnType::String, |(t, _)| t);
+
+                let offenders: Vec<&DataType> = types
+                    .iter()
+                    .filter(|(found, _)| !found.is_some_and(|found| compatible(column_type, found)))
+                    .map(|&(_, value)| value)
+                    .collect();
+
+                InferredColumn {
+                    name: column.name.clone(),
+                    position: self.column_position(&column.name),
+                    column_type,
+                    values: types.len(),
+                    nulls,
+                    offending: offenders.len(),
+                    examples: offenders
+                        .iter()
+                        .take(MAX_EXAMPLES)
+                        .map(|v| cell_to_str(v).into_owned())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        InferredSchema { columns }
+    }
+}
@@ -0,0 +1,269 @@
+//! A quick look at a workbook's sheets, for picking one before a full load
+
+use crate::coords::Span;
+use crate::stream::{RawCell, SharedStrings, SheetReader, SHARED_STRINGS_REL};
+use crate::xlsx::{self, XlsxPackage};
+use crate::{
+    cell_is_empty, cell_to_str, DataType, HeaderDetector, HeuristicDetector, LoadError, Range,
+    SheetVisibility,
+};
+use calamine::{open_workbook_auto, Cell, Reader};
+use std::io::{BufRead, Read, Seek};
+use std::path::Path;
+
+/// Rows read from the top of each sheet when looking for headers
+const PREVIEW_ROWS: usize = 25;
+
+/// A sheet as seen by `inspect`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetOverview {
+    pub name: String,
+    pub visibility: SheetVisibility,
+    /// Rows and columns of the used range, including any rows above the header
+    ///
+    /// For xlsx files this is the extent the sheet declares, which may include formatted but
+    /// empty cells. `None` if it is not known without reading the whole sheet, as for
+    /// delimited files.
+    pub dimensions: Option<(u32, u32)>,
+    /// Rows near the top of the sheet that look like headers, the one header detection would
+    /// pick first
+    pub header_candidates: Vec<HeaderCandidate>,
+}
+
+/// A row that could be a sheet's header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderCandidate {
+    /// Absolute row number
+    pub row: u32,
+    /// Absolute column of the first header
+    pub column: u32,
+    pub headers: Vec<String>,
+}
+
+/// List the sheets of a workbook with their sizes and likely header rows, reading only the
+/// top of each sheet
+///
+/// xlsx sheets and delimited files are read only as far as needed. Other formats are read in
+/// full, as calamine has no partial reads for them.
+pub fn inspect<P: AsRef<Path>>(path: P) -> Result<Vec<SheetOverview>, LoadError> {
+    let path = path.as_ref();
+
+    #[cfg(feature = "csv")]
+    if let Some(delimiter) = crate::delimited::delimiter(path) {
+        let preview = crate::delimited::read_preview(path, delimiter, PREVIEW_ROWS)?;
+        return Ok(vec![SheetOverview {
+            name: crate::delimited::sheet_name(path),
+            visibility: SheetVisibility::Visible,
+            dimensions: None,
+            header_candidates: header_candidates(&preview),
+        }]);
+    }
+
+    if xlsx::is_xlsx_path(path) {
+        return XlsxPackage::open(path)?.inspect();
+    }
+
+    let mut workbook = open_workbook_auto(path)?;
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names().to_owned() {
+        let range = match workbook.worksheet_range(&name) {
+            Some(range) => range?,
+            None => continue,
+        };
+
+        let preview = match (range.start(), range.end()) {
+            (Some((top, left)), Some((bottom, right))) => {
+                let last = bottom.min(top + PREVIEW_ROWS as u32 - 1);
+                range.range((top, left), (last, right))
+            }
+            _ => Range::empty(),
+        };
+
+        sheets.push(SheetOverview {
+            name,
+            visibility: SheetVisibility::Visible,
+            dimensions: Some((range.height() as u32, range.width() as u32)),
+            header_candidates: header_candidates(&preview),
+        });
+    }
+    Ok(sheets)
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// Inspect each sheet from its declared dimension and first rows
+    fn inspect(&mut self) -> Result<Vec<SheetOverview>, LoadError> {
+        let mut previews = Vec::new();
+        let mut strings_needed = 0;
+
+        for sheet in self.sheets()? {
+            let (dimension, cells) = match self.part_reader(&sheet.path)? {
+                Some(xml) => read_preview(xml)?,
+                None => (None, Vec::new()),
+            };
+            strings_needed = cells
+                .iter()
+                .filter(|c| c.kind.as_deref() == Some("s"))
+                .filter_map(|c| c.value.trim().parse::<usize>().ok())
+                .map(|i| i + 1)
+                .fold(strings_needed, usize::max);
+            previews.push((sheet, dimension, cells));
+        }
+
+        let strings = self.shared_strings(strings_needed)?;
+
+        let mut sheets = Vec::new();
+        for (sheet, dimension, raw) in previews {
+            let mut cells = Vec::new();
+            for cell in raw {
+                let value = cell.to_data_type(&strings)?;
+                if !cell_is_empty(&value) {
+                    cells.push(Cell::new(cell.position, value));
+                }
+            }
+            cells.sort_by_key(|c| c.get_position());
+
+            sheets.push(SheetOverview {
+                name: sheet.name,
+                visibility: sheet.visibility,
+                dimensions: dimension.map(|((r0, c0), (r1, c1))| (r1 - r0 + 1, c1 - c0 + 1)),
+                header_candidates: header_candidates(&Range::from_sparse(cells)),
+            });
+        }
+        Ok(sheets)
+    }
+
+    /// Read the first `count` shared strings, stopping there to skip the rest of a large table
+    fn shared_strings(&mut self, count: usize) -> Result<SharedStrings, LoadError> {
+        if count == 0 {
+            return Ok(SharedStrings::empty());
+        }
+
+        let path = self
+            .relationships("xl/workbook.xml")?
+            .into_iter()
+            .find(|r| r.kind.ends_with(SHARED_STRINGS_REL))
+            .map_or_else(|| "xl/sharedStrings.xml".to_owned(), |r| r.target);
+        match self.part_reader(&path)? {
+            Some(xml) => SharedStrings::read(xml, count, None),
+            None => Ok(SharedStrings::empty()),
+        }
+    }
+}
+
+/// Read a sheet's declared dimension and the cells of its first `PREVIEW_ROWS` rows
+fn read_preview<B: BufRead>(xml: B) -> Result<(Option<Span>, Vec<RawCell>), LoadError> {
+    let mut sheet = SheetReader::new(xml);
+    let mut cells = Vec::new();
+
+    for _ in 0..PREVIEW_ROWS {
+        match sheet.next_row()? {
+            Some((_, row)) => cells.extend(row),
+            None => break,
+        }
+    }
+    Ok((sheet.dimension, cells))
+}
+
+/// Find rows that look like headers: at least half full, all text, and without repeats
+///
+/// The row the default header detection picks comes first, whether or not it looks like one.
+fn header_candidates(preview: &Range<DataType>) -> Vec<HeaderCandidate> {
+    let (start_row, start_col) = match preview.start() {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    let min_cells = preview.width().div_ceil(2);
+    let detected = HeuristicDetector::new().detect(preview);
+
+    let mut candidates = Vec::new();
+    for (i, cells) in preview.rows().enumerate() {
+        let row = start_row + i as u32;
+        let filled: Vec<&DataType> = cells.iter().filter(|c| !cell_is_empty(c)).collect();
+
+        let mut texts: Vec<&str> = filled.iter().filter_map(|c| c.get_string()).collect();
+        texts.sort_unstable();
+        texts.dedup();
+        let looks_like_header =
+            !filled.is_empty() && filled.len() >= min_cells && texts.len() == filled.len();
+
+        if looks_like_header || detected == Some(row) {
+            let first = cells.iter().position(|c| !cell_is_empty(c)).unwrap_or(0);
+            let last = cells.iter().rposition(|c| !cell_is_empty(c)).unwrap_or(0);
+            let headers = cells[first..=last]
+                .iter()
+                .map(|c| cell_to_str(c).into_owned())
+                .collect();
+
+            let candidate = HeaderCandidate {
+                row,
+                column: start_col + first as u32,
+                headers,
+            };
+            if detected == Some(row) {
+                candidates.insert(0, candidate);
+            } else {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestXlsx;
+
+    #[test]
+    fn lists_sheets_with_their_header_candidates() {
+        let path = TestXlsx::new()
+            .sheet(
+                "Report",
+                &[
+                    &["Quarterly report", "", ""],
+                    &["", "", ""],
+                    &["Name", "Region", "Sales"],
+                    &["Ann", "North", "10"],
+                    &["Bob", "South", "12"],
+                ],
+            )
+            .sheet("Empty", &[])
+            .write();
+        let sheets = inspect(&path).unwrap();
+
+        let names: Vec<_> = sheets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Report", "Empty"]);
+        assert_eq!(sheets[0].visibility, SheetVisibility::Visible);
+
+        let detected = &sheets[0].header_candidates[0];
+        assert_eq!(detected.row, 2);
+        assert_eq!(detected.column, 0);
+        assert_eq!(detected.headers, ["Name", "Region", "Sales"]);
+        assert!(sheets[1].header_candidates.is_empty());
+    }
+
+    #[test]
+    fn lists_only_all_text_rows_without_repeats() {
+        let preview = Range::from_sparse(vec![
+            Cell::new((0, 0), DataType::String("Id".into())),
+            Cell::new((0, 1), DataType::String("Id".into())),
+            Cell::new((1, 0), DataType::String("Code".into())),
+            Cell::new((1, 1), DataType::String("Label".into())),
+            Cell::new((2, 0), DataType::String("A".into())),
+            Cell::new((2, 1), DataType::Float(1.0)),
+        ]);
+        let rows: Vec<_> = header_candidates(&preview).iter().map(|c| c.row).collect();
+        // Row 0 is only listed as the detector's pick
+        assert_eq!(rows, [0, 1]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn previews_delimited_files_without_dimensions() {
+        let path = crate::testing::TempPath::with_text("csv", "Name,Age\nAnn,31\n");
+        let sheets = inspect(&path).unwrap();
+        assert_eq!(sheets.len(), 1);
+        assert_eq!(sheets[0].dimensions, None);
+        assert_eq!(sheets[0].header_candidates[0].headers, ["Name", "Age"]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Reusable invariant checks for loaded data
+//!
+//! Each `check_*` function returns the first violation found; the `assert_*` functions panic
+//! with it instead, for use in tests.
+
+use crate::WorkbookData;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvariantViolation {
+    #[error("Header '{}' does not resolve to a column", .0)]
+    UnresolvedHeader(String),
+
+    #[error("Row {} column '{}' has no value inside the data rows", .row, .header)]
+    MissingValue { row: u32, header: String },
+
+    #[error("Row {} column '{}' has a value outside the data rows", .row, .header)]
+    ValueOutOfBounds { row: u32, header: String },
+
+    #[error("Headers differ: {:?} != {:?}", .expected, .found)]
+    HeaderMismatch {
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+
+    #[error("Row count differs: {} != {}", .expected, .found)]
+    RowCountMismatch { expected: usize, found: usize },
+
+    #[error("Data row {} column '{}' differs: '{}' != '{}'", .index, .header, .expected, .found)]
+    ValueMismatch {
+        index: usize,
+        header: String,
+        expected: String,
+        found: String,
+    },
+}
+
+/// Check that every header resolves to a column and has a value in every data row
+pub fn check_headers_resolve(data: &WorkbookData) -> Result<(), InvariantViolation> {
+    for header in data.headers() {
+        if data.column_id(header).is_none() {
+            return Err(InvariantViolation::UnresolvedHeader(header.into()));
+        }
+
+        for row in data.rows() {
+            if row.get(header).is_err() {
+                return Err(InvariantViolation::MissingValue {
+                    row: row.number(),
+                    header: header.into(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that `get` returns a value exactly for rows inside the data rows
+///
+/// Probes a margin of rows around the data, which also exercises `get` for panics.
+pub fn check_get_in_bounds(data: &WorkbookData) -> Result<(), InvariantViolation> {
+    let probe_start = data.first_row.saturating_sub(3);
+    let probe_end = data.last_row.saturating_add(3);
+
+    for row in probe_start..=probe_end {
+        let in_bounds = row >= data.first_row && row <= data.last_row;
+
+        for header in data.headers() {
+            match (in_bounds, data.get(row, header).is_some()) {
+                (true, false) => {
+                    return Err(InvariantViolation::MissingValue {
+                        row,
+                        header: header.into(),
+                    })
+                }
+                (false, true) => {
+                    return Err(InvariantViolation::ValueOutOfBounds {
+                        row,
+                        header: header.into(),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that re-imported data has the same headers and cell values as the original, row by row
+///
+/// Row numbers are not compared, since an export may place data at a different offset.
+pub fn check_round_trip(
+    original: &WorkbookData,
+    reimported: &WorkbookData,
+) -> Result<(), InvariantViolation> {
+    let expected: Vec<String> = original.headers().map(String::from).collect();
+    let found: Vec<String> = reimported.headers().map(String::from).collect();
+
+    if expected != found {
+        return Err(InvariantViolation::HeaderMismatch { expected, found });
+    }
+
+    let expected_rows = original.rows().count();
+    let found_rows = reimported.rows().count();
+
+    if expected_rows != found_rows {
+        return Err(InvariantViolation::RowCountMismatch {
+            expected: expected_rows,
+            found: found_rows,
+        });
+    }
+
+    for (index, (a, b)) in original.rows().zip(reimported.rows()).enumerate() {
+        for ((header, expected), (_, found)) in a.iter().zip(b.iter()) {
+            if expected != found {
+                return Err(InvariantViolation::ValueMismatch {
+                    index,
+                    header: header.to_owned(),
+                    expected: expected.into_owned(),
+                    found: found.into_owned(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run every single-table check
+pub fn check_all(data: &WorkbookData) -> Result<(), InvariantViolation> {
+    check_headers_resolve(data)?;
+    check_get_in_bounds(data)
+}
+
+pub fn assert_headers_resolve(data: &WorkbookData) {
+    if let Err(err) = check_headers_resolve(data) {
+        panic!("{}", err);
+    }
+}
+
+pub fn assert_get_in_bounds(data: &WorkbookData) {
+    if let Err(err) = check_get_in_bounds(data) {
+        panic!("{}", err);
+    }
+}
+
+pub fn assert_round_trip(original: &WorkbookData, reimported: &WorkbookData) {
+    if let Err(err) = check_round_trip(original, reimported) {
+        panic!("{}", err);
+    }
+}
+
+pub fn assert_all(data: &WorkbookData) {
+    if let Err(err) = check_all(data) {
+        panic!("{}", err);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{cell_is_empty, cell_to_str, DataError, RowData, WorkbookData};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Which rows `WorkbookData::join` keeps when a key has no match on the other side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only rows with a match on both sides
+    Inner,
+    /// Every left row, with or without a match
+    Left,
+    /// Every right row, with or without a match
+    Right,
+    /// Every row from both sides
+    Full,
+}
+
+/// A left row paired with a matching right row, from `WorkbookData::join`
+///
+/// Either side is missing when the row had no match under a `Left`, `Right` or `Full` join.
+#[derive(Clone, Copy)]
+pub struct JoinedRow<'a> {
+    left: &'a WorkbookData,
+    right: &'a WorkbookData,
+    left_row: Option<u32>,
+    right_row: Option<u32>,
+}
+
+impl<'a> JoinedRow<'a> {
+    pub fn left(&self) -> Option<RowData<'a>> {
+        self.left_row.map(|row_number| RowData {
+            source: self.left,
+            row_number,
+        })
+    }
+
+    pub fn right(&self) -> Option<RowData<'a>> {
+        self.right_row.map(|row_number| RowData {
+            source: self.right,
+            row_number,
+        })
+    }
+
+    /// Get a value by header, preferring the left row when both sheets have the column
+    ///
+    /// Columns only on a missing side read as empty; shared columns (including the key) fall
+    /// back to the side that is present.
+    pub fn get(&self, column_header: &str) -> Result<Cow<'a, str>, DataError> {
+        let (source, row_number) = self.side(column_header)?;
+        let value = row_number.and_then(|row| source.get(row, column_header));
+        Ok(value.unwrap_or_default())
+    }
+
+    /// Check if the cell with the matching column header is empty (or on a missing side)
+    pub fn is_cell_empty(&self, column_header: &str) -> bool {
+        match self.side(column_header) {
+            Ok((source, Some(row_number))) => source.is_cell_empty(row_number, column_header),
+            _ => true,
+        }
+    }
+
+    /// Get the left headers followed by the right headers not also on the left
+    pub fn headers(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let left = self.left;
+        left.headers().chain(
+            self.right
+                .headers()
+                .filter(move |h| left.column_id(h).is_none()),
+        )
+    }
+
+    fn side(&self, column_header: &str) -> Result<(&'a WorkbookData, Option<u32>), DataError> {
+        let on_left = self.left.column_id(column_header).is_some();
+        let on_right = self.right.column_id(column_header).is_some();
+
+        match (on_left, on_right) {
+            (true, true) if self.left_row.is_none() => Ok((self.right, self.right_row)),
+            (true, _) => Ok((self.left, self.left_row)),
+            (false, true) => Ok((self.right, self.right_row)),
+            (false, false) => Err(DataError::NoValue(column_header.into())),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for JoinedRow<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinedRow")
+            .field("left", &self.left())
+            .field("right", &self.right())
+            .finish()
+    }
+}
+
+impl WorkbookData {
+    /// Pair rows of this sheet with rows of `other` that have the same value in a key column
+    ///
+    /// Every matching pair is returned, in this sheet's row order, followed by the unmatched
+    /// rows of `other` for `Right` and `Full` joins. Rows with an empty key never match.
+    pub fn join<'a>(
+        &'a self,
+        other: &'a WorkbookData,
+        key_header: &str,
+        kind: JoinKind,
+    ) -> Result<Vec<JoinedRow<'a>>, DataError> {
+        let key_rows = |data: &'a WorkbookData| -> Result<Vec<(u32, Option<String>)>, DataError> {
+            let id = data
+                .column_id(key_header)
+                .ok_or_else(|| DataError::NoValue(key_header.into()))?;
+
+            Ok((data.first_row..=data.last_row)
+                .map(|row_number| {
+                    let key = data
+                        .get_cell_by_id(row_number, id)
+                        .filter(|value| !cell_is_empty(value))
+                        .map(|value| cell_to_str(value).into_owned());
+                    (row_number, key)
+                })
+                .collect())
+        };
+
+        let left_keys = key_rows(self)?;
+        let right_keys = key_rows(other)?;
+
+        let mut lookup: HashMap<&str, Vec<u32>> = HashMap::new();
+        for (row_number, key) in &right_keys {
+            if let Some(key) = key {
+                lookup.entry(key.as_str()).or_default().push(*row_number);
+            }
+        }
+
+        let pair = |left_row, right_row| JoinedRow {
+            left: self,
+            right: other,
+            left_row,
+            right_row,
+        };
+
+        let keep_left = matches!(kind, JoinKind::Left | JoinKind::Full);
+        let keep_right = matches!(kind, JoinKind::Right | JoinKind::Full);
+
+        let mut rows = Vec::new();
+        let mut matched = vec![false; right_keys.len()];
+        let first_right = other.first_row;
+
+        for (left_row, key) in &left_keys {
+            match key.as_deref().and_then(|key| lookup.get(key)) {
+                Some(right_rows) => {
+                    for &right_row in right_rows {
+                        matched[(right_row - first_right) as usize] = true;
+                        rows.push(pair(Some(*left_row), Some(right_row)));
+                    }
+                }
+                None if keep_left => rows.push(pair(Some(*left_row), None)),
+                None => {}
+            }
+        }
+
+        if keep_right {
+            for ((right_row, _), _) in right_keys.iter().zip(&matched).filter(|(_, &m)| !m) {
+                rows.push(pair(None, Some(*right_row)));
+            }
+        }
+
+        Ok(rows)
+    }
+}
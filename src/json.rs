@@ -0,0 +1,68 @@
+use crate::stats::Timer;
+use crate::{DataType, RowData, WorkbookData};
+use serde_json::{Map, Number, Value};
+use std::io::{self, Write};
+
+fn to_value(cell: &DataType) -> Value {
+    match cell {
+        DataType::Int(i) => Value::from(*i),
+        // NaN and infinities have no JSON representation
+        DataType::Float(f) => Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        DataType::String(s) => Value::String(s.clone()),
+        DataType::Bool(b) => Value::Bool(*b),
+        #[cfg(feature = "dates")]
+        DataType::DateTime(_) => cell
+            .as_datetime()
+            .map_or(Value::Null, |dt| Value::String(dt.to_string())),
+        #[cfg(not(feature = "dates"))]
+        DataType::DateTime(serial) => Number::from_f64(*serial).map_or(Value::Null, Value::Number),
+        DataType::Error(e) => Value::String(e.to_string()),
+        DataType::Empty => Value::Null,
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Convert to a JSON object keyed by header, in column order
+    ///
+    /// Numbers and booleans keep their types and empty cells become `null`. Dates are Excel
+    /// serial numbers, or ISO 8601 strings with the `dates` feature.
+    pub fn to_json_value(&self) -> Value {
+        let source = self.source;
+        let object: Map<String, Value> = source
+            .columns
+            .iter()
+            .map(|c| {
+                let value = source
+                    .range
+                    .get_value((self.row_number, c.index))
+                    .map_or(Value::Null, |v| to_value(c.round(v).as_ref().unwrap_or(v)));
+                (c.name.clone(), value)
+            })
+            .collect();
+
+        Value::Object(object)
+    }
+}
+
+impl WorkbookData {
+    /// Convert the data rows to a JSON array of objects, as `RowData::to_json_value`
+    pub fn to_json(&self) -> Value {
+        let start = Timer::start();
+        let json = Value::Array(self.rows().map(|row| row.to_json_value()).collect());
+        self.counters.record_pass(self.data_cells(), start);
+        json
+    }
+
+    /// Write one JSON object per line (JSON Lines), one row at a time
+    pub fn write_jsonl<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let start = Timer::start();
+        for row in self.rows() {
+            serde_json::to_writer(&mut writer, &row.to_json_value())?;
+            writer.write_all(b"\n")?;
+        }
+
+        writer.flush()?;
+        self.counters.record_pass(self.data_cells(), start);
+        Ok(())
+    }
+}
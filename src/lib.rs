@@ -1,9 +1,167 @@
-use calamine::{open_workbook_auto, DataType, Range, Reader, Sheets};
+#[cfg(feature = "arrow")]
+mod arrow;
+mod batch;
+mod boolean;
+mod bytes;
+#[cfg(feature = "cache")]
+mod cache;
+mod cell;
+mod comment;
+mod compact;
+mod concat;
+pub mod coords;
+#[cfg(feature = "polars")]
+mod dataframe;
+#[cfg(feature = "dates")]
+mod dates;
+#[cfg(feature = "serde")]
+mod de;
+mod dedup;
+#[cfg(feature = "csv")]
+mod delimited;
+mod detect;
+mod diff;
+mod display;
+#[cfg(feature = "write")]
+mod edit;
+mod export;
+mod fill;
+mod filter;
+mod formula;
+mod from_row;
+pub mod generator;
+pub mod golden;
+mod group;
+mod hyperlink;
+#[cfg(feature = "manifest")]
+mod idempotency;
+mod index;
+mod infer;
+mod inspect;
+pub mod invariants;
+mod join;
+#[cfg(feature = "json")]
+mod json;
+mod locale;
+mod localize;
+#[cfg(feature = "manifest")]
+mod manifest;
+mod materialize;
+mod money;
+mod names;
+mod numfmt;
+mod options;
+mod owned;
+mod partial;
+mod pii;
+#[cfg(any(feature = "write", feature = "sqlite"))]
+mod plan;
+pub mod prelude;
+mod properties;
+mod ragged;
+mod reconcile;
+mod registry;
+mod reload;
+mod rounding;
+mod schema;
+mod sheets;
+mod sort;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stats;
+mod stream;
+mod stringify;
+mod summary;
+mod tables;
+#[cfg(feature = "write")]
+mod template;
+#[cfg(test)]
+mod testing;
+mod trace;
+mod unpivot;
+mod validate;
+mod view;
+#[cfg(feature = "write")]
+mod write;
+mod xlsx;
+
+use calamine::{open_workbook_auto, Reader};
+use coords::Span;
+use options::ProgressHandle;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{Read, Seek};
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::OnceLock;
+
+pub use batch::{
+    from_glob, from_glob_with_options, from_paths, from_paths_with_options, BatchRow, BatchRows,
+    WorkbookBatch,
+};
+pub use boolean::BoolOptions;
+pub use calamine::{CellErrorType, DataType, Range};
+pub use cell::{CellErrorKind, CellValue};
+pub use comment::Comment;
+pub use compact::{ColumnValues, CompactColumn, CompactData};
+pub use concat::{
+    concat_streaming, concat_streaming_with_options, ColumnAlign, ConcatRow, ConcatRows,
+};
+pub use coords::column_letter;
+pub use dedup::KeepPolicy;
+pub use detect::{HeaderDetector, HeuristicDetector};
+pub use diff::{diff, RowChange, WorkbookDiff};
+#[cfg(feature = "write")]
+pub use edit::{append_rows, plan_append_rows, SheetEditor};
+pub use export::ExportOptions;
+pub use filter::Filter;
+pub use from_row::FromRow;
+pub use group::{sum_column, Groups};
+pub use hyperlink::Hyperlink;
+pub use index::{DuplicateKeyPolicy, RowIndex};
+pub use infer::{InferredColumn, InferredSchema};
+pub use inspect::{inspect, HeaderCandidate, SheetOverview};
+pub use join::{JoinKind, JoinedRow};
+pub use locale::NumberLocale;
+pub use localize::{CatalogParseError, Localize, MessageCatalog};
+#[cfg(feature = "manifest")]
+pub use manifest::{Manifest, ManifestFile};
+pub use names::defined_names;
+pub use numfmt::format_value;
+pub use options::{CancelToken, HeaderPredicate, LoadOptions, RaggedRowPolicy};
+pub use owned::{CellChange, OwnedRow};
+pub use partial::{load_partial, LoadProblem, PartialLoad};
+pub use pii::{PiiFinding, PiiKind, PiiScanner};
+#[cfg(any(feature = "write", feature = "sqlite"))]
+pub use plan::{SheetPlan, WritePlan};
+pub use properties::{custom_properties, CustomProperty};
+pub use reconcile::{
+    header_similarity, FileMapping, HeaderSuggestion, ReconciledHeaders, Reconciliation,
+};
+pub use registry::Registry;
+pub use rounding::round_to;
+pub use schema::{
+    ColumnType, Schema, SchemaColumn, SchemaMismatch, TypeConflict, Validated, Violation,
+    Violations,
+};
+pub use sheets::{list_sheets, SheetInfo, SheetVisibility};
+pub use sort::Order;
+pub use stats::{ColumnImportStats, ImportStats};
+pub use stream::{stream_rows, RowStream};
+pub use stringify::NumberText;
+pub use summary::{ColumnStats, NumericStats};
+pub use tables::{list_tables, ExcelTable};
+#[cfg(feature = "write")]
+pub use template::{fill_template, plan_fill_template};
+pub use validate::{Constraint, ValidationReport};
+pub use view::{ColumnView, ViewRow, ViewRows};
+#[cfg(feature = "write")]
+pub use write::WorkbookWriter;
+pub use xlsx::{Relationship, SheetEntry, XlsxPackage};
 
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum LoadError {
     #[error("No data found in '{}'", .filename)]
     Empty { filename: String },
@@ -14,161 +172,975 @@ pub enum LoadError {
         sheet_name: String,
     },
 
+    #[error("No header row found in range")]
+    NoHeader,
+
+    #[error("Invalid cell range '{}'", .0)]
+    InvalidRange(String),
+
+    #[error("No defined name '{}' found in '{}'", .name, .filename)]
+    UnknownDefinedName { filename: String, name: String },
+
+    #[error("No table '{}' found in '{}'", .table_name, .filename)]
+    UnknownTable {
+        filename: String,
+        table_name: String,
+    },
+
+    #[error("Headers in '{}' do not match the first file: missing {:?}, unexpected {:?}", .filename, .missing, .unexpected)]
+    IncompatibleHeaders {
+        filename: String,
+        missing: Vec<String>,
+        unexpected: Vec<String>,
+    },
+
+    #[error("No files match '{}'", .0)]
+    NoMatchingFiles(String),
+
+    #[error("{} row(s) have values in columns without a header, first at row {}", .0.len(), .0[0] + 1)]
+    RaggedRows(Vec<u32>),
+
+    #[error("Row {} has a value in column {}, past the columns found while looking for the header; load the sheet or a range instead of streaming it", .row + 1, .column)]
+    StreamTooWide { row: u32, column: String },
+
+    #[error("Invalid xlsx package: {}", .0)]
+    InvalidPackage(String),
+
+    #[cfg(feature = "csv")]
+    #[error("Invalid delimited file: {}", .0)]
+    CsvError(String),
+
+    #[error("No workbook registered as '{}'", .0)]
+    NotRegistered(String),
+
+    #[cfg(feature = "cache")]
+    #[error("Invalid cache file: {}", .0)]
+    InvalidCache(String),
+
+    #[error("Loading was cancelled")]
+    Cancelled,
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     #[error(transparent)]
     CalamineError(#[from] calamine::Error),
 }
 
+#[cfg(feature = "write")]
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum WriteError {
+    #[error(transparent)]
+    XlsxError(#[from] rust_xlsxwriter::XlsxError),
+
+    #[error("Cannot edit in place: {}", .0)]
+    NotEditable(String),
+
+    #[error("No table or defined name '{}' found in the template", .0)]
+    UnknownRegion(String),
+
+    #[error(transparent)]
+    LoadError(#[from] LoadError),
+
+    #[error(transparent)]
+    DataError(#[from] DataError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum DataError {
-    #[error("Key '{}' value could not be parsed: {}", .key, .value)]
-    ParseError { key: String, value: String },
+    #[error("{} / '{}' value could not be parsed: {}", .position, .key, .value)]
+    ParseError {
+        key: String,
+        value: String,
+        /// Where the column is in the sheet, like `"Column AJ"`
+        position: String,
+    },
 
     #[error("No data found for key '{}'", .0)]
     NoValue(String),
+
+    #[error("{} / '{}' value '{}' is repeated in rows {} and {}", .position, .key, .value, .rows.0 + 1, .rows.1 + 1)]
+    DuplicateKey {
+        key: String,
+        value: String,
+        rows: (u32, u32),
+        position: String,
+    },
+
+    #[error("Key '{}' has no declared money scale", .0)]
+    UndeclaredScale(String),
+
+    #[error("{} / '{}' contains the error value {}", .position, .key, .kind)]
+    CellError {
+        key: String,
+        kind: CellErrorKind,
+        position: String,
+    },
+
+    #[cfg(feature = "serde")]
+    #[error("Row {} could not be deserialized: {}", .row, .message)]
+    DeserializeError { row: u32, message: String },
+}
+
+/// Map each header to its column, the first one if a header repeats
+fn header_map(columns: &[Column]) -> HashMap<String, usize> {
+    let mut header = HashMap::new();
+    for (i, column) in columns.iter().enumerate() {
+        header.entry(column.name.clone()).or_insert(i);
+    }
+    header
 }
 
 pub struct WorkbookData {
-    header: HashMap<String, u32>,
+    header: HashMap<String, usize>,
+    columns: Vec<Column>,
     range: Range<DataType>,
+    sheet_name: Option<String>,
+    transposed: bool,
+    /// Content hash of the file this data was loaded from, hashed when first asked for
+    fingerprint: OnceLock<Option<String>>,
+    /// Formulas by absolute (row, column), if loaded with `LoadOptions::formulas`
+    formulas: HashMap<(u32, u32), String>,
+    /// Hyperlink targets by absolute (row, column), if loaded with `LoadOptions::hyperlinks`
+    hyperlinks: HashMap<(u32, u32), String>,
+    /// Comment text by absolute (row, column), if loaded with `LoadOptions::comments`
+    comments: HashMap<(u32, u32), String>,
+    /// Number format codes by absolute (row, column), if loaded with
+    /// `LoadOptions::number_formats`
+    number_formats: HashMap<(u32, u32), String>,
+    /// Called as rows are iterated, if loaded with `LoadOptions::on_progress`
+    progress: Option<ProgressHandle>,
+    /// Stops row iteration once cancelled, if loaded with `LoadOptions::cancel_token`
+    cancel: Option<CancelToken>,
+    /// The file and options to re-load from, if loaded from a path
+    source: Option<reload::SourceFile>,
+    /// How numbers are written as text
+    number_text: NumberText,
+    counters: stats::Counters,
     pub first_row: u32,
     pub last_row: u32,
     pub first_col: u32,
     pub last_col: u32,
 }
 
+struct Column {
+    name: String,
+    /// Absolute column number in the sheet
+    index: u32,
+    /// Whether any data row has a value in this column
+    populated: bool,
+    /// Whether values are hidden in `Debug`/`Display` output
+    redacted: bool,
+    /// Decimal places of the minor unit, if this is a money column
+    money_scale: Option<u32>,
+    /// Decimal places floating-point values are rounded to when read
+    round_places: Option<u32>,
+    counters: stats::Counters,
+}
+
+/// Pre-resolved column handle, avoiding a header lookup on every access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnId(usize);
+
 impl WorkbookData {
-    fn from_workbook_sheet_name(
-        workbook: &mut Sheets,
+    /// Load a sheet, opening the xlsx package with `open_package` for the parts calamine does
+    /// not read; it returns `None` for other formats
+    fn from_workbook_sheet_name<W, R>(
+        workbook: &mut W,
+        open_package: &impl Fn() -> Option<Result<xlsx::XlsxPackage<R>, LoadError>>,
         sheet_name: &str,
-    ) -> Option<Result<Self, LoadError>> {
+        options: &LoadOptions,
+    ) -> Option<Result<Self, LoadError>>
+    where
+        W: Reader,
+        calamine::Error: From<W::Error>,
+        R: Read + Seek,
+    {
+        let _span = trace::enter_span!("sheet", name = sheet_name);
+        if let Some(progress) = &options.progress {
+            progress.report(0, None);
+        }
+        if let Err(err) = options.check_cancelled() {
+            return Some(Err(err));
+        }
         let range = match workbook.worksheet_range(sheet_name)? {
             Ok(range) => range,
-            Err(err) => return Some(Err(err.into())),
+            Err(err) => return Some(Err(calamine::Error::from(err).into())),
         };
+        trace::debug!(
+            rows = range.height(),
+            columns = range.width(),
+            "scanned sheet"
+        );
+        if let Err(err) = options.check_cancelled() {
+            return Some(Err(err));
+        }
+        if let Some(progress) = &options.progress {
+            progress.report(range.height(), Some(range.height()));
+        }
 
-        let (mut first_row, first_col) = range.start()?;
-        let (last_row, last_col) = range.end()?;
+        let merged = match options.expand_merged_cells.then(open_package).flatten() {
+            Some(package) => match package.and_then(|mut p| p.merged_regions(sheet_name)) {
+                Ok(merged) => merged,
+                Err(err) => return Some(Err(err)),
+            },
+            None => Vec::new(),
+        };
 
-        let min_cols = last_col - first_col + 1;
+        let formulas = if options.formulas {
+            match formula::read_formulas(workbook, sheet_name, options.transposed) {
+                Ok(formulas) => formulas,
+                Err(err) => return Some(Err(err)),
+            }
+        } else {
+            HashMap::new()
+        };
 
-        let mut rows = range.rows();
+        // Hyperlinks, comments and formats are only in package parts calamine does not read
+        let mut hyperlinks = HashMap::new();
+        let mut comments = HashMap::new();
+        let mut number_formats = HashMap::new();
+        let package = (options.hyperlinks || options.comments || options.number_formats)
+            .then(open_package)
+            .flatten();
+        if let Some(package) = package {
+            let read = package.and_then(|mut package| {
+                if let (true, Some(start), Some(end)) =
+                    (options.hyperlinks, range.start(), range.end())
+                {
+                    let bounds = (start, end);
+                    hyperlinks = hyperlink::read_hyperlinks(
+                        &mut package,
+                        sheet_name,
+                        bounds,
+                        options.transposed,
+                    )?;
+                }
+                if options.comments {
+                    comments =
+                        comment::read_comments(&mut package, sheet_name, options.transposed)?;
+                }
+                if options.number_formats {
+                    number_formats =
+                        numfmt::read_number_formats(&mut package, sheet_name, options.transposed)?;
+                }
+                Ok(())
+            });
 
-        loop {
-            first_row += 1;
+            if let Err(err) = read {
+                return Some(Err(err));
+            }
+        }
 
-            let row = rows.next()?;
+        Self::from_range_inner(range, options, &merged).map(|mut data| {
+            data.sheet_name = Some(sheet_name.to_owned());
+            data.formulas = formulas;
+            data.hyperlinks = hyperlinks;
+            data.comments = comments;
+            data.number_formats = number_formats;
+            data.apply_ragged_policy(options.ragged_rows)?;
+            Ok(data)
+        })
+    }
 
-            let row: Vec<_> = row.iter().map(|h| h.to_string()).collect();
-            let count_cols = row.iter().filter(|x| !x.is_empty()).count() as u32;
+    fn from_range_inner(
+        range: Range<DataType>,
+        options: &LoadOptions,
+        merged: &[Span],
+    ) -> Option<Self> {
+        // Validated by the public constructors
+        let region = options.region().ok().flatten();
 
-            if count_cols >= min_cols {
-                let header = row
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, s)| (s, i as u32))
-                    .collect();
+        let range = match region {
+            Some(region) => match range_in_region(&range, region) {
+                Some(range) => range,
+                None => {
+                    trace::debug!("region is outside the used range");
+                    return None;
+                }
+            },
+            None => range,
+        };
 
-                return Some(Ok(Self {
-                    header,
-                    range,
-                    first_row,
-                    last_row,
-                    first_col,
-                    last_col,
-                }));
+        let mut range = match trim_range(range) {
+            Some(range) => range,
+            None => {
+                trace::debug!("range has no values");
+                return None;
             }
+        };
+
+        let merged: Cow<'_, [Span]> = if options.transposed {
+            range = transpose_range(&range);
+            merged
+                .iter()
+                .map(|&((r0, c0), (r1, c1))| ((c0, r0), (c1, r1)))
+                .collect()
+        } else {
+            Cow::Borrowed(merged)
+        };
+
+        if options.headerless {
+            let (start_row, _) = range.start()?;
+            expand_merged_regions(&mut range, &merged, start_row);
+            let data = Self::without_header(range, options.transposed)?;
+            return Some(data.with_column_options(options));
         }
+
+        let header_row = match (options.header_row, region) {
+            (Some(row), _) => {
+                trace::debug!(header_row = row, "header row set in options");
+                row
+            }
+            (None, Some(_)) => {
+                // The region's first row with values, as a whole-column region starts at row 1
+                let top = first_row_with_values(&range)?;
+                let row = top + options.header_rows - 1;
+                trace::debug!(header_row = row, "header row at top of region");
+                row
+            }
+            (None, None) => Self::detect_header_row(&range, options)?,
+        };
+
+        // Merged cells are only expanded below the header, so a merged title banner
+        // does not turn into a fully populated (header-like) row
+        expand_merged_regions(&mut range, &merged, header_row + 1);
+
+        let data = Self::with_header_rows(
+            range,
+            header_row,
+            options.header_rows,
+            &options.header_separator,
+        )?;
+
+        Some(data.with_column_options(options))
     }
 
-    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
-        // For error message only
-        let filename = path.as_ref().to_string_lossy().to_string();
+    /// Apply the per-column settings of `options`, after the columns are known
+    fn with_column_options(mut self, options: &LoadOptions) -> Self {
+        for column_header in &options.fill_down {
+            if let Some(column) = self.column_id(column_header) {
+                self.fill_down_id(column);
+            }
+        }
 
-        let mut workbook = open_workbook_auto(path)?;
+        for column_header in &options.redact {
+            self.set_redacted(column_header, true);
+        }
 
-        for s in workbook.sheet_names().to_owned() {
-            if let Some(Ok(data)) = Self::from_workbook_sheet_name(&mut workbook, &s) {
-                return Ok(data);
+        for (column_header, scale) in &options.money {
+            self.set_money_scale(column_header, *scale);
+        }
+
+        for (column_header, places) in &options.rounding {
+            self.set_rounding(column_header, *places);
+        }
+
+        self.number_text = options.number_text;
+        self.transposed = options.transposed;
+        self.progress = options.progress.clone();
+        self.cancel = options.cancel.clone();
+
+        self
+    }
+
+    /// Build with every row as data and columns named by position, for `LoadOptions::headerless`
+    fn without_header(range: Range<DataType>, transposed: bool) -> Option<Self> {
+        let (start_row, _) = range.start()?;
+        let mut data = Self::with_header(range, start_row)?;
+        data.first_row = start_row;
+
+        let (range, rows) = (&data.range, data.first_row..=data.last_row);
+        for column in &mut data.columns {
+            column.name = if transposed {
+                (column.index + 1).to_string()
+            } else {
+                coords::column_letter(column.index)
+            };
+            column.populated = rows.clone().any(|r| {
+                range
+                    .get_value((r, column.index))
+                    .is_some_and(|v| !cell_is_empty(v))
+            });
+        }
+
+        data.header = header_map(&data.columns);
+        Some(data)
+    }
+
+    /// Build using the given absolute row as the header, without any detection or trimming
+    fn with_header(range: Range<DataType>, header_row: u32) -> Option<Self> {
+        Self::with_header_rows(range, header_row, 1, "")
+    }
+
+    /// Build using `header_rows` rows ending at `header_row` as the header, joining the
+    /// non-empty parts of each column's header cells with `separator`
+    fn with_header_rows(
+        range: Range<DataType>,
+        header_row: u32,
+        header_rows: u32,
+        separator: &str,
+    ) -> Option<Self> {
+        let (start_row, first_col) = range.start()?;
+        let (last_row, last_col) = range.end()?;
+
+        header_row.checked_sub(start_row)?;
+        let top_row = header_row
+            .saturating_sub(header_rows.max(1) - 1)
+            .max(start_row);
+
+        let mut names = vec![Vec::new(); (last_col - first_col + 1) as usize];
+
+        for row in top_row..=header_row {
+            // Upper header rows usually label a group of columns from a merged cell, which
+            // only has a value in its leftmost cell, so blanks continue the label to the left
+            let carry = row < header_row;
+            let mut last = None;
+
+            for (i, parts) in names.iter_mut().enumerate() {
+                let value = range
+                    .get_value((row, first_col + i as u32))
+                    .filter(|v| !cell_is_empty(v))
+                    .map(|v| v.to_string());
+
+                let value = match value {
+                    Some(value) => {
+                        last = Some(value.clone());
+                        Some(value)
+                    }
+                    None if carry => last.clone(),
+                    None => None,
+                };
+
+                parts.extend(value);
             }
         }
 
-        Err(LoadError::Empty { filename })
+        let first_row = header_row + 1;
+
+        let columns: Vec<_> = names
+            .into_iter()
+            .map(|parts| parts.join(separator))
+            .enumerate()
+            .map(|(i, h)| {
+                let index = first_col + i as u32;
+                let populated = (first_row..=last_row).any(|r| {
+                    range
+                        .get_value((r, index))
+                        .is_some_and(|v| !cell_is_empty(v))
+                });
+
+                Column {
+                    name: h,
+                    index,
+                    populated,
+                    redacted: false,
+                    money_scale: None,
+                    round_places: None,
+                    counters: Default::default(),
+                }
+            })
+            .collect();
+
+        Some(Self {
+            header: header_map(&columns),
+            columns,
+            range,
+            sheet_name: None,
+            transposed: false,
+            fingerprint: OnceLock::new(),
+            formulas: HashMap::new(),
+            hyperlinks: HashMap::new(),
+            comments: HashMap::new(),
+            number_formats: HashMap::new(),
+            progress: None,
+            cancel: None,
+            source: None,
+            number_text: NumberText::default(),
+            counters: Default::default(),
+            first_row,
+            last_row,
+            first_col,
+            last_col,
+        })
+    }
+
+    fn detect_header_row(range: &Range<DataType>, options: &LoadOptions) -> Option<u32> {
+        match &options.header_detector {
+            Some(detector) => {
+                let row = detector.detect(range);
+                trace::debug!(header_row = ?row, "custom header detector ran");
+                row
+            }
+            None => HeuristicDetector::from_options(options).detect(range),
+        }
+    }
+
+    /// Build from an already loaded calamine range, detecting the header as `from_path` does
+    pub fn from_range(range: Range<DataType>, options: &LoadOptions) -> Result<Self, LoadError> {
+        options.region()?;
+
+        let mut data = Self::from_range_inner(range, options, &[]).ok_or(LoadError::NoHeader)?;
+        data.apply_ragged_policy(options.ragged_rows)?;
+        Ok(data)
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        Self::from_path_with_options(path, &LoadOptions::default())
     }
 
     pub fn from_path_with_sheet_name<P: AsRef<Path>>(
         path: P,
         sheet_name: &str,
     ) -> Result<Self, LoadError> {
+        Self::from_path_with_options(path, &LoadOptions::new().sheet_name(sheet_name))
+    }
+
+    pub fn from_path_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &LoadOptions,
+    ) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let _span = trace::enter_span!("load", path = %path.display());
+
+        let source = reload::SourceFile::new(path, options);
+        let mut data = Self::from_path_inner(path, options)?;
+        data.source = Some(source);
+        Ok(data)
+    }
+
+    fn from_path_inner(path: &Path, options: &LoadOptions) -> Result<Self, LoadError> {
+        options.check_cancelled()?;
+
+        if let Some(options) = Self::resolve_named_region(path, options)? {
+            return Self::from_path_inner(path, &options);
+        }
+
+        options.region()?;
+
         // For error message only
-        let filename = path.as_ref().to_string_lossy().to_string();
+        let filename = path.to_string_lossy().to_string();
+
+        #[cfg(feature = "csv")]
+        if let Some(delimiter) = delimited::delimiter(path) {
+            trace::debug!(delimiter = %char::from(delimiter), "reading delimited text");
+            return Self::from_delimited_path(path, delimiter, options);
+        }
 
         let mut workbook = open_workbook_auto(path)?;
+        let open_package = || xlsx::is_xlsx_path(path).then(|| xlsx::XlsxPackage::open(path));
+        Self::from_workbook(&mut workbook, &open_package, filename, options)
+    }
 
-        match Self::from_workbook_sheet_name(&mut workbook, sheet_name) {
-            Some(Ok(data)) => Ok(data),
-            Some(Err(err)) => Err(err.into()),
-            None => Err(LoadError::EmptySheet {
-                filename,
-                sheet_name: sheet_name.to_owned(),
-            }),
+    /// Get `options` with a defined name or table replaced by the sheet and range it refers
+    /// to, or `None` if it selects neither
+    pub(crate) fn resolve_named_region(
+        path: &Path,
+        options: &LoadOptions,
+    ) -> Result<Option<LoadOptions>, LoadError> {
+        if let Some(name) = &options.defined_name {
+            return Self::defined_name_options(path, name, options).map(Some);
         }
+        if let Some(table_name) = &options.table {
+            return Self::table_options(path, table_name, options).map(Some);
+        }
+        Ok(None)
     }
 
-    pub fn get(&self, row_number: u32, column_header: &str) -> Option<String> {
+    /// Load the sheet named in `options`, or else the first sheet with a header
+    fn from_workbook<W, R>(
+        workbook: &mut W,
+        open_package: &impl Fn() -> Option<Result<xlsx::XlsxPackage<R>, LoadError>>,
+        filename: String,
+        options: &LoadOptions,
+    ) -> Result<Self, LoadError>
+    where
+        W: Reader,
+        calamine::Error: From<W::Error>,
+        R: Read + Seek,
+    {
+        trace::debug!(sheets = ?workbook.sheet_names(), "opened workbook");
+
+        if let Some(sheet_name) = &options.sheet_name {
+            return match Self::from_workbook_sheet_name(workbook, open_package, sheet_name, options)
+            {
+                Some(result) => result,
+                None => Err(LoadError::EmptySheet {
+                    filename,
+                    sheet_name: sheet_name.to_owned(),
+                }),
+            };
+        }
+
+        let package = options.skip_hidden_sheets.then(open_package).flatten();
+        let hidden: Vec<String> = if let Some(package) = package {
+            package?
+                .sheets()?
+                .into_iter()
+                .filter(|s| !s.visibility.is_visible())
+                .map(|s| s.name)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for s in workbook.sheet_names().to_owned() {
+            if hidden.contains(&s) {
+                trace::debug!(sheet = %s, "skipping hidden sheet");
+                continue;
+            }
+
+            match Self::from_workbook_sheet_name(workbook, open_package, &s, options) {
+                Some(Ok(data)) => {
+                    trace::debug!(sheet = %s, "using first sheet with data");
+                    return Ok(data);
+                }
+                Some(Err(err @ LoadError::RaggedRows(_)))
+                | Some(Err(err @ LoadError::Cancelled)) => return Err(err),
+                Some(Err(_err)) => {
+                    trace::debug!(sheet = %s, error = %_err, "skipping sheet that failed to load");
+                }
+                None => trace::debug!(sheet = %s, "skipping sheet with no data or header"),
+            }
+        }
+
+        Err(LoadError::Empty { filename })
+    }
+
+    /// Get the underlying calamine range, including the header and any rows above it
+    pub fn as_range(&self) -> &Range<DataType> {
+        &self.range
+    }
+
+    /// Consume this data, returning the underlying calamine range
+    pub fn into_range(self) -> Range<DataType> {
+        self.range
+    }
+
+    /// Get the name of the sheet this data was loaded from, if loaded from a file
+    pub fn sheet_name(&self) -> Option<&str> {
+        self.sheet_name.as_deref()
+    }
+
+    /// Get the content hash of the file this data was loaded from
+    ///
+    /// With the `manifest` feature, data loaded from a path hashes its file the first time this
+    /// is called, and has none if the file has changed since loading. Otherwise it is only set
+    /// with `set_fingerprint`.
+    pub fn fingerprint(&self) -> Option<&str> {
+        self.fingerprint
+            .get_or_init(|| self.source_fingerprint())
+            .as_deref()
+    }
+
+    /// Identify the source of data not loaded from a file, e.g. for `RowData::idempotency_key`
+    pub fn set_fingerprint(&mut self, fingerprint: &str) {
+        self.fingerprint = OnceLock::from(Some(fingerprint.to_owned()));
+    }
+
+    /// Check if this data was loaded with `LoadOptions::transposed`, in which case row numbers
+    /// are source column numbers and column numbers are source row numbers
+    pub fn is_transposed(&self) -> bool {
+        self.transposed
+    }
+
+    /// Get the absolute row number of the header row
+    pub fn header_row(&self) -> u32 {
+        self.first_row - 1
+    }
+
+    /// Get the column headers in sheet order
+    pub fn headers(&self) -> impl Iterator<Item = &str> + '_ {
+        self.columns.iter().map(|c| c.name.as_str())
+    }
+
+    /// Resolve a column header once for repeated `get_by_id` lookups
+    pub fn column_id(&self, column_header: &str) -> Option<ColumnId> {
+        self.header.get(column_header).copied().map(ColumnId)
+    }
+
+    fn get_cell_by_id(&self, row_number: u32, column: ColumnId) -> Option<&DataType> {
         if row_number < self.first_row || row_number > self.last_row {
             return None;
         }
 
-        let col_number = self.header.get(column_header)?;
+        let col_number = self.columns.get(column.0)?.index;
 
-        let value = self.range.get_value((row_number, *col_number))?;
+        self.range.get_value((row_number, col_number))
+    }
+
+    /// Describe where a column is in the sheet for diagnostics, like `"Column AJ"`
+    ///
+    /// Fields of transposed data run along sheet rows, so they are described as rows.
+    fn column_position(&self, column_header: &str) -> String {
+        let index = match self.column_id(column_header) {
+            Some(id) => self.columns[id.0].index,
+            None => return "Column ?".into(),
+        };
+
+        if self.transposed {
+            format!("Row {}", index + 1)
+        } else {
+            format!("Column {}", coords::column_letter(index))
+        }
+    }
+
+    /// Get the handle of the column at a zero-based position in header order, for columns
+    /// whose headers are unreliable
+    pub fn column_at(&self, index: u32) -> Option<ColumnId> {
+        let index = index as usize;
+        (index < self.columns.len()).then_some(ColumnId(index))
+    }
+
+    fn get_cell(&self, row_number: u32, column_header: &str) -> Option<&DataType> {
+        self.get_cell_by_id(row_number, self.column_id(column_header)?)
+    }
 
-        Some(value.to_string())
+    /// Get a cell value as a string, borrowing string cells instead of allocating
+    pub fn get(&self, row_number: u32, column_header: &str) -> Option<Cow<'_, str>> {
+        self.get_by_id(row_number, self.column_id(column_header)?)
+    }
+
+    /// Get a cell value by a column handle from `column_id`
+    pub fn get_by_id(&self, row_number: u32, column: ColumnId) -> Option<Cow<'_, str>> {
+        let value = self.get_cell_by_id(row_number, column)?;
+        Some(self.cell_text(row_number, &self.columns[column.0], value))
+    }
+
+    /// Get a cell value by the zero-based position of its column, ignoring the headers
+    pub fn get_by_index(&self, row_number: u32, index: u32) -> Option<Cow<'_, str>> {
+        self.get_by_id(row_number, self.column_at(index)?)
+    }
+
+    /// Check if a cell is empty (or missing) without stringifying it
+    pub fn is_cell_empty(&self, row_number: u32, column_header: &str) -> bool {
+        self.get_cell(row_number, column_header)
+            .is_none_or(cell_is_empty)
     }
 
     pub fn is_row_empty(&self, row_number: u32) -> bool {
-        0 == self
-            .header
-            .keys()
-            .filter_map(|h| self.get(row_number, h))
-            .filter(|v| !v.is_empty())
-            .count()
+        if row_number < self.first_row || row_number > self.last_row {
+            return true;
+        }
+
+        self.columns.iter().filter(|c| c.populated).all(|c| {
+            self.range
+                .get_value((row_number, c.index))
+                .is_none_or(cell_is_empty)
+        })
     }
 
     pub fn iter_rows<'a>(&'a self) -> RowsIterator<'a> {
+        RowsIterator {
+            observed: true,
+            ..self.rows()
+        }
+    }
+
+    /// Iterate rows, failing with `LoadError::Cancelled` once the load's cancel token is
+    /// cancelled
+    ///
+    /// After the error, iteration ends. `iter_rows` ignores the token.
+    pub fn try_iter_rows(&self) -> TryRows<'_> {
+        TryRows {
+            inner: self.iter_rows(),
+            done: false,
+        }
+    }
+
+    /// Iterate rows for a pass over the whole table, without reporting progress
+    pub(crate) fn rows(&self) -> RowsIterator<'_> {
         RowsIterator {
             source: self,
+            observed: false,
             current_row: self.first_row,
             last_row: self.last_row,
             first_col: self.first_col,
             last_col: self.last_col,
         }
     }
+
+    /// Iterate rows up to (not including) the first fully empty row, ignoring anything below it
+    pub fn iter_rows_until_blank(&self) -> UntilBlank<'_> {
+        self.iter_rows().until_blank()
+    }
+}
+
+impl TryFrom<Range<DataType>> for WorkbookData {
+    type Error = LoadError;
+
+    /// Build from a range using the default header detection
+    fn try_from(range: Range<DataType>) -> Result<Self, Self::Error> {
+        Self::from_range(range, &LoadOptions::default())
+    }
 }
 
 pub struct RowsIterator<'a> {
     source: &'a WorkbookData,
+    /// Report progress, for iteration the caller asked for
+    observed: bool,
     pub current_row: u32,
     pub last_row: u32,
     pub first_col: u32,
     pub last_col: u32,
 }
 
+impl<'a> RowsIterator<'a> {
+    /// Skip rows where every column is empty
+    pub fn skip_empty(self) -> SkipEmpty<'a> {
+        SkipEmpty { inner: self }
+    }
+
+    /// End iteration at the first row where every column is empty
+    pub fn until_blank(self) -> UntilBlank<'a> {
+        UntilBlank {
+            inner: self,
+            done: false,
+        }
+    }
+}
+
 impl<'a> Iterator for RowsIterator<'a> {
     type Item = RowData<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.current_row > self.last_row {
+            trace::trace!(
+                rows = self.current_row.saturating_sub(self.source.first_row),
+                "finished iterating rows"
+            );
             return None;
         }
 
+        let row_number = self.current_row;
         self.current_row += 1;
 
+        if let Some(progress) = self.source.progress.as_ref().filter(|_| self.observed) {
+            let rows_read = row_number.saturating_sub(self.source.first_row) as usize + 1;
+            let total = (self.source.last_row + 1 - self.source.first_row) as usize;
+            progress.report_row(rows_read, Some(total));
+        }
+
         Some(RowData {
             source: self.source,
-            row_number: self.current_row,
+            row_number,
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (u64::from(self.last_row) + 1).saturating_sub(u64::from(self.current_row));
+        let len = usize::try_from(len).unwrap_or(usize::MAX);
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for RowsIterator<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current_row > self.last_row {
+            return None;
+        }
+
+        let row_number = self.last_row;
+        if row_number == self.current_row {
+            self.current_row += 1;
+        } else {
+            self.last_row -= 1;
+        }
+
+        Some(RowData {
+            source: self.source,
+            row_number,
+        })
+    }
+}
+
+impl<'a> ExactSizeIterator for RowsIterator<'a> {}
+
+impl<'a> IntoIterator for &'a WorkbookData {
+    type Item = RowData<'a>;
+    type IntoIter = RowsIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_rows()
+    }
+}
+
+/// Rows iterator adapter returned by `RowsIterator::skip_empty`
+pub struct SkipEmpty<'a> {
+    inner: RowsIterator<'a>,
+}
+
+impl<'a> Iterator for SkipEmpty<'a> {
+    type Item = RowData<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.by_ref().find(|row| !row.is_empty())
+    }
+}
+
+/// Rows iterator adapter returned by `RowsIterator::until_blank`
+pub struct UntilBlank<'a> {
+    inner: RowsIterator<'a>,
+    done: bool,
+}
+
+impl<'a> Iterator for UntilBlank<'a> {
+    type Item = RowData<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.inner.next() {
+            Some(row) if !row.is_empty() => Some(row),
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+/// Rows iterator returned by `WorkbookData::try_iter_rows`
+pub struct TryRows<'a> {
+    inner: RowsIterator<'a>,
+    done: bool,
+}
+
+impl<'a> Iterator for TryRows<'a> {
+    type Item = Result<RowData<'a>, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if let Err(err) = self
+            .inner
+            .source
+            .cancel
+            .as_ref()
+            .map_or(Ok(()), CancelToken::check)
+        {
+            trace::debug!(
+                rows = self
+                    .inner
+                    .current_row
+                    .saturating_sub(self.inner.source.first_row),
+                "row iteration cancelled"
+            );
+            self.done = true;
+            return Some(Err(err));
+        }
+
+        self.inner.next().map(Ok)
+    }
 }
 
 pub struct RowData<'a> {
@@ -183,34 +1155,303 @@ impl<'a> RowData<'a> {
     }
 
     /// Get the value in the cell of this row with the matching column header
-    pub fn get(&self, column_header: &str) -> Result<String, DataError> {
+    pub fn get(&self, column_header: &str) -> Result<Cow<'a, str>, DataError> {
         match self.source.get(self.row_number, column_header) {
             Some(value) => Ok(value),
             None => Err(DataError::NoValue(column_header.into())),
         }
     }
 
+    /// Parse a cell's text, failing with `DataError::CellError` on error values like `#N/A`
     pub fn parse<T: FromStr>(&self, column_header: &str) -> Result<T, DataError> {
-        let value_str = self.get(column_header)?;
+        let value = self.parse_source(column_header)?;
+        let column = &self.source.columns[self.source.header[column_header]];
+        let parsed = column.cell_str(value).parse();
+        self.source.record_parse(column_header, parsed.is_ok());
 
-        value_str.parse().map_err(|_| DataError::ParseError {
+        parsed.map_err(|_| DataError::ParseError {
             key: column_header.into(),
-            value: value_str,
+            value: self
+                .source
+                .error_text(self.row_number, column_header, value),
+            position: self.source.column_position(column_header),
         })
     }
 
+    /// Get the value in the cell of this row by a column handle from `WorkbookData::column_id`
+    pub fn get_by_id(&self, column: ColumnId) -> Option<Cow<'a, str>> {
+        self.source.get_by_id(self.row_number, column)
+    }
+
+    /// Get the value in the cell of this row by the zero-based position of its column
+    pub fn get_index(&self, index: u32) -> Option<Cow<'a, str>> {
+        self.source.get_by_index(self.row_number, index)
+    }
+
+    /// Check if the cell in this row with the matching column header is empty
+    pub fn is_cell_empty(&self, column_header: &str) -> bool {
+        self.source.is_cell_empty(self.row_number, column_header)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.source.is_row_empty(self.row_number)
     }
+
+    /// Get every cell of this row in column order, formatted the same way as `get`
+    ///
+    /// Repeated headers each get their own value, so the row can be written out as it was read.
+    pub fn values(&self) -> Vec<Cow<'a, str>> {
+        self.iter().map(|(_, value)| value).collect()
+    }
+
+    /// Iterate over `(header, value)` pairs in column order
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, Cow<'a, str>)> + 'a {
+        let (source, row_number) = (self.source, self.row_number);
+
+        source.columns.iter().enumerate().map(move |(i, column)| {
+            let value = source
+                .get_by_id(row_number, ColumnId(i))
+                .unwrap_or_default();
+            (column.name.as_str(), value)
+        })
+    }
+}
+
+/// Cut a range down to the cells it holds within a region, if there are any
+///
+/// Clamping to the used range first keeps a region like `A1:XFD1048576` from allocating
+/// the whole sheet.
+fn range_in_region(range: &Range<DataType>, region: Span) -> Option<Range<DataType>> {
+    let used = (range.start()?, range.end()?);
+    let (start, end) = coords::intersect(region, used)?;
+    Some(range.range(start, end))
+}
+
+/// Get the first row of a range with any value
+fn first_row_with_values(range: &Range<DataType>) -> Option<u32> {
+    let (start_row, _) = range.start()?;
+    range
+        .rows()
+        .position(|row| row.iter().any(|c| !cell_is_empty(c)))
+        .map(|i| start_row + i as u32)
+}
+
+/// Shrink a range to exclude trailing rows and columns with no data
+fn trim_range(range: Range<DataType>) -> Option<Range<DataType>> {
+    let (start_row, start_col) = range.start()?;
+    let (end_row, end_col) = range.end()?;
+
+    let mut last = None;
+    for (r, c, value) in range.used_cells() {
+        if !cell_is_empty(value) {
+            let (last_r, last_c) = last.unwrap_or((0, 0));
+            last = Some((last_r.max(r as u32), last_c.max(c as u32)));
+        }
+    }
+
+    let (last_r, last_c) = last?;
+    let end = (start_row + last_r, start_col + last_c);
+
+    if end == (end_row, end_col) {
+        Some(range)
+    } else {
+        Some(range.range((start_row, start_col), end))
+    }
+}
+
+/// Swap rows and columns, keeping absolute positions (so cell `(r, c)` moves to `(c, r)`)
+fn transpose_range(range: &Range<DataType>) -> Range<DataType> {
+    let ((start_row, start_col), (end_row, end_col)) = match (range.start(), range.end()) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return Range::empty(),
+    };
+
+    let mut transposed = Range::new((start_col, start_row), (end_col, end_row));
+    for (r, c, value) in range.used_cells() {
+        transposed.set_value((start_col + c as u32, start_row + r as u32), value.clone());
+    }
+    transposed
+}
+
+/// Copy the top-left value of each merged region into the rest of the region, limited to rows
+/// from `first_row` on and to the existing range bounds
+fn expand_merged_regions(range: &mut Range<DataType>, merged: &[Span], first_row: u32) {
+    let (end_row, end_col) = match range.end() {
+        Some(end) => end,
+        None => return,
+    };
+
+    for &(start, end) in merged {
+        let value = match range.get_value(start) {
+            Some(value) if !cell_is_empty(value) => value.clone(),
+            _ => continue,
+        };
+
+        for row in start.0.max(first_row)..=end.0.min(end_row) {
+            for col in start.1..=end.1.min(end_col) {
+                if (row, col) != start {
+                    range.set_value((row, col), value.clone());
+                }
+            }
+        }
+    }
+}
+
+fn cell_to_str(value: &DataType) -> Cow<'_, str> {
+    match value {
+        DataType::String(s) => Cow::Borrowed(s),
+        DataType::Empty => Cow::Borrowed(""),
+        value => Cow::Owned(value.to_string()),
+    }
+}
+
+fn cell_is_empty(value: &DataType) -> bool {
+    match value {
+        DataType::String(s) => s.is_empty(),
+        DataType::Empty => true,
+        _ => false,
+    }
 }
 
 pub fn from_path<P: AsRef<Path>>(path: P) -> Result<WorkbookData, LoadError> {
     WorkbookData::from_path(path)
 }
 
+pub fn from_file_bytes(bytes: &[u8]) -> Result<WorkbookData, LoadError> {
+    WorkbookData::from_file_bytes(bytes)
+}
+
+pub fn from_file_bytes_with_options(
+    bytes: &[u8],
+    options: &LoadOptions,
+) -> Result<WorkbookData, LoadError> {
+    WorkbookData::from_file_bytes_with_options(bytes, options)
+}
+
 pub fn from_path_with_sheet_name<P: AsRef<Path>>(
     path: P,
     sheet_name: &str,
 ) -> Result<WorkbookData, LoadError> {
     WorkbookData::from_path_with_sheet_name(path, sheet_name)
 }
+
+pub fn from_path_with_defined_name<P: AsRef<Path>>(
+    path: P,
+    name: &str,
+) -> Result<WorkbookData, LoadError> {
+    WorkbookData::from_path_with_defined_name(path, name)
+}
+
+pub fn from_path_with_table<P: AsRef<Path>>(
+    path: P,
+    table_name: &str,
+) -> Result<WorkbookData, LoadError> {
+    WorkbookData::from_path_with_table(path, table_name)
+}
+
+pub fn from_path_with_options<P: AsRef<Path>>(
+    path: P,
+    options: &LoadOptions,
+) -> Result<WorkbookData, LoadError> {
+    WorkbookData::from_path_with_options(path, options)
+}
+
+pub fn from_range(
+    range: Range<DataType>,
+    options: &LoadOptions,
+) -> Result<WorkbookData, LoadError> {
+    WorkbookData::from_range(range, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+
+    fn people() -> Range<DataType> {
+        let mut range = Range::new((0, 0), (3, 1));
+        for (row, cells) in [["Name", "Age"], ["Ann", "30"], ["Bob", "41"], ["Cho", "25"]]
+            .iter()
+            .enumerate()
+        {
+            for (col, cell) in cells.iter().enumerate() {
+                range.set_value((row as u32, col as u32), DataType::String(cell.to_string()));
+            }
+        }
+        range
+    }
+
+    #[test]
+    fn try_iter_rows_fails_once_cancelled() {
+        let token = CancelToken::new();
+        let data = from_range(people(), &LoadOptions::new().cancel_token(&token)).unwrap();
+
+        let mut rows = data.try_iter_rows();
+        assert_eq!(rows.next().unwrap().unwrap().number(), 1);
+        token.cancel();
+        assert!(matches!(rows.next(), Some(Err(LoadError::Cancelled))));
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn whole_table_passes_ignore_cancellation() {
+        let token = CancelToken::new();
+        let data = from_range(people(), &LoadOptions::new().cancel_token(&token)).unwrap();
+        token.cancel();
+
+        assert_eq!(data.iter_rows().count(), 3);
+        assert_eq!(data.rows_where("Name", |_| true).unwrap().len(), 3);
+        assert_eq!(data.to_table_string(10).lines().count(), 5);
+    }
+
+    #[test]
+    fn region_is_clamped_to_used_range() {
+        let data = from_range(people(), &LoadOptions::new().range("A1:XFD1048576")).unwrap();
+        assert_eq!(data.headers().collect::<Vec<_>>(), ["Name", "Age"]);
+        assert_eq!(data.iter_rows().count(), 3);
+
+        let data = from_range(people(), &LoadOptions::new().range("B2:Z1000000")).unwrap();
+        assert_eq!(data.headers().collect::<Vec<_>>(), ["30"]);
+        assert_eq!(data.iter_rows().count(), 2);
+
+        assert!(from_range(people(), &LoadOptions::new().range("D10:F20")).is_err());
+    }
+
+    #[test]
+    fn display_does_not_report_progress() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let options = LoadOptions::new().on_progress(move |_, _| {
+            counted.fetch_add(1, AtomicOrdering::Relaxed);
+        });
+        let data = from_range(people(), &options).unwrap();
+        let reported = calls.load(AtomicOrdering::Relaxed);
+
+        let _ = format!("{} {:?}", data, data);
+        assert_eq!(calls.load(AtomicOrdering::Relaxed), reported);
+    }
+
+    #[test]
+    fn repeated_headers_keep_every_column() {
+        let mut range = Range::new((0, 0), (1, 2));
+        for (col, (header, value)) in [("Name", "Ann"), ("Note", "a"), ("Note", "b")]
+            .iter()
+            .enumerate()
+        {
+            range.set_value((0, col as u32), DataType::String(header.to_string()));
+            range.set_value((1, col as u32), DataType::String(value.to_string()));
+        }
+        let data = from_range(range, &LoadOptions::new()).unwrap();
+        let row = data.rows().next().unwrap();
+
+        let cells: Vec<_> = row.to_owned_row().into_cells();
+        assert_eq!(cells[1], ("Note".to_owned(), "a".to_owned()));
+        assert_eq!(cells[2], ("Note".to_owned(), "b".to_owned()));
+        // A repeated header reads its first column everywhere
+        assert_eq!(row.get("Note").unwrap(), "a");
+        assert_eq!(row.to_map()["Note"], row.get("Note").unwrap());
+        assert_eq!(row.to_owned_row().get("Note"), Some("a"));
+        assert_eq!(data.compact().get(1, "Note").as_deref(), Some("a"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Numbers written with a locale's decimal and thousands separators, like `"1.234,56"`, and
+//! with currency symbols or percent signs
+
+use crate::{DataError, DataType, RowData};
+use std::str::FromStr;
+
+/// The separators a locale writes numbers with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    pub decimal: char,
+    /// Separates groups of three digits in the whole part; a space also matches other spaces,
+    /// such as the no-break spaces spreadsheets often write
+    pub grouping: char,
+}
+
+impl NumberLocale {
+    /// `1,234.56`
+    pub const US: NumberLocale = NumberLocale::new('.', ',');
+    /// `1.234,56`, as in Germany, Italy, Spain and the Netherlands
+    pub const EUROPEAN: NumberLocale = NumberLocale::new(',', '.');
+    /// `1 234,56`, as in France and much of Eastern Europe
+    pub const FRENCH: NumberLocale = NumberLocale::new(',', ' ');
+    /// `1'234.56`
+    pub const SWISS: NumberLocale = NumberLocale::new('.', '\'');
+
+    pub const fn new(decimal: char, grouping: char) -> Self {
+        Self { decimal, grouping }
+    }
+
+    fn is_grouping(&self, c: char) -> bool {
+        c == self.grouping || (self.grouping == ' ' && c.is_whitespace())
+    }
+
+    /// Rewrite a number in this locale as plain `1234.56`, ready for `str::parse`
+    ///
+    /// Grouping separators must fall between groups of three digits, so `"1.5"` is not read
+    /// as fifteen in a locale that groups with dots. Returns `None` if the text is not a number.
+    pub fn normalize(&self, text: &str) -> Option<String> {
+        let text = text.trim();
+        let (sign, digits) = match text.chars().next()? {
+            c @ ('-' | '+') => (Some(c), &text[1..]),
+            _ => (None, text),
+        };
+
+        let (whole, fraction) = match digits.split_once(self.decimal) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+
+        let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        let groups: Vec<&str> = whole.split(|c| self.is_grouping(c)).collect();
+        let grouped = groups.len() > 1;
+
+        let whole_valid = groups.iter().enumerate().all(|(i, group)| {
+            is_digits(group)
+                && match (grouped, i) {
+                    (false, _) => true,
+                    (true, 0) => (1..=3).contains(&group.len()),
+                    (true, _) => group.len() == 3,
+                }
+        });
+        let fraction_valid = fraction.is_none_or(is_digits);
+        let has_digits = !whole.is_empty() || fraction.is_some_and(|f| !f.is_empty());
+
+        if !whole_valid || !fraction_valid || !has_digits {
+            return None;
+        }
+
+        let mut normalized: String = sign.into_iter().chain(groups.concat().chars()).collect();
+        if let Some(fraction) = fraction.filter(|f| !f.is_empty()) {
+            normalized.push('.');
+            normalized.push_str(fraction);
+        }
+        Some(normalized)
+    }
+
+    /// Parse a number written in this locale
+    pub fn parse_f64(&self, text: &str) -> Option<f64> {
+        self.normalize(text)?.parse().ok()
+    }
+
+    /// Parse an amount of money like `"$1,234.50"`, `"-€5"`, `"1.234,50 EUR"` or `"($12.00)"`
+    ///
+    /// A currency symbol or three-letter code may come before or after the number, and
+    /// parentheses mark a negative amount as in accounting formats.
+    pub fn parse_currency(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let (negative, text) = match text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            Some(inner) => (true, inner.trim()),
+            None => (false, text),
+        };
+
+        let mut start = text.find(|c: char| c.is_ascii_digit())?;
+        let end = text.rfind(|c: char| c.is_ascii_digit())? + 1;
+
+        // Take in a leading decimal separator, as in "$.50", but not the dot of "Fr. 5"
+        if let Some(before) = text[..start].strip_suffix(self.decimal) {
+            if !before.ends_with(char::is_alphabetic) {
+                start = before.len();
+            }
+        }
+
+        // The sign may go before or after a leading symbol: "-$5" or "$-5"
+        let prefix = &text[..start];
+        let signs = prefix.matches(['-', '+']).count();
+        let prefix = prefix.replace(['-', '+'], "");
+        if signs > 1 || (signs == 1 && negative) {
+            return None;
+        }
+        let negative = negative || text[..start].contains('-');
+
+        if !is_currency_affix(prefix.trim()) || !is_currency_affix(text[end..].trim()) {
+            return None;
+        }
+
+        let amount = self.parse_f64(&text[start..end])?;
+        Some(if negative { -amount } else { amount })
+    }
+
+    /// Parse a percentage like `"15%"` or `"15 %"` as a fraction, `0.15`
+    ///
+    /// Text without a percent sign is taken to be a fraction already.
+    pub fn parse_percent(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        match text.strip_suffix('%') {
+            Some(number) => self.parse_f64(number).map(|n| n / 100.0),
+            None => self.parse_f64(text),
+        }
+    }
+}
+
+/// Currency symbols that may come before or after an amount, besides three-letter codes
+const CURRENCY_SYMBOLS: &[&str] = &[
+    "$", "€", "£", "¥", "₹", "₩", "₽", "₺", "₪", "¢", "₫", "₴", "₦", "₱", "฿", "R$", "US$", "C$",
+    "A$", "kr", "zł", "Fr.",
+];
+
+fn is_currency_affix(affix: &str) -> bool {
+    affix.is_empty()
+        || CURRENCY_SYMBOLS.contains(&affix)
+        || (affix.len() == 3 && affix.bytes().all(|b| b.is_ascii_uppercase()))
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        NumberLocale::US
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Parse a text cell with `parse_text`, or a numeric cell from its value like `parse`
+    fn parse_with<T: FromStr>(
+        &self,
+        column_header: &str,
+        parse_text: impl Fn(&str) -> Option<T>,
+    ) -> Result<T, DataError> {
+        let value = self.parse_source(column_header)?;
+
+        let parsed = match value {
+            DataType::String(s) => parse_text(s),
+            _ => {
+                let column = &self.source.columns[self.source.header[column_header]];
+                column.cell_str(value).parse().ok()
+            }
+        };
+
+        self.source.record_parse(column_header, parsed.is_some());
+
+        parsed.ok_or_else(|| DataError::ParseError {
+            key: column_header.into(),
+            value: self
+                .source
+                .error_text(self.row_number, column_header, value),
+            position: self.source.column_position(column_header),
+        })
+    }
+
+    /// Parse a value written with a locale's separators, like `parse`
+    ///
+    /// Only text cells are read with the locale; numeric cells already hold a number and are
+    /// parsed from it, whatever text the number text policy gives them.
+    pub fn parse_locale<T: FromStr>(
+        &self,
+        column_header: &str,
+        locale: NumberLocale,
+    ) -> Result<T, DataError> {
+        self.parse_with(column_header, |s| locale.normalize(s)?.parse().ok())
+    }
+
+    /// Get a number written with a locale's separators
+    pub fn get_f64_locale(
+        &self,
+        column_header: &str,
+        locale: NumberLocale,
+    ) -> Result<f64, DataError> {
+        self.parse_locale(column_header, locale)
+    }
+
+    /// Get an amount of money, ignoring currency symbols and thousands separators
+    ///
+    /// Numeric cells formatted as currency already hold the plain amount.
+    pub fn get_currency(&self, column_header: &str) -> Result<f64, DataError> {
+        self.get_currency_locale(column_header, NumberLocale::US)
+    }
+
+    pub fn get_currency_locale(
+        &self,
+        column_header: &str,
+        locale: NumberLocale,
+    ) -> Result<f64, DataError> {
+        self.parse_with(column_header, |s| locale.parse_currency(s))
+    }
+
+    /// Get a percentage as a fraction, so both `15%` as text and a cell formatted as `15%` give
+    /// `0.15`
+    ///
+    /// Numeric cells hold the fraction whatever their display format, and are returned as is.
+    pub fn get_percent(&self, column_header: &str) -> Result<f64, DataError> {
+        self.get_percent_locale(column_header, NumberLocale::US)
+    }
+
+    pub fn get_percent_locale(
+        &self,
+        column_header: &str,
+        locale: NumberLocale,
+    ) -> Result<f64, DataError> {
+        self.parse_with(column_header, |s| locale.parse_percent(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_range, LoadOptions, NumberText, Range};
+
+    #[test]
+    fn normalizes_grouped_numbers() {
+        let eu = NumberLocale::EUROPEAN;
+        assert_eq!(eu.normalize("1.234,56").as_deref(), Some("1234.56"));
+        assert_eq!(eu.normalize("-12.345.678").as_deref(), Some("-12345678"));
+        assert_eq!(eu.normalize(",5").as_deref(), Some(".5"));
+        assert_eq!(eu.normalize("1.5"), None);
+        assert_eq!(eu.normalize("12.34.567"), None);
+        assert_eq!(eu.normalize("-"), None);
+
+        let fr = NumberLocale::FRENCH;
+        assert_eq!(fr.parse_f64("1\u{a0}234,5"), Some(1234.5));
+        assert_eq!(NumberLocale::SWISS.parse_f64("1'234.5"), Some(1234.5));
+        assert_eq!(NumberLocale::US.parse_f64("1,234.5"), Some(1234.5));
+    }
+
+    #[test]
+    fn parses_currency() {
+        let us = NumberLocale::US;
+        assert_eq!(us.parse_currency("$1,234.50"), Some(1234.5));
+        assert_eq!(us.parse_currency("-$5"), Some(-5.0));
+        assert_eq!(us.parse_currency("$-5"), Some(-5.0));
+        assert_eq!(us.parse_currency("($12.00)"), Some(-12.0));
+        assert_eq!(us.parse_currency("$.50"), Some(0.5));
+        assert_eq!(us.parse_currency("Fr. 5"), Some(5.0));
+        assert_eq!(us.parse_currency("(-$5)"), None);
+        assert_eq!(us.parse_currency("5 apples"), None);
+        assert_eq!(
+            NumberLocale::EUROPEAN.parse_currency("1.234,50 EUR"),
+            Some(1234.5)
+        );
+    }
+
+    #[test]
+    fn parses_percentages() {
+        let us = NumberLocale::US;
+        assert_eq!(us.parse_percent("15%"), Some(0.15));
+        assert_eq!(us.parse_percent(" 15 % "), Some(0.15));
+        assert_eq!(us.parse_percent("0.15"), Some(0.15));
+        assert_eq!(NumberLocale::EUROPEAN.parse_percent("12,5%"), Some(0.125));
+        assert_eq!(us.parse_percent("%"), None);
+    }
+
+    #[test]
+    fn reads_text_with_the_locale_and_numbers_as_is() {
+        let mut range = Range::new((0, 0), (2, 0));
+        range.set_value((0, 0), DataType::String("Amount".into()));
+        range.set_value((1, 0), DataType::String("1.234,5".into()));
+        range.set_value((2, 0), DataType::Float(2.5));
+        let data = from_range(range, &LoadOptions::new()).unwrap();
+        let rows: Vec<_> = data.iter_rows().collect();
+
+        let eu = NumberLocale::EUROPEAN;
+        assert_eq!(rows[0].get_f64_locale("Amount", eu).unwrap(), 1234.5);
+        assert_eq!(rows[1].get_f64_locale("Amount", eu).unwrap(), 2.5);
+        assert!(matches!(
+            rows[0].get_f64_locale("Amount", NumberLocale::US),
+            Err(DataError::ParseError { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_numbers_from_the_value_under_any_number_text() {
+        let mut range = Range::new((0, 0), (1, 0));
+        range.set_value((0, 0), DataType::String("Count".into()));
+        range.set_value((1, 0), DataType::Float(3.0));
+        let options = LoadOptions::new().number_text(NumberText::Fixed(2));
+        let data = from_range(range, &options).unwrap();
+        let row = data.iter_rows().next().unwrap();
+
+        assert_eq!(row.get("Count").unwrap(), "3.00");
+        let count: i64 = row.parse_locale("Count", NumberLocale::EUROPEAN).unwrap();
+        assert_eq!(count, 3);
+    }
+}
@@ -0,0 +1,525 @@
+//! Translating error and validation messages through catalogs keyed by message code
+//!
+//! A catalog maps codes to templates whose `{name}` placeholders are replaced with the
+//! message's parameters (`{{` and `}}` are literal braces). A message whose code (or the code
+//! of one of its own lines) is missing from a catalog falls back to its English `Display`
+//! text. Codes and their parameters:
+//!
+//! | Code | Parameters |
+//! |------|------------|
+//! | `parse_error` | `position`, `column`, `value` |
+//! | `no_value` | `column` |
+//! | `duplicate_key` | `position`, `column`, `value`, `first_row`, `second_row` |
+//! | `undeclared_scale` | `column` |
+//! | `cell_error` | `position`, `column`, `error` |
+//! | `deserialize_error` | `row`, `message` |
+//! | `missing_column` | `column` |
+//! | `type_mismatch` | `position`, `column`, `expected`, `value` |
+//! | `empty_value` | `position`, `column` |
+//! | `constraint_failed` | `position`, `column`, `value`, `constraint` |
+//! | `duplicate_value` | `position`, `column`, `value`, `first_row` |
+//! | `must_not_be_empty`, `must_be_unique`, `must_be_number` | (none) |
+//! | `must_match` | `pattern` |
+//! | `must_be_between` | `min`, `max` |
+//! | `must_be_at_least` | `min` |
+//! | `must_be_at_most` | `max` |
+//! | `must_be_one_of` | `values` |
+//! | `row_violations` | `row`, `violations` |
+//! | `schema_mismatch` | (none) |
+//! | `validation_failed` | `rows` |
+//! | `validation_passed` | (none) |
+//! | `more_invalid_rows` | `more` |
+//! | `missing_columns` | `columns` |
+//! | `unexpected_columns` | `columns` |
+//! | `type_conflict` | `position`, `column`, `expected`, `example`, `rows` |
+//! | `more_rows` | `rows`, `more` |
+//! | `type_integer`, `type_float`, `type_bool`, `type_datetime`, `type_string` | (none) |
+//!
+//! Row numbers are shown the same way as in the English messages.
+
+use crate::schema::MAX_LISTED_ROWS;
+use crate::{
+    ColumnType, Constraint, DataError, SchemaMismatch, TypeConflict, ValidationReport, Violation,
+    Violations,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A line of a catalog file that is not `code = template`
+#[derive(Debug, thiserror::Error)]
+#[error("Line {} of message catalog is not 'code = message'", .0)]
+pub struct CatalogParseError(pub usize);
+
+/// Message templates for one language, keyed by message code
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    templates: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or replace) the template for a message code
+    pub fn message(mut self, code: &str, template: &str) -> Self {
+        self.templates.insert(code.to_owned(), template.to_owned());
+        self
+    }
+
+    /// Read a catalog from `code = template` lines, skipping blank lines and `#` comments
+    pub fn parse(text: &str) -> Result<Self, CatalogParseError> {
+        let mut catalog = Self::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some((code, template)) if !code.trim().is_empty() => {
+                    catalog = catalog.message(code.trim(), template.trim());
+                }
+                _ => return Err(CatalogParseError(i + 1)),
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.templates.get(code).map(String::as_str)
+    }
+
+    /// Fill in the template for `code`, or return `None` if the catalog does not have it
+    pub fn render(&self, code: &str, params: &[(&str, String)]) -> Option<String> {
+        self.get(code).map(|template| interpolate(template, params))
+    }
+
+    /// Fill in the template for `code`, or use `fallback` if the catalog does not have it
+    fn render_or(
+        &self,
+        code: &str,
+        params: &[(&str, String)],
+        fallback: impl fmt::Display,
+    ) -> String {
+        self.render(code, params)
+            .unwrap_or_else(|| fallback.to_string())
+    }
+}
+
+/// Replace `{name}` placeholders, leaving unknown names as they are
+fn interpolate(template: &str, params: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+
+        let placeholder = rest
+            .strip_prefix('{')
+            .and_then(|r| r.find('}').map(|end| &r[..end]))
+            .and_then(|name| params.iter().find(|(n, _)| *n == name));
+
+        match placeholder {
+            Some((name, value)) => {
+                out.push_str(value);
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                out.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// A message that can be rendered through a `MessageCatalog`
+pub trait Localize: fmt::Display {
+    /// The catalog key for this kind of message
+    fn code(&self) -> &'static str;
+
+    /// Render this message with `catalog`, falling back to English for missing codes
+    fn localize(&self, catalog: &MessageCatalog) -> String;
+}
+
+impl Localize for ColumnType {
+    fn code(&self) -> &'static str {
+        match self {
+            ColumnType::Integer => "type_integer",
+            ColumnType::Float => "type_float",
+            ColumnType::Bool => "type_bool",
+            ColumnType::DateTime => "type_datetime",
+            ColumnType::String => "type_string",
+        }
+    }
+
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        catalog.render_or(self.code(), &[], self)
+    }
+}
+
+impl Localize for DataError {
+    fn code(&self) -> &'static str {
+        match self {
+            DataError::ParseError { .. } => "parse_error",
+            DataError::NoValue(_) => "no_value",
+            DataError::DuplicateKey { .. } => "duplicate_key",
+            DataError::UndeclaredScale(_) => "undeclared_scale",
+            DataError::CellError { .. } => "cell_error",
+            #[cfg(feature = "serde")]
+            DataError::DeserializeError { .. } => "deserialize_error",
+        }
+    }
+
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        let params = match self {
+            DataError::ParseError {
+                key,
+                value,
+                position,
+            } => vec![
+                ("position", position.clone()),
+                ("column", key.clone()),
+                ("value", value.clone()),
+            ],
+            DataError::NoValue(key) | DataError::UndeclaredScale(key) => {
+                vec![("column", key.clone())]
+            }
+            DataError::CellError {
+                key,
+                kind,
+                position,
+            } => vec![
+                ("position", position.clone()),
+                ("column", key.clone()),
+                ("error", kind.to_string()),
+            ],
+            DataError::DuplicateKey {
+                key,
+                value,
+                rows,
+                position,
+            } => vec![
+                ("position", position.clone()),
+                ("column", key.clone()),
+                ("value", value.clone()),
+                ("first_row", (rows.0 + 1).to_string()),
+                ("second_row", (rows.1 + 1).to_string()),
+            ],
+            #[cfg(feature = "serde")]
+            DataError::DeserializeError { row, message } => {
+                vec![("row", row.to_string()), ("message", message.clone())]
+            }
+        };
+
+        catalog.render_or(self.code(), &params, self)
+    }
+}
+
+impl Localize for Violation {
+    fn code(&self) -> &'static str {
+        match self {
+            Violation::MissingColumn(_) => "missing_column",
+            Violation::TypeMismatch { .. } => "type_mismatch",
+            Violation::Empty { .. } => "empty_value",
+            Violation::Constraint { .. } => "constraint_failed",
+            Violation::Duplicate { .. } => "duplicate_value",
+        }
+    }
+
+    /// Nested type names and constraints are localized, but a missing code falls back to the
+    /// whole English message
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        let params = match self {
+            Violation::MissingColumn(column) => vec![("column", column.clone())],
+            Violation::TypeMismatch {
+                column,
+                position,
+                expected,
+                value,
+            } => vec![
+                ("position", position.clone()),
+                ("column", column.clone()),
+                ("expected", expected.localize(catalog)),
+                ("value", value.clone()),
+            ],
+            Violation::Empty { column, position } => {
+                vec![("position", position.clone()), ("column", column.clone())]
+            }
+            Violation::Constraint {
+                column,
+                position,
+                constraint,
+                value,
+            } => vec![
+                ("position", position.clone()),
+                ("column", column.clone()),
+                ("value", value.clone()),
+                ("constraint", constraint.localize(catalog)),
+            ],
+            Violation::Duplicate {
+                column,
+                position,
+                value,
+                first_row,
+            } => vec![
+                ("position", position.clone()),
+                ("column", column.clone()),
+                ("value", value.clone()),
+                ("first_row", (first_row + 1).to_string()),
+            ],
+        };
+
+        catalog.render_or(self.code(), &params, self)
+    }
+}
+
+impl Localize for Constraint {
+    fn code(&self) -> &'static str {
+        match self {
+            Constraint::NotEmpty => "must_not_be_empty",
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(_) => "must_match",
+            Constraint::Range {
+                min: Some(_),
+                max: Some(_),
+            } => "must_be_between",
+            Constraint::Range { min: Some(_), .. } => "must_be_at_least",
+            Constraint::Range { max: Some(_), .. } => "must_be_at_most",
+            Constraint::Range { .. } => "must_be_number",
+            Constraint::OneOf(_) => "must_be_one_of",
+            Constraint::Unique => "must_be_unique",
+        }
+    }
+
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        let params = match self {
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(regex) => vec![("pattern", regex.as_str().to_owned())],
+            Constraint::Range { min, max } => min
+                .map(|min| ("min", min.to_string()))
+                .into_iter()
+                .chain(max.map(|max| ("max", max.to_string())))
+                .collect(),
+            Constraint::OneOf(values) => vec![("values", format!("'{}'", values.join("', '")))],
+            Constraint::NotEmpty | Constraint::Unique => Vec::new(),
+        };
+
+        catalog.render_or(self.code(), &params, self)
+    }
+}
+
+impl Localize for Violations {
+    fn code(&self) -> &'static str {
+        "row_violations"
+    }
+
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        let violations: Vec<String> = self
+            .violations
+            .iter()
+            .map(|v| v.localize(catalog))
+            .collect();
+
+        let params = [
+            ("row", (self.row + 1).to_string()),
+            ("violations", violations.join("; ")),
+        ];
+
+        catalog.render_or(self.code(), &params, self)
+    }
+}
+
+impl Localize for TypeConflict {
+    fn code(&self) -> &'static str {
+        "type_conflict"
+    }
+
+    /// A conflict with more rows than are listed needs `more_rows` too, or falls back to English
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        let listed: Vec<String> = self
+            .rows
+            .iter()
+            .take(MAX_LISTED_ROWS)
+            .map(|r| (r + 1).to_string())
+            .collect();
+        let listed = listed.join(", ");
+
+        let rows = if self.rows.len() > MAX_LISTED_ROWS {
+            let more = (self.rows.len() - MAX_LISTED_ROWS).to_string();
+            match catalog.render("more_rows", &[("rows", listed), ("more", more)]) {
+                Some(rows) => rows,
+                None => return self.to_string(),
+            }
+        } else {
+            listed
+        };
+
+        let params = [
+            ("position", self.position.clone()),
+            ("column", self.column.clone()),
+            ("expected", self.expected.localize(catalog)),
+            ("example", self.example.clone()),
+            ("rows", rows),
+        ];
+
+        catalog.render_or(self.code(), &params, self)
+    }
+}
+
+/// Render the missing and unexpected columns lines of a mismatch or report, each on its own
+/// indented line, or `None` if the catalog lacks a code they need
+fn column_lines(
+    catalog: &MessageCatalog,
+    missing: &[String],
+    unexpected: &[String],
+) -> Option<String> {
+    let mut out = String::new();
+
+    for (code, columns) in [
+        ("missing_columns", missing),
+        ("unexpected_columns", unexpected),
+    ] {
+        if !columns.is_empty() {
+            let columns = format!("'{}'", columns.join("', '"));
+            out.push_str("\n  ");
+            out.push_str(&catalog.render(code, &[("columns", columns)])?);
+        }
+    }
+
+    Some(out)
+}
+
+impl Localize for SchemaMismatch {
+    fn code(&self) -> &'static str {
+        "schema_mismatch"
+    }
+
+    /// Render one line for the summary and each kind of difference
+    ///
+    /// Type conflicts are localized on their own; if the summary or column lines are missing
+    /// from the catalog, the whole message falls back to English.
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        let summary = catalog.render(self.code(), &[]).and_then(|summary| {
+            Some(summary + &column_lines(catalog, &self.missing, &self.unexpected)?)
+        });
+        let mut out = match summary {
+            Some(summary) => summary,
+            None => return self.to_string(),
+        };
+
+        for conflict in &self.type_conflicts {
+            out.push_str("\n  ");
+            out.push_str(&conflict.localize(catalog));
+        }
+
+        out
+    }
+}
+
+impl Localize for ValidationReport {
+    fn code(&self) -> &'static str {
+        if self.is_valid() {
+            "validation_passed"
+        } else {
+            "validation_failed"
+        }
+    }
+
+    /// Render one line for the summary, the column differences and each listed row
+    ///
+    /// Rows are localized on their own; if the summary, column or "more rows" lines are
+    /// missing from the catalog, the whole report falls back to English.
+    fn localize(&self, catalog: &MessageCatalog) -> String {
+        if self.is_valid() {
+            return catalog.render_or(self.code(), &[], self);
+        }
+
+        let rows = self.rows.len().to_string();
+        let summary = catalog
+            .render(self.code(), &[("rows", rows)])
+            .and_then(|summary| {
+                Some(summary + &column_lines(catalog, &self.missing, &self.unexpected)?)
+            });
+        let mut out = match summary {
+            Some(summary) => summary,
+            None => return self.to_string(),
+        };
+
+        for row in self.rows.iter().take(MAX_LISTED_ROWS) {
+            out.push_str("\n  ");
+            out.push_str(&row.localize(catalog));
+        }
+
+        if self.rows.len() > MAX_LISTED_ROWS {
+            let more = (self.rows.len() - MAX_LISTED_ROWS).to_string();
+            match catalog.render("more_invalid_rows", &[("more", more)]) {
+                Some(more) => {
+                    out.push_str("\n  ");
+                    out.push_str(&more);
+                }
+                None => return self.to_string(),
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> ValidationReport {
+        let violation = Violation::Empty {
+            column: "Name".into(),
+            position: "B3".into(),
+        };
+        ValidationReport {
+            rows: vec![Violations {
+                row: 2,
+                violations: vec![violation],
+            }],
+            missing: vec!["Price".into()],
+            unexpected: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_english_text() {
+        let report = report();
+        assert_eq!(report.localize(&MessageCatalog::new()), report.to_string());
+
+        // Without `missing_columns`, the summary alone would leave the report half translated
+        let partial = MessageCatalog::new().message("validation_failed", "{rows} Zeilen ungültig");
+        assert_eq!(report.localize(&partial), report.to_string());
+    }
+
+    #[test]
+    fn renders_each_line_from_the_catalog() {
+        let catalog = MessageCatalog::parse(
+            "validation_failed = {rows} Zeilen ungültig
+             missing_columns = Fehlende Spalten: {columns}
+             row_violations = Zeile {row}: {violations}
+             empty_value = {position} / '{column}' darf nicht leer sein",
+        )
+        .unwrap();
+
+        assert_eq!(
+            report().localize(&catalog),
+            "1 Zeilen ungültig\n  Fehlende Spalten: 'Price'\n  Zeile 3: B3 / 'Name' darf nicht leer sein"
+        );
+    }
+}
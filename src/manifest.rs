@@ -0,0 +1,173 @@
+//! Manifests describing exported data, so consumers can verify they received all of it
+//!
+//! Hashes are SHA-256, rendered as lowercase hex. Data hashes cover each cell's string value
+//! (as returned by `get`) followed by a 0x1F separator, with a 0x1E separator after each row,
+//! and start with the header row.
+
+use crate::WorkbookData;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+pub(crate) const UNIT_SEPARATOR: &[u8] = b"\x1f";
+pub(crate) const RECORD_SEPARATOR: &[u8] = b"\x1e";
+
+pub(crate) fn to_hex(digest: &[u8]) -> String {
+    digest.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// An exported file listed in a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestFile {
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Row count, columns and content hashes of a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub rows: usize,
+    pub columns: Vec<String>,
+    /// Hash of each column's values, in column order
+    pub column_hashes: Vec<String>,
+    /// Hash of the headers and every data row
+    pub content_hash: String,
+    pub files: Vec<ManifestFile>,
+}
+
+impl Manifest {
+    /// Add an exported file, hashing its contents
+    pub fn add_file<P: AsRef<Path>>(mut self, path: P) -> io::Result<Self> {
+        self.files.push(hash_file(path.as_ref())?);
+        Ok(self)
+    }
+
+    /// Check that `data` has the same row count, columns and contents as this manifest
+    pub fn verify(&self, data: &WorkbookData) -> bool {
+        let other = data.manifest();
+        self.rows == other.rows
+            && self.columns == other.columns
+            && self.column_hashes == other.column_hashes
+            && self.content_hash == other.content_hash
+    }
+
+    /// List the files whose size or hash no longer matches, or that cannot be read
+    pub fn verify_files(&self) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|f| hash_file(Path::new(&f.path)).ok().as_ref() != Some(*f))
+            .map(|f| f.path.as_str())
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let strings = |v: &[String]| {
+            v.iter()
+                .map(|s| json_string(s))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"path\": {}, \"bytes\": {}, \"sha256\": {}}}",
+                    json_string(&f.path),
+                    f.bytes,
+                    json_string(&f.sha256)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"rows\": {},\n  \"columns\": [{}],\n  \"column_hashes\": [{}],\n  \"content_hash\": {},\n  \"files\": [{}]\n}}\n",
+            self.rows,
+            strings(&self.columns),
+            strings(&self.column_hashes),
+            json_string(&self.content_hash),
+            files.join(", ")
+        )
+    }
+
+    /// Write the manifest as JSON, e.g. next to the exported files
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
+
+pub(crate) fn hash_file(path: &Path) -> io::Result<ManifestFile> {
+    let mut hasher = Sha256::new();
+    let bytes = io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(ManifestFile {
+        path: path.to_string_lossy().into_owned(),
+        bytes,
+        sha256: to_hex(&hasher.finalize()),
+    })
+}
+
+impl WorkbookData {
+    /// Describe the data rows for an export manifest
+    pub fn manifest(&self) -> Manifest {
+        let columns: Vec<String> = self.headers().map(String::from).collect();
+
+        let mut content = Sha256::new();
+        for header in &columns {
+            content.update(header.as_bytes());
+            content.update(UNIT_SEPARATOR);
+        }
+        content.update(RECORD_SEPARATOR);
+
+        let mut column_hashers: Vec<Sha256> = columns.iter().map(|_| Sha256::new()).collect();
+        let mut rows = 0;
+
+        for row in self.rows() {
+            for ((_, value), hasher) in row.iter().zip(&mut column_hashers) {
+                hasher.update(value.as_bytes());
+                hasher.update(UNIT_SEPARATOR);
+                content.update(value.as_bytes());
+                content.update(UNIT_SEPARATOR);
+            }
+            content.update(RECORD_SEPARATOR);
+            rows += 1;
+        }
+
+        Manifest {
+            rows,
+            columns,
+            column_hashes: column_hashers
+                .into_iter()
+                .map(|h| to_hex(&h.finalize()))
+                .collect(),
+            content_hash: to_hex(&content.finalize()),
+            files: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{DataError, DataType, Range, RowData, WorkbookData};
+use std::sync::OnceLock;
+
+impl WorkbookData {
+    /// Copy the rows matching `filter` into a new, compact `WorkbookData`
+    ///
+    /// The copy owns only the header and matching rows, so the (possibly huge) original can be
+    /// dropped. Row numbers in the copy start at 1, directly below the header.
+    pub fn materialize<F>(&self, filter: F) -> WorkbookData
+    where
+        F: FnMut(&RowData) -> bool,
+    {
+        let all: Vec<usize> = (0..self.columns.len()).collect();
+        self.materialize_indices(&all, filter)
+    }
+
+    /// Like `materialize`, but keeping only the given columns (in the given order)
+    ///
+    /// An empty selection keeps every column.
+    pub fn materialize_columns<F>(
+        &self,
+        column_headers: &[&str],
+        filter: F,
+    ) -> Result<WorkbookData, DataError>
+    where
+        F: FnMut(&RowData) -> bool,
+    {
+        if column_headers.is_empty() {
+            return Ok(self.materialize(filter));
+        }
+
+        let indices = column_headers
+            .iter()
+            .map(|h| {
+                self.header
+                    .get(*h)
+                    .copied()
+                    .ok_or_else(|| DataError::NoValue((*h).into()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.materialize_indices(&indices, filter))
+    }
+
+    fn materialize_indices<F>(&self, indices: &[usize], mut filter: F) -> WorkbookData
+    where
+        F: FnMut(&RowData) -> bool,
+    {
+        let rows: Vec<u32> = self
+            .rows()
+            .filter(|row| filter(row))
+            .map(|row| row.number())
+            .collect();
+
+        let mut range = Range::new((0, 0), (rows.len() as u32, indices.len() as u32 - 1));
+
+        for (c, &i) in indices.iter().enumerate() {
+            let column = &self.columns[i];
+
+            range.set_value((0, c as u32), DataType::String(column.name.clone()));
+
+            for (r, &row_number) in rows.iter().enumerate() {
+                if let Some(value) = self.range.get_value((row_number, column.index)) {
+                    range.set_value((r as u32 + 1, c as u32), value.clone());
+                }
+            }
+        }
+
+        let mut data =
+            Self::with_header(range, 0).expect("materialized range always has a header row");
+
+        data.sheet_name = self.sheet_name.clone();
+        data.fingerprint = OnceLock::from(self.fingerprint().map(String::from));
+        for (c, &i) in indices.iter().enumerate() {
+            for (r, &row_number) in rows.iter().enumerate() {
+                let source = (row_number, self.columns[i].index);
+                let position = (r as u32 + 1, c as u32);
+                if let Some(formula) = self.formulas.get(&source) {
+                    data.formulas.insert(position, formula.clone());
+                }
+                if let Some(target) = self.hyperlinks.get(&source) {
+                    data.hyperlinks.insert(position, target.clone());
+                }
+                if let Some(comment) = self.comments.get(&source) {
+                    data.comments.insert(position, comment.clone());
+                }
+                if let Some(code) = self.number_formats.get(&source) {
+                    data.number_formats.insert(position, code.clone());
+                }
+            }
+        }
+        for (column, &i) in data.columns.iter_mut().zip(indices) {
+            column.redacted = self.columns[i].redacted;
+            column.money_scale = self.columns[i].money_scale;
+            column.round_places = self.columns[i].round_places;
+        }
+
+        data
+    }
+}
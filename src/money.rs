@@ -0,0 +1,154 @@
+use crate::{cell_to_str, rounding, DataError, DataType, RowData, WorkbookData};
+
+/// Parse a plain decimal string into an integer number of minor units, without rounding
+fn parse_minor_units(text: &str, scale: u32) -> Option<i64> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (digits, ""),
+    };
+
+    let valid = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !valid(whole) || !valid(fraction) {
+        return None;
+    }
+
+    // Trailing zeros beyond the scale are harmless; anything else would need rounding
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > scale as usize {
+        return None;
+    }
+
+    let mut units: i64 = 0;
+    for b in whole
+        .bytes()
+        .chain(fraction.bytes())
+        .chain(std::iter::repeat_n(b'0', scale as usize - fraction.len()))
+    {
+        units = units.checked_mul(10)?.checked_add(i64::from(b - b'0'))?;
+    }
+
+    Some(if negative { -units } else { units })
+}
+
+impl WorkbookData {
+    /// Declare the number of decimal places of a money column's minor unit (e.g. 2 for USD)
+    ///
+    /// Returns `false` if no column has this header.
+    pub fn set_money_scale(&mut self, column_header: &str, scale: u32) -> bool {
+        let mut found = false;
+        for column in self.columns.iter_mut().filter(|c| c.name == column_header) {
+            column.money_scale = Some(scale);
+            found = true;
+        }
+        found
+    }
+
+    pub fn money_scale(&self, column_header: &str) -> Option<u32> {
+        self.columns[self.column_id(column_header)?.0].money_scale
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get an amount in integer minor units (e.g. cents) using the column's declared scale
+    ///
+    /// Text is parsed exactly and rejected if it has more decimal places than the scale.
+    /// Numeric cells are rounded to the scale first, since they are stored as floats, from the
+    /// decimal the cell shows and half away from zero like `round_to`: 1.005 is 101 cents.
+    pub fn get_money(&self, column_header: &str) -> Result<i64, DataError> {
+        let scale = self
+            .source
+            .money_scale(column_header)
+            .ok_or_else(|| DataError::UndeclaredScale(column_header.into()))?;
+
+        let value = self.parse_source(column_header)?;
+
+        let units = match value {
+            DataType::Float(f) => {
+                parse_minor_units(&rounding::format_fixed(*f, scale as usize), scale)
+            }
+            value => parse_minor_units(&cell_to_str(value), scale),
+        };
+
+        self.source.record_parse(column_header, units.is_some());
+
+        units.ok_or_else(|| DataError::ParseError {
+            key: column_header.into(),
+            value: self
+                .source
+                .error_text(self.row_number, column_header, value),
+            position: self.source.column_position(column_header),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_range, LoadOptions, Range};
+
+    fn amounts(values: &[DataType]) -> WorkbookData {
+        let mut range = Range::new((0, 0), (values.len() as u32, 0));
+        range.set_value((0, 0), DataType::String("Amount".into()));
+        for (i, value) in values.iter().enumerate() {
+            range.set_value((i as u32 + 1, 0), value.clone());
+        }
+        let mut data = from_range(range, &LoadOptions::new()).unwrap();
+        assert!(data.set_money_scale("Amount", 2));
+        data
+    }
+
+    fn cents(data: &WorkbookData) -> Vec<Option<i64>> {
+        data.rows()
+            .map(|row| row.get_money("Amount").ok())
+            .collect()
+    }
+
+    #[test]
+    fn rounds_half_cents_away_from_zero() {
+        let data = amounts(&[
+            DataType::Float(0.125),
+            DataType::Float(1.005),
+            DataType::Float(-1.005),
+            DataType::Float(-0.125),
+            DataType::Float(2.675),
+            DataType::Float(0.1 + 0.2),
+            DataType::Int(7),
+        ]);
+        assert_eq!(
+            cents(&data),
+            [
+                Some(13),
+                Some(101),
+                Some(-101),
+                Some(-13),
+                Some(268),
+                Some(30),
+                Some(700)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_text_exactly() {
+        let data = amounts(&[
+            DataType::String("12.30".into()),
+            DataType::String("-0.5".into()),
+            DataType::String("1.005".into()),
+            DataType::String("1.000".into()),
+            DataType::String("$5".into()),
+        ]);
+        assert_eq!(cents(&data), [Some(1230), Some(-50), None, Some(100), None]);
+
+        let row = data.rows().next().unwrap();
+        assert!(matches!(
+            row.get_money("Missing"),
+            Err(DataError::UndeclaredScale(_))
+        ));
+    }
+}
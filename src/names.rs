@@ -0,0 +1,74 @@
+//! Defined names, which templates use to mark an import region independently of layout
+
+use crate::coords::{self, Span};
+use crate::{trace, LoadError, LoadOptions, WorkbookData};
+use calamine::{open_workbook_auto, Reader};
+use std::path::Path;
+
+/// List a workbook's defined names with what they refer to, like
+/// `("ImportArea", "Data!$B$4:$F$200")`
+///
+/// Delimited files have no defined names.
+pub fn defined_names<P: AsRef<Path>>(path: P) -> Result<Vec<(String, String)>, LoadError> {
+    let path = path.as_ref();
+
+    #[cfg(feature = "csv")]
+    if crate::delimited::delimiter(path).is_some() {
+        return Ok(Vec::new());
+    }
+
+    Ok(open_workbook_auto(path)?.defined_names().to_vec())
+}
+
+/// Find the sheet and range a defined name refers to, matching names case-insensitively as
+/// Excel does
+pub(crate) fn resolve(path: &Path, name: &str) -> Result<(String, Span), LoadError> {
+    let filename = path.to_string_lossy();
+    resolve_in(&defined_names(path)?, &filename, name)
+}
+
+/// Find a defined name among those already read from a workbook
+pub(crate) fn resolve_in(
+    names: &[(String, String)],
+    filename: &str,
+    name: &str,
+) -> Result<(String, Span), LoadError> {
+    let reference = names
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, reference)| reference.clone())
+        .ok_or_else(|| LoadError::UnknownDefinedName {
+            filename: filename.to_owned(),
+            name: name.to_owned(),
+        })?;
+
+    // Constants, formulas and multi-area references do not describe a single table
+    coords::parse_sheet_range(&reference).ok_or(LoadError::InvalidRange(reference))
+}
+
+impl WorkbookData {
+    /// Load the range a defined name refers to, using its first row as the header
+    pub fn from_path_with_defined_name<P: AsRef<Path>>(
+        path: P,
+        name: &str,
+    ) -> Result<Self, LoadError> {
+        Self::from_path_with_options(path, &LoadOptions::new().defined_name(name))
+    }
+
+    /// Get `options` with its defined name replaced by the sheet name and range it refers to
+    pub(crate) fn defined_name_options(
+        path: &Path,
+        name: &str,
+        options: &LoadOptions,
+    ) -> Result<LoadOptions, LoadError> {
+        let (sheet_name, region) = resolve(path, name)?;
+        trace::debug!(name, sheet = %sheet_name, range = %coords::format_range(region), "resolved defined name");
+
+        let mut options = options
+            .clone()
+            .sheet_name(&sheet_name)
+            .range(&coords::format_range(region));
+        options.defined_name = None;
+        Ok(options)
+    }
+}
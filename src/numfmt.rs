@@ -0,0 +1,872 @@
+//! Excel number formats, to show values the way the spreadsheet displays them
+
+use crate::coords;
+use crate::rounding;
+use crate::xlsx::{self, XlsxPackage};
+use crate::{CellErrorKind, DataError, DataType, LoadError, RowData, WorkbookData};
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+/// Shown for date cells whose format is not known, with and without a time of day
+const DEFAULT_DATE_FORMAT: &str = "yyyy-mm-dd";
+const DEFAULT_DATETIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+
+/// Excel serial number of 1970-01-01
+const UNIX_EPOCH_SERIAL: i64 = 25569;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Get the code of a built-in format, which styles refer to by id only
+fn builtin_format(id: u32) -> Option<&'static str> {
+    Some(match id {
+        0 => "General",
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        12 => "# ?/?",
+        13 => "# ??/??",
+        14 => "mm-dd-yy",
+        15 => "d-mmm-yy",
+        16 => "d-mmm",
+        17 => "mmm-yy",
+        18 => "h:mm AM/PM",
+        19 => "h:mm:ss AM/PM",
+        20 => "h:mm",
+        21 => "h:mm:ss",
+        22 => "m/d/yy h:mm",
+        37 => "#,##0 ;(#,##0)",
+        38 => "#,##0 ;[Red](#,##0)",
+        39 => "#,##0.00;(#,##0.00)",
+        40 => "#,##0.00;[Red](#,##0.00)",
+        45 => "mm:ss",
+        46 => "[h]:mm:ss",
+        47 => "mmss.0",
+        48 => "##0.0E+0",
+        49 => "@",
+        _ => return None,
+    })
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// Get the number format code of each cell style, by style index
+    fn style_formats(&mut self) -> Result<Vec<String>, LoadError> {
+        let styles = self
+            .relationships("xl/workbook.xml")?
+            .into_iter()
+            .find(|r| r.kind.ends_with("/styles"))
+            .map_or_else(|| "xl/styles.xml".to_owned(), |r| r.target);
+        let xml = match self.read_part(&styles)? {
+            Some(xml) => xml,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = quick_xml::Reader::from_reader(xml.as_slice());
+        let mut buf = Vec::new();
+        let mut custom: HashMap<u32, String> = HashMap::new();
+        let mut format_ids = Vec::new();
+        let mut in_cell_xfs = false;
+
+        loop {
+            match reader.read_event(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name() {
+                    b"numFmt" => {
+                        let id = xlsx::attr(e, b"numFmtId").and_then(|id| id.parse().ok());
+                        if let (Some(id), Some(code)) = (id, xlsx::attr(e, b"formatCode")) {
+                            custom.insert(id, code);
+                        }
+                    }
+                    b"cellXfs" => in_cell_xfs = true,
+                    b"xf" if in_cell_xfs => format_ids.push(
+                        xlsx::attr(e, b"numFmtId")
+                            .and_then(|id| id.parse::<u32>().ok())
+                            .unwrap_or(0),
+                    ),
+                    _ => {}
+                },
+                Event::End(ref e) if e.local_name() == b"cellXfs" => in_cell_xfs = false,
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(format_ids
+            .into_iter()
+            .map(|id| match custom.get(&id) {
+                Some(code) => code.clone(),
+                None => builtin_format(id).unwrap_or("General").to_owned(),
+            })
+            .collect())
+    }
+
+    /// Get the number format code of every cell of a sheet that has one, by absolute
+    /// (row, column)
+    ///
+    /// Cells in the `General` and text (`@`) formats are left out.
+    pub fn cell_number_formats(
+        &mut self,
+        sheet_name: &str,
+    ) -> Result<HashMap<(u32, u32), String>, LoadError> {
+        let xml = match self.sheet_xml(sheet_name)? {
+            Some(xml) => xml,
+            None => return Ok(HashMap::new()),
+        };
+        let formats = self.style_formats()?;
+
+        let mut cells = HashMap::new();
+        xlsx::for_each_element(&xml, |e| {
+            if e.local_name() != b"c" {
+                return;
+            }
+
+            let cell = xlsx::attr(e, b"r").as_deref().and_then(coords::parse_cell);
+            let style = xlsx::attr(e, b"s").and_then(|s| s.parse::<usize>().ok());
+            if let (Some(cell), Some(code)) = (cell, style.and_then(|s| formats.get(s))) {
+                if !code.eq_ignore_ascii_case("General") && code != "@" {
+                    cells.insert(cell, code.clone());
+                }
+            }
+        })?;
+        Ok(cells)
+    }
+}
+
+/// Read the number formats of a sheet, swapping rows and columns when `transposed`
+pub(crate) fn read_number_formats<R: Read + Seek>(
+    package: &mut XlsxPackage<R>,
+    sheet_name: &str,
+    transposed: bool,
+) -> Result<HashMap<(u32, u32), String>, LoadError> {
+    Ok(package
+        .cell_number_formats(sheet_name)?
+        .into_iter()
+        .map(|((row, col), code)| {
+            let position = if transposed { (col, row) } else { (row, col) };
+            (position, code)
+        })
+        .collect())
+}
+
+/// Split a format code into its `;`-separated sections
+fn sections(code: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut bracketed = false;
+    let mut escaped = false;
+
+    for (i, c) in code.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if !quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '[' if !quoted => bracketed = true,
+            ']' if !quoted => bracketed = false,
+            ';' if !quoted && !bracketed => {
+                sections.push(&code[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    sections.push(&code[start..]);
+    sections
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    /// A digit placeholder: `0`, `#` or `?`
+    Digit(char),
+    Point,
+    Comma,
+    Percent,
+    Exponent(char),
+    General,
+    Text,
+    /// A date or time part: the letter and how many times it repeats
+    Date(char, usize),
+    /// Elapsed hours, minutes or seconds, like `[h]`
+    Elapsed(char),
+    /// `AM/PM`, or `A/P` when short
+    AmPm {
+        short: bool,
+    },
+}
+
+fn tokenize(section: &str) -> Vec<Token> {
+    let chars: Vec<char> = section.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let starts_with = |i: usize, s: &str| {
+        let rest: String = chars[i..].iter().take(s.len()).collect();
+        rest.eq_ignore_ascii_case(s)
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+
+        match c {
+            '"' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == '"')
+                    .map_or(chars.len(), |p| i + p);
+                tokens.push(Token::Literal(chars[i..end].iter().collect()));
+                i = end + 1;
+            }
+            '\\' => {
+                if let Some(&c) = chars.get(i) {
+                    tokens.push(Token::Literal(c.to_string()));
+                    i += 1;
+                }
+            }
+            '_' => {
+                tokens.push(Token::Literal(" ".into()));
+                i += 1;
+            }
+            '*' => i += 1,
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map_or(chars.len(), |p| i + p);
+                let content: String = chars[i..end].iter().collect();
+                i = end + 1;
+
+                if let Some(currency) = content.strip_prefix('$') {
+                    let symbol = currency.split('-').next().unwrap_or_default();
+                    tokens.push(Token::Literal(symbol.to_owned()));
+                } else if !content.is_empty()
+                    && content
+                        .chars()
+                        .all(|c| c == content.chars().next().unwrap())
+                    && matches!(
+                        content.chars().next(),
+                        Some('h' | 'H' | 'm' | 'M' | 's' | 'S')
+                    )
+                {
+                    let unit = content.chars().next().unwrap().to_ascii_lowercase();
+                    tokens.push(Token::Elapsed(unit));
+                }
+                // Anything else is a color or condition
+            }
+            _ if starts_with(i - 1, "General") => {
+                tokens.push(Token::General);
+                i += "General".len() - 1;
+            }
+            _ if starts_with(i - 1, "AM/PM") => {
+                tokens.push(Token::AmPm { short: false });
+                i += "AM/PM".len() - 1;
+            }
+            _ if starts_with(i - 1, "A/P") => {
+                tokens.push(Token::AmPm { short: true });
+                i += "A/P".len() - 1;
+            }
+            '0' | '#' | '?' => tokens.push(Token::Digit(c)),
+            '.' => tokens.push(Token::Point),
+            ',' => tokens.push(Token::Comma),
+            '%' => tokens.push(Token::Percent),
+            '@' => tokens.push(Token::Text),
+            'E' | 'e' if matches!(chars.get(i), Some('+' | '-')) => {
+                tokens.push(Token::Exponent(chars[i]));
+                i += 1;
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                let letter = c.to_ascii_lowercase();
+                let mut count = 1;
+                while chars
+                    .get(i)
+                    .is_some_and(|c| c.to_ascii_lowercase() == letter)
+                {
+                    count += 1;
+                    i += 1;
+                }
+                tokens.push(Token::Date(letter, count));
+            }
+            c => tokens.push(Token::Literal(c.to_string())),
+        }
+    }
+
+    tokens
+}
+
+/// Format a number the way Excel's General format does, with up to 10 significant digits
+fn general(n: f64) -> String {
+    if !n.is_finite() {
+        return n.to_string();
+    }
+    if n.fract() == 0.0 && n.abs() < 1e11 {
+        return format!("{}", n as i64);
+    }
+
+    let magnitude = n.abs().log10().floor() as i32;
+    if !(-9..11).contains(&magnitude) {
+        let (mantissa, exponent) = rounding::format_exponent(n, 5);
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        let sign = if exponent < 0 { '-' } else { '+' };
+        return format!("{}E{}{:02}", mantissa, sign, exponent.abs());
+    }
+
+    let decimals = (9 - magnitude).clamp(0, 10) as usize;
+    let text = rounding::format_fixed(n, decimals);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        text
+    }
+}
+
+/// Format a number with a code already split into tokens
+fn format_number(n: f64, tokens: &[Token]) -> String {
+    let has_digits = tokens.iter().any(|t| matches!(t, Token::Digit(_)));
+    let is_fraction = tokens
+        .iter()
+        .any(|t| matches!(t, Token::Literal(s) if s == "/"));
+
+    if has_digits && is_fraction {
+        return general(n);
+    }
+
+    if tokens
+        .iter()
+        .any(|t| matches!(t, Token::General | Token::Text))
+    {
+        return tokens
+            .iter()
+            .map(|t| match t {
+                Token::General | Token::Text => general(n),
+                Token::Literal(s) => s.clone(),
+                _ => String::new(),
+            })
+            .collect();
+    }
+
+    if !has_digits {
+        return literals(tokens);
+    }
+
+    if let Some(e) = tokens.iter().position(|t| matches!(t, Token::Exponent(_))) {
+        return format_scientific(n, tokens, e);
+    }
+
+    let percent = tokens.iter().filter(|t| **t == Token::Percent).count();
+    let mut n = n * 100f64.powi(percent as i32);
+
+    let point = tokens.iter().position(|t| *t == Token::Point);
+    let (whole, fraction) = tokens.split_at(point.unwrap_or(tokens.len()));
+
+    // Commas right after the last digit scale by thousands; others between digits group
+    let scaling_at = tokens
+        .iter()
+        .rposition(|t| matches!(t, Token::Digit(_)))
+        .map_or(tokens.len(), |last| last + 1);
+    let scaling = tokens[scaling_at..]
+        .iter()
+        .take_while(|t| **t == Token::Comma)
+        .count();
+    let is_scaling = |i: usize| (scaling_at..scaling_at + scaling).contains(&i);
+    n /= 1000f64.powi(scaling as i32);
+
+    let last_digit = whole.iter().rposition(|t| matches!(t, Token::Digit(_)));
+    let first_digit = whole.iter().position(|t| matches!(t, Token::Digit(_)));
+    let grouping = match (first_digit, last_digit) {
+        (Some(first), Some(last)) => whole[first..last].contains(&Token::Comma),
+        _ => false,
+    };
+
+    let decimals = fraction
+        .iter()
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count();
+    let text = rounding::format_fixed(n.abs(), decimals);
+    let (int_digits, frac_digits) = text.split_once('.').unwrap_or((&text, ""));
+
+    let mut out = String::new();
+    if n < 0.0 && text.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        out.push('-');
+    }
+
+    // Whole part: literals before the first digit, the digits, then what follows
+    for token in &whole[..first_digit.unwrap_or(whole.len())] {
+        out.push_str(&token_literal(token));
+    }
+    if let (Some(first), Some(last)) = (first_digit, last_digit) {
+        out.push_str(&format_whole(int_digits, &whole[first..=last], grouping));
+        for (i, token) in whole.iter().enumerate().skip(last + 1) {
+            if !is_scaling(i) {
+                out.push_str(&token_literal(token));
+            }
+        }
+    }
+
+    // Fraction part: '#' drops trailing zeros, '?' turns them into spaces
+    let mut frac_digits = frac_digits.chars();
+    let mut remaining: String = frac_digits.clone().collect();
+    for (i, token) in fraction.iter().enumerate() {
+        match token {
+            _ if is_scaling(whole.len() + i) => {}
+            Token::Point => out.push('.'),
+            Token::Digit(placeholder) => {
+                let digit = frac_digits.next().unwrap_or('0');
+                let rest_zero = remaining.chars().all(|c| c == '0');
+                remaining = frac_digits.clone().collect();
+                match (placeholder, rest_zero) {
+                    ('#', true) => {}
+                    ('?', true) => out.push(' '),
+                    _ => out.push(digit),
+                }
+            }
+            token => out.push_str(&token_literal(token)),
+        }
+    }
+
+    out
+}
+
+/// Fill the whole-number placeholders right to left, keeping any literals between them
+fn format_whole(digits: &str, tokens: &[Token], grouping: bool) -> String {
+    let min_digits = tokens.iter().filter(|t| **t == Token::Digit('0')).count();
+    let digits = digits.trim_start_matches('0');
+
+    if grouping {
+        let padded = format!("{:0>width$}", digits, width = min_digits);
+        let mut grouped = String::new();
+        for (i, c) in padded.chars().enumerate() {
+            if i > 0 && (padded.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        return grouped;
+    }
+
+    let placeholders = tokens
+        .iter()
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count();
+    let mut digits = digits.chars().rev().peekable();
+    let mut seen = 0;
+    let mut out = Vec::new();
+
+    for token in tokens.iter().rev() {
+        match token {
+            Token::Digit(placeholder) => {
+                seen += 1;
+                if seen == placeholders {
+                    // The leftmost placeholder takes every remaining digit
+                    let rest: Vec<char> = digits.by_ref().collect();
+                    if rest.is_empty() {
+                        out.extend(placeholder_fill(*placeholder));
+                    }
+                    out.extend(rest);
+                } else {
+                    match digits.next() {
+                        Some(digit) => out.push(digit),
+                        None => out.extend(placeholder_fill(*placeholder)),
+                    }
+                }
+            }
+            Token::Comma => {}
+            token => out.extend(token_literal(token).chars().rev()),
+        }
+    }
+
+    out.into_iter().rev().collect()
+}
+
+/// What an unfilled digit placeholder shows
+fn placeholder_fill(placeholder: char) -> Option<char> {
+    match placeholder {
+        '0' => Some('0'),
+        '?' => Some(' '),
+        _ => None,
+    }
+}
+
+fn format_scientific(n: f64, tokens: &[Token], exponent_at: usize) -> String {
+    let (mantissa, exponent) = tokens.split_at(exponent_at);
+    let decimals = mantissa
+        .iter()
+        .skip_while(|t| **t != Token::Point)
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count();
+    let exponent_digits = exponent
+        .iter()
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count();
+    let always_sign = exponent[0] == Token::Exponent('+');
+
+    let (mantissa_text, exp) = rounding::format_exponent(n, decimals);
+    let sign = match (exp < 0, always_sign) {
+        (true, _) => "-",
+        (false, true) => "+",
+        (false, false) => "",
+    };
+
+    let first_digit = mantissa
+        .iter()
+        .position(|t| matches!(t, Token::Digit(_)))
+        .unwrap_or(0);
+    let last_digit = exponent
+        .iter()
+        .rposition(|t| matches!(t, Token::Digit(_)))
+        .unwrap_or(0);
+
+    format!(
+        "{}{}E{}{:0width$}{}",
+        literals(&mantissa[..first_digit]),
+        mantissa_text,
+        sign,
+        exp.abs(),
+        literals(&exponent[last_digit + 1..]),
+        width = exponent_digits
+    )
+}
+
+fn token_literal(token: &Token) -> String {
+    match token {
+        Token::Literal(s) => s.clone(),
+        Token::Percent => "%".into(),
+        Token::Point => ".".into(),
+        Token::Comma => ",".into(),
+        _ => String::new(),
+    }
+}
+
+fn literals(tokens: &[Token]) -> String {
+    tokens.iter().map(token_literal).collect()
+}
+
+/// Convert days since 1970-01-01 to (year, month, day)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Format an Excel serial date with a code already split into tokens
+fn format_date(serial: f64, tokens: &[Token]) -> String {
+    let subsecond_digits = tokens
+        .iter()
+        .skip_while(|t| **t != Token::Point)
+        .take_while(|t| matches!(t, Token::Point | Token::Digit(_)))
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count()
+        .min(3);
+    let scale = 10i64.pow(subsecond_digits as u32);
+
+    let seconds = rounding::round_to(serial * 86_400.0, subsecond_digits as u32);
+    let ticks = (seconds * scale as f64).round() as i64;
+    let (seconds, subsecond) = (ticks.div_euclid(scale), ticks.rem_euclid(scale));
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    let (hour, minute, second) = (time / 3600, time / 60 % 60, time % 60);
+
+    // Excel counts the nonexistent 1900-02-29 as day 60
+    let (year, month, day) = match days {
+        60 => (1900, 2, 29),
+        d if d < 60 => civil_from_days(d - UNIX_EPOCH_SERIAL + 1),
+        d => civil_from_days(d - UNIX_EPOCH_SERIAL),
+    };
+    let weekday = (days - UNIX_EPOCH_SERIAL + 4).rem_euclid(7) as usize;
+
+    let twelve_hour = tokens.iter().any(|t| matches!(t, Token::AmPm { .. }));
+    let hour_shown = if twelve_hour {
+        (hour + 11) % 12 + 1
+    } else {
+        hour
+    };
+
+    let date_parts: Vec<(usize, char)> = tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| match t {
+            Token::Date(letter, _) => Some((i, *letter)),
+            _ => None,
+        })
+        .collect();
+
+    // An 'm' right after hours or right before seconds means minutes
+    let is_minute = |i: usize| {
+        let at = date_parts.iter().position(|&(j, _)| j == i).unwrap_or(0);
+        (at > 0 && date_parts[at - 1].1 == 'h')
+            || date_parts.get(at + 1).is_some_and(|&(_, l)| l == 's')
+    };
+
+    let mut subsecond_text =
+        format!("{:0width$}", subsecond, width = subsecond_digits).into_bytes();
+    subsecond_text.reverse();
+    let mut in_subsecond = false;
+
+    let mut out = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Date('y', count) if *count <= 2 => {
+                out.push_str(&format!("{:02}", year.rem_euclid(100)))
+            }
+            Token::Date('y', _) => out.push_str(&year.to_string()),
+            Token::Date('m', count) if is_minute(i) => out.push_str(&pad(minute, *count)),
+            Token::Date('m', count @ (1 | 2)) => out.push_str(&pad(i64::from(month), *count)),
+            Token::Date('m', 3) => out.push_str(&MONTHS[month as usize - 1][..3]),
+            Token::Date('m', 5) => out.push_str(&MONTHS[month as usize - 1][..1]),
+            Token::Date('m', _) => out.push_str(MONTHS[month as usize - 1]),
+            Token::Date('d', count @ (1 | 2)) => out.push_str(&pad(i64::from(day), *count)),
+            Token::Date('d', 3) => out.push_str(&WEEKDAYS[weekday][..3]),
+            Token::Date('d', _) => out.push_str(WEEKDAYS[weekday]),
+            Token::Date('h', count) => out.push_str(&pad(hour_shown, *count)),
+            Token::Date('s', count) => out.push_str(&pad(second, *count)),
+            Token::Date(_, _) => {}
+            Token::Elapsed(unit) => {
+                let total = match unit {
+                    'h' => seconds / 3600,
+                    'm' => seconds / 60,
+                    _ => seconds,
+                };
+                out.push_str(&total.to_string());
+            }
+            Token::AmPm { short } => out.push_str(match (hour < 12, short) {
+                (true, false) => "AM",
+                (false, false) => "PM",
+                (true, true) => "A",
+                (false, true) => "P",
+            }),
+            Token::Point if subsecond_digits > 0 => {
+                in_subsecond = true;
+                out.push('.');
+            }
+            Token::Digit(_) if in_subsecond => {
+                if let Some(digit) = subsecond_text.pop() {
+                    out.push(digit as char);
+                }
+            }
+            token => {
+                in_subsecond = false;
+                out.push_str(&token_literal(token));
+            }
+        }
+    }
+
+    out
+}
+
+fn pad(value: i64, width: usize) -> String {
+    format!("{:0width$}", value, width = width.min(2))
+}
+
+/// Format a number with a full format code, choosing the section for its sign
+fn format_code(n: f64, code: &str) -> String {
+    if code.trim().is_empty() {
+        return general(n);
+    }
+
+    let sections = sections(code);
+    let (section, n) = match sections.len() {
+        _ if n > 0.0 || sections.len() == 1 => (sections[0], n),
+        2 if n < 0.0 => (sections[1], n.abs()),
+        2 => (sections[0], n),
+        _ if n < 0.0 => (sections[1], n.abs()),
+        _ => (sections[2], n),
+    };
+
+    let tokens = tokenize(section);
+    if tokens
+        .iter()
+        .any(|t| matches!(t, Token::Date(..) | Token::Elapsed(_) | Token::AmPm { .. }))
+    {
+        format_date(n, &tokens)
+    } else {
+        format_number(n, &tokens)
+    }
+}
+
+/// Show a cell value the way a spreadsheet displays it with the format `code`
+///
+/// Supports the common parts of Excel format codes: digit placeholders (`0`, `#`, `?`),
+/// thousands separators and scaling, percentages, scientific notation, dates and times,
+/// literal text, and separate sections for positive, negative and zero values. Fractions
+/// are shown as decimals.
+pub fn format_value(value: &DataType, code: &str) -> String {
+    match value {
+        DataType::Empty => String::new(),
+        DataType::String(s) => match sections(code).get(3) {
+            Some(section) => tokenize(section)
+                .iter()
+                .map(|t| match t {
+                    Token::Text => s.clone(),
+                    t => token_literal(t),
+                })
+                .collect(),
+            None => s.clone(),
+        },
+        DataType::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_owned(),
+        DataType::Error(e) => CellErrorKind::from(e).to_string(),
+        DataType::Int(i) => format_code(*i as f64, code),
+        DataType::Float(f) | DataType::DateTime(f) => format_code(*f, code),
+    }
+}
+
+/// Show a value with its cell's format code, or as an unformatted cell is shown without one
+pub(crate) fn format_with(code: Option<&str>, value: &DataType) -> String {
+    let code = match (code, value) {
+        (Some(code), _) => code,
+        (None, DataType::DateTime(d)) if d.fract() == 0.0 => DEFAULT_DATE_FORMAT,
+        (None, DataType::DateTime(_)) => DEFAULT_DATETIME_FORMAT,
+        (None, _) => "General",
+    };
+
+    format_value(value, code)
+}
+
+impl WorkbookData {
+    /// Get the number format code of a cell, if it has one other than `General`
+    ///
+    /// Always `None` unless the data was loaded with `LoadOptions::number_formats` (xlsx only).
+    pub fn number_format(&self, row_number: u32, column_header: &str) -> Option<&str> {
+        let column = &self.columns[self.column_id(column_header)?.0];
+        self.number_formats
+            .get(&(row_number, column.index))
+            .map(String::as_str)
+    }
+
+    /// Get a cell as the spreadsheet displays it, using its number format
+    pub fn get_formatted(&self, row_number: u32, column_header: &str) -> Option<String> {
+        let id = self.column_id(column_header)?;
+        let value = self.get_cell_by_id(row_number, id)?;
+        Some(self.format_cell(row_number, self.columns[id.0].index, value))
+    }
+
+    /// Format a value as the spreadsheet displays the cell at this row and absolute column
+    pub(crate) fn format_cell(&self, row_number: u32, index: u32, value: &DataType) -> String {
+        let code = self.number_formats.get(&(row_number, index));
+        format_with(code.map(String::as_str), value)
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get a cell as the spreadsheet displays it: dates as dates, IDs with their leading zeros,
+    /// numbers with their decimal places, currency symbols and percent signs
+    ///
+    /// Formats are read when loading with `LoadOptions::number_formats` (xlsx only). Without
+    /// them, numbers are shown as in the `General` format and dates as `yyyy-mm-dd`.
+    pub fn get_formatted(&self, column_header: &str) -> Result<String, DataError> {
+        self.source
+            .get_formatted(self.row_number, column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(n: f64, code: &str) -> String {
+        format_value(&DataType::Float(n), code)
+    }
+
+    fn builtin(n: f64, id: u32) -> String {
+        number(n, builtin_format(id).unwrap())
+    }
+
+    #[test]
+    fn formats_builtin_numbers() {
+        assert_eq!(builtin(1.0 / 3.0, 0), "0.3333333333");
+        assert_eq!(builtin(1_234_567_890_123.0, 0), "1.23457E+12");
+        assert_eq!(builtin(2.5, 1), "3");
+        assert_eq!(builtin(-2.5, 1), "-3");
+        assert_eq!(builtin(0.125, 2), "0.13");
+        assert_eq!(builtin(1234.5, 3), "1,235");
+        assert_eq!(builtin(1_234_567.891, 4), "1,234,567.89");
+        assert_eq!(builtin(0.125, 9), "13%");
+        assert_eq!(builtin(0.5, 10), "50.00%");
+        assert_eq!(builtin(12345.0, 11), "1.23E+04");
+        assert_eq!(builtin(0.000_125, 11), "1.25E-04");
+        assert_eq!(builtin(1234.0, 37), "1,234 ");
+        assert_eq!(builtin(-1234.0, 37), "(1,234)");
+        assert_eq!(
+            format_value(&DataType::String("x".into()), builtin_format(49).unwrap()),
+            "x"
+        );
+    }
+
+    #[test]
+    fn formats_builtin_dates() {
+        // 2023-01-01, a Sunday
+        let day = 44927.0;
+        assert_eq!(builtin(day, 14), "01-01-23");
+        assert_eq!(builtin(day, 15), "1-Jan-23");
+        assert_eq!(builtin(0.75, 18), "6:00 PM");
+        assert_eq!(builtin(0.5 + 1.0 / 86_400.0, 21), "12:00:01");
+        assert_eq!(builtin(day + 0.5, 22), "1/1/23 12:00");
+        assert_eq!(builtin(1.5, 46), "36:00:00");
+    }
+
+    #[test]
+    fn chooses_sections_by_sign() {
+        let code = r#"0.00;(0.00);"zero";"["@"]""#;
+        assert_eq!(number(1.5, code), "1.50");
+        assert_eq!(number(-1.5, code), "(1.50)");
+        assert_eq!(number(0.0, code), "zero");
+        assert_eq!(format_value(&DataType::String("x".into()), code), "[x]");
+        assert_eq!(number(-1.5, "0.0;-0.0"), "-1.5");
+        assert_eq!(number(-0.001, "0.00"), "0.00");
+    }
+
+    #[test]
+    fn scales_by_trailing_commas() {
+        assert_eq!(number(1_234_567.0, "#,##0,"), "1,235");
+        assert_eq!(number(1_234_567.0, r#"0.0,,"M""#), "1.2M");
+        assert_eq!(number(1500.0, "0,"), "2");
+    }
+
+    #[test]
+    fn formats_date_and_time_tokens() {
+        let day = 44927.75;
+        assert_eq!(number(day, "yyyy-mm-dd hh:mm:ss"), "2023-01-01 18:00:00");
+        assert_eq!(number(day, "dddd, mmmm d"), "Sunday, January 1");
+        assert_eq!(number(day, "ddd d mmm yy"), "Sun 1 Jan 23");
+        assert_eq!(number(day, "h A/P"), "6 P");
+        assert_eq!(number(1.5 / 86_400.0, "mm:ss.0"), "00:01.5");
+        assert_eq!(number(0.25, "[m]"), "360");
+        // Excel's nonexistent leap day, and the day after it
+        assert_eq!(number(60.0, "yyyy-mm-dd"), "1900-02-29");
+        assert_eq!(number(61.0, "yyyy-mm-dd"), "1900-03-01");
+    }
+}
@@ -0,0 +1,442 @@
+use crate::coords::{self, Span};
+use crate::{DataType, HeaderDetector, LoadError, NumberText, Range};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+type PredicateFn = dyn Fn(&[String]) -> bool + Send + Sync;
+type ProgressFn = dyn Fn(usize, Option<usize>) + Send + Sync;
+
+/// Rows between progress reports while reading or iterating
+const PROGRESS_INTERVAL: usize = 1000;
+
+/// A custom test for whether a row (as cell strings) is the header row
+#[derive(Clone)]
+pub struct HeaderPredicate(Arc<PredicateFn>);
+
+impl HeaderPredicate {
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&[String]) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(predicate))
+    }
+
+    pub fn matches(&self, cells: &[String]) -> bool {
+        (self.0)(cells)
+    }
+}
+
+impl fmt::Debug for HeaderPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HeaderPredicate(..)")
+    }
+}
+
+/// A shared `HeaderDetector`, so `LoadOptions` stays cheap to clone
+#[derive(Clone)]
+pub(crate) struct DetectorHandle(Arc<dyn HeaderDetector>);
+
+impl DetectorHandle {
+    pub fn detect(&self, range: &Range<DataType>) -> Option<u32> {
+        self.0.detect(range)
+    }
+}
+
+impl fmt::Debug for DetectorHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HeaderDetector(..)")
+    }
+}
+
+/// A shared progress callback, from `LoadOptions::on_progress`
+#[derive(Clone)]
+pub(crate) struct ProgressHandle(Arc<ProgressFn>);
+
+impl ProgressHandle {
+    pub fn report(&self, rows_read: usize, total: Option<usize>) {
+        (self.0)(rows_read, total)
+    }
+
+    /// Report every `PROGRESS_INTERVAL` rows, and at the last row if the total is known
+    pub fn report_row(&self, rows_read: usize, total: Option<usize>) {
+        if rows_read.is_multiple_of(PROGRESS_INTERVAL) || Some(rows_read) == total {
+            self.report(rows_read, total);
+        }
+    }
+}
+
+impl fmt::Debug for ProgressHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Progress(..)")
+    }
+}
+
+/// A flag for cancelling a load from another thread, from `LoadOptions::cancel_token`
+///
+/// Clones share the flag, so keep one to call `cancel` on.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fail with `LoadError::Cancelled` if cancelled
+    pub(crate) fn check(&self) -> Result<(), LoadError> {
+        if self.is_cancelled() {
+            Err(LoadError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancelToken {
+    /// Share an existing flag, cancelling when it is set to `true`
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        Self(flag)
+    }
+}
+
+/// What to do with data rows that have values in columns without a header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RaggedRowPolicy {
+    /// Load anyway; the values are only reachable through the blank header
+    #[default]
+    Ignore,
+    /// Fail with `LoadError::RaggedRows`
+    Error,
+    /// Name the unheaded columns that have values `_extra_1`, `_extra_2`, ...
+    ExtraColumns,
+}
+
+/// Options controlling how a `WorkbookData` is built from a sheet or range
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    pub(crate) sheet_name: Option<String>,
+    pub(crate) skip_hidden_sheets: bool,
+    pub(crate) defined_name: Option<String>,
+    pub(crate) table: Option<String>,
+    pub(crate) header_row: Option<u32>,
+    pub(crate) range: Option<String>,
+    pub(crate) header_rows: u32,
+    pub(crate) headerless: bool,
+    pub(crate) header_separator: String,
+    pub(crate) min_header_cells: Option<usize>,
+    pub(crate) max_header_scan_rows: Option<usize>,
+    pub(crate) header_predicate: Option<HeaderPredicate>,
+    pub(crate) header_detector: Option<DetectorHandle>,
+    pub(crate) expand_merged_cells: bool,
+    pub(crate) formulas: bool,
+    pub(crate) hyperlinks: bool,
+    pub(crate) comments: bool,
+    pub(crate) number_formats: bool,
+    pub(crate) fill_down: Vec<String>,
+    pub(crate) redact: Vec<String>,
+    pub(crate) money: Vec<(String, u32)>,
+    pub(crate) rounding: Vec<(String, u32)>,
+    pub(crate) number_text: NumberText,
+    pub(crate) ragged_rows: RaggedRowPolicy,
+    pub(crate) transposed: bool,
+    pub(crate) progress: Option<ProgressHandle>,
+    pub(crate) cancel: Option<CancelToken>,
+    pub(crate) memory_limit: Option<usize>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            sheet_name: None,
+            skip_hidden_sheets: false,
+            defined_name: None,
+            table: None,
+            header_row: None,
+            range: None,
+            header_rows: 1,
+            headerless: false,
+            header_separator: " / ".into(),
+            min_header_cells: None,
+            max_header_scan_rows: None,
+            header_predicate: None,
+            header_detector: None,
+            expand_merged_cells: false,
+            formulas: false,
+            hyperlinks: false,
+            comments: false,
+            number_formats: false,
+            fill_down: Vec::new(),
+            redact: Vec::new(),
+            money: Vec::new(),
+            rounding: Vec::new(),
+            number_text: NumberText::default(),
+            ragged_rows: RaggedRowPolicy::default(),
+            transposed: false,
+            progress: None,
+            cancel: None,
+            memory_limit: None,
+        }
+    }
+}
+
+impl LoadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load this sheet instead of the first sheet with data (ignored by `from_range`)
+    pub fn sheet_name(mut self, sheet_name: &str) -> Self {
+        self.sheet_name = Some(sheet_name.to_owned());
+        self
+    }
+
+    /// Pass over hidden and very hidden sheets when looking for the first sheet with data
+    /// (xlsx only)
+    ///
+    /// A sheet named with `sheet_name` is loaded whether or not it is hidden.
+    pub fn skip_hidden_sheets(mut self, skip: bool) -> Self {
+        self.skip_hidden_sheets = skip;
+        self
+    }
+
+    /// Use the given absolute row as the header instead of detecting it
+    ///
+    /// With `transposed`, this is the absolute column holding the field names.
+    pub fn header_row(mut self, row: u32) -> Self {
+        self.header_row = Some(row);
+        self
+    }
+
+    /// Only load this region of the sheet, given in A1 notation like `"B4:H200"` or `"B:H"`
+    ///
+    /// The header is taken from the first row of the region with any values, instead of
+    /// being detected.
+    pub fn range(mut self, range: &str) -> Self {
+        self.range = Some(range.to_owned());
+        self
+    }
+
+    /// Read the range a defined name refers to, like a name set up in a template's
+    /// Name Manager, using its first row as the header
+    ///
+    /// This replaces any `sheet_name` and `range`. The name must refer to a single range.
+    pub fn defined_name(mut self, name: &str) -> Self {
+        self.defined_name = Some(name.to_owned());
+        self
+    }
+
+    /// Read an Excel Table by name, using its header row and extent without any totals row
+    /// (xlsx only)
+    ///
+    /// This replaces any `sheet_name` and `range`.
+    pub fn table(mut self, table_name: &str) -> Self {
+        self.table = Some(table_name.to_owned());
+        self
+    }
+
+    pub(crate) fn region(&self) -> Result<Option<Span>, LoadError> {
+        match &self.range {
+            Some(range) => coords::parse_range(range)
+                .map(Some)
+                .ok_or_else(|| LoadError::InvalidRange(range.clone())),
+            None => Ok(None),
+        }
+    }
+
+    /// Detect the header as the first row with at least this many non-empty cells
+    ///
+    /// Defaults to the full width of the sheet's used range, which fails on sheets with
+    /// legitimately blank header cells.
+    pub fn min_header_cells(mut self, count: usize) -> Self {
+        self.min_header_cells = Some(count);
+        self
+    }
+
+    /// Only look for the header in this many rows from the top of the used range
+    pub fn max_header_scan_rows(mut self, rows: usize) -> Self {
+        self.max_header_scan_rows = Some(rows);
+        self
+    }
+
+    /// Detect the header as the first row for which `predicate` returns `true`, instead of
+    /// counting non-empty cells
+    pub fn header_predicate<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&[String]) -> bool + Send + Sync + 'static,
+    {
+        self.header_predicate = Some(HeaderPredicate::new(predicate));
+        self
+    }
+
+    /// Find the header with a custom strategy, replacing the built-in `HeuristicDetector`
+    /// (and the settings above, which only configure it)
+    pub fn header_detector<D>(mut self, detector: D) -> Self
+    where
+        D: HeaderDetector + 'static,
+    {
+        self.header_detector = Some(DetectorHandle(Arc::new(detector)));
+        self
+    }
+
+    /// Treat every row as data, for sheets without a header row
+    ///
+    /// Columns are named after their sheet column letters (`"A"`, `"B"`, ...), or their row
+    /// numbers (`"1"`, `"2"`, ...) with `transposed`. Overrides `header_row`.
+    pub fn headerless(mut self, headerless: bool) -> Self {
+        self.headerless = headerless;
+        self
+    }
+
+    /// Combine this many rows into the header, ending at the detected (or given) header row
+    ///
+    /// Each column's header becomes its non-empty header cells from top to bottom joined with
+    /// `header_separator`, e.g. `"Q1 / Revenue"`. Blank cells in the upper rows repeat the
+    /// value to their left, as for a merged group label.
+    pub fn header_rows(mut self, rows: u32) -> Self {
+        self.header_rows = rows.max(1);
+        self
+    }
+
+    /// Separator used to join multi-row headers (default `" / "`)
+    pub fn header_separator(mut self, separator: &str) -> Self {
+        self.header_separator = separator.to_owned();
+        self
+    }
+
+    /// Copy the value of each merged cell region into every cell of the region (xlsx only)
+    ///
+    /// Only regions below the header row are expanded.
+    pub fn expand_merged_cells(mut self, expand: bool) -> Self {
+        self.expand_merged_cells = expand;
+        self
+    }
+
+    /// Also read cell formulas, for `RowData::get_formula` (ignored by `from_range` and for
+    /// delimited files)
+    pub fn formulas(mut self, load: bool) -> Self {
+        self.formulas = load;
+        self
+    }
+
+    /// Also read hyperlink targets, for `RowData::get_hyperlink` (xlsx only)
+    pub fn hyperlinks(mut self, load: bool) -> Self {
+        self.hyperlinks = load;
+        self
+    }
+
+    /// Also read cell comments, for `RowData::get_comment` (xlsx only)
+    pub fn comments(mut self, load: bool) -> Self {
+        self.comments = load;
+        self
+    }
+
+    /// Also read each cell's number format, for `RowData::get_formatted` (xlsx only)
+    pub fn number_formats(mut self, load: bool) -> Self {
+        self.number_formats = load;
+        self
+    }
+
+    /// Fill blank cells in this column with the last non-empty value above them
+    ///
+    /// May be given several times. Headers not found in the data are ignored.
+    pub fn fill_down(mut self, column_header: &str) -> Self {
+        self.fill_down.push(column_header.to_owned());
+        self
+    }
+
+    /// Hide this column's values in `Debug`/`Display` output, e.g. for personal data
+    ///
+    /// May be given several times. Headers not found in the data are ignored.
+    pub fn redact(mut self, column_header: &str) -> Self {
+        self.redact.push(column_header.to_owned());
+        self
+    }
+
+    /// Declare a money column read by `RowData::get_money`, with the number of decimal places
+    /// of its currency's minor unit (e.g. 2 for USD, 0 for JPY)
+    ///
+    /// May be given several times. Headers not found in the data are ignored.
+    pub fn money(mut self, column_header: &str, scale: u32) -> Self {
+        self.money.push((column_header.to_owned(), scale));
+        self
+    }
+
+    /// Round this column's floating-point values to this many decimal places wherever they
+    /// are read, hiding noise like `0.30000000000000004`
+    ///
+    /// May be given several times. Headers not found in the data are ignored.
+    pub fn round(mut self, column_header: &str, places: u32) -> Self {
+        self.rounding.push((column_header.to_owned(), places));
+        self
+    }
+
+    /// Set how numbers are written as text by `get`, exports and error messages
+    pub fn number_text(mut self, policy: NumberText) -> Self {
+        self.number_text = policy;
+        self
+    }
+
+    /// Set how rows with values in columns without a header are handled
+    pub fn ragged_rows(mut self, policy: RaggedRowPolicy) -> Self {
+        self.ragged_rows = policy;
+        self
+    }
+
+    /// Read a sideways table, with field names down a column and one record per column
+    ///
+    /// The sheet is transposed before header detection, so rows of the resulting data are the
+    /// source columns and `RowData::number` is the source column number.
+    pub fn transposed(mut self, transposed: bool) -> Self {
+        self.transposed = transposed;
+        self
+    }
+
+    /// Call `progress(rows_read, total)` while the sheet is read, then again while the
+    /// loaded rows are iterated, so a long load can show a progress bar
+    ///
+    /// Delimited files report every 1000 records as they are read, with no total. Workbook
+    /// formats are read in one step by calamine, so they report once before and once after.
+    /// Iterating with `iter_rows` reports every 1000 rows and at the last row, with the number
+    /// of data rows as the total.
+    pub fn on_progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(usize, Option<usize>) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressHandle(Arc::new(progress)));
+        self
+    }
+
+    /// Stop loading with `LoadError::Cancelled` once `token` is cancelled
+    ///
+    /// Delimited files check between records. Workbook formats check between sheets and
+    /// after each is read, as calamine reads a sheet in one step. Iterate the loaded rows with
+    /// `try_iter_rows` to fail the same way once the token is cancelled.
+    pub fn cancel_token(mut self, token: &CancelToken) -> Self {
+        self.cancel = Some(token.clone());
+        self
+    }
+
+    /// Keep at most about this many bytes of an xlsx file's shared strings in memory when
+    /// reading with `stream_rows`, writing the rest to a temporary file
+    ///
+    /// Without a limit, the whole shared strings table is kept in memory. Has no effect on
+    /// loading a `WorkbookData`, which holds the whole sheet.
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Fail with `LoadError::Cancelled` if this load has been cancelled
+    pub(crate) fn check_cancelled(&self) -> Result<(), LoadError> {
+        self.cancel.as_ref().map_or(Ok(()), CancelToken::check)
+    }
+}
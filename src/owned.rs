@@ -0,0 +1,154 @@
+use crate::RowData;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A row detached from its `WorkbookData`, holding header/value pairs in column order
+#[derive(Debug, Clone, Default)]
+pub struct OwnedRow {
+    number: u32,
+    cells: Vec<(String, String)>,
+}
+
+/// A single cell difference between two rows. `None` means the column is missing on that side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellChange {
+    pub header: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl fmt::Display for CellChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn show(value: &Option<String>) -> String {
+            match value {
+                Some(value) => format!("'{}'", value),
+                None => "(missing)".into(),
+            }
+        }
+
+        write!(
+            f,
+            "'{}': {} -> {}",
+            self.header,
+            show(&self.old),
+            show(&self.new)
+        )
+    }
+}
+
+impl OwnedRow {
+    pub fn new(number: u32, cells: Vec<(String, String)>) -> Self {
+        Self { number, cells }
+    }
+
+    /// Get the row number this data came from in the source workbook
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// Get the value with the matching column header (the first one, if headers repeat)
+    pub fn get(&self, column_header: &str) -> Option<&str> {
+        self.cells
+            .iter()
+            .find(|(h, _)| h == column_header)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn headers(&self) -> impl Iterator<Item = &str> + '_ {
+        self.cells.iter().map(|(h, _)| h.as_str())
+    }
+
+    pub fn cells(&self) -> &[(String, String)] {
+        &self.cells
+    }
+
+    pub fn into_cells(self) -> Vec<(String, String)> {
+        self.cells
+    }
+
+    /// List the cells that differ from `other`, matching columns by header
+    pub fn diff(&self, other: &OwnedRow) -> Vec<CellChange> {
+        let mut changes = Vec::new();
+
+        for header in self.headers() {
+            let old = self.get(header);
+            let new = other.get(header);
+
+            if old != new && !changes.iter().any(|c: &CellChange| c.header == header) {
+                changes.push(CellChange {
+                    header: header.into(),
+                    old: old.map(String::from),
+                    new: new.map(String::from),
+                });
+            }
+        }
+
+        for header in other.headers() {
+            if self.get(header).is_none() && !changes.iter().any(|c| c.header == header) {
+                changes.push(CellChange {
+                    header: header.into(),
+                    old: None,
+                    new: other.get(header).map(String::from),
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// Rows are equal when they have the same headers with the same values, in any column order
+impl PartialEq for OwnedRow {
+    fn eq(&self, other: &OwnedRow) -> bool {
+        self.diff(other).is_empty()
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Copy this row's header/value pairs out of the source data
+    pub fn to_owned_row(&self) -> OwnedRow {
+        OwnedRow {
+            number: self.row_number,
+            cells: self
+                .iter()
+                .map(|(header, value)| (header.to_owned(), value.into_owned()))
+                .collect(),
+        }
+    }
+
+    /// Get every header with this row's value, for consumers that don't know the columns
+    ///
+    /// Use `to_owned_row` to keep the columns in order, and every column of a repeated header;
+    /// here a header shared by several columns maps to the first one's value, as with `get`.
+    pub fn to_map(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        for (header, value) in self.iter() {
+            map.entry(header.to_owned())
+                .or_insert_with(|| value.into_owned());
+        }
+        map
+    }
+
+    /// List the cells that differ from `other`, matching columns by header
+    pub fn diff(&self, other: &RowData) -> Vec<CellChange> {
+        self.to_owned_row().diff(&other.to_owned_row())
+    }
+}
+
+impl<'a, 'b> PartialEq<RowData<'b>> for RowData<'a> {
+    fn eq(&self, other: &RowData<'b>) -> bool {
+        self.diff(other).is_empty()
+    }
+}
+
+impl<'a> PartialEq<OwnedRow> for RowData<'a> {
+    fn eq(&self, other: &OwnedRow) -> bool {
+        self.to_owned_row() == *other
+    }
+}
+
+impl<'a> PartialEq<RowData<'a>> for OwnedRow {
+    fn eq(&self, other: &RowData<'a>) -> bool {
+        *self == other.to_owned_row()
+    }
+}
@@ -0,0 +1,388 @@
+//! Best-effort loading that reports problems instead of failing outright
+
+use crate::{
+    cell_is_empty, coords, first_row_with_values, range_in_region, transpose_range, trim_range,
+    xlsx, DataType, LoadError, LoadOptions, RaggedRowPolicy, Range, WorkbookData,
+};
+use calamine::{open_workbook_auto, Reader};
+use std::fmt;
+use std::path::Path;
+
+/// Something wrong with a sheet that `load_partial` worked around or could not recover from
+#[derive(Debug)]
+pub enum LoadProblem {
+    /// Loading failed; no data is available
+    Failed(LoadError),
+    /// No row looked like a header, so the first non-empty row was used
+    HeaderNotDetected { used_row: u32 },
+    /// Absolute column numbers with an empty header cell
+    BlankHeaders(Vec<u32>),
+    /// Header names used by more than one column; only the last is reachable by name
+    DuplicateHeaders(Vec<String>),
+    /// Data rows with values in columns that have no header (see `RaggedRowPolicy`)
+    RaggedRows(Vec<u32>),
+    /// The configured range cuts through data, which continues past its bottom or right edge
+    RangeTruncated(String),
+    /// Merged cell regions could not be read, so they were not expanded
+    MergedCellsUnavailable(LoadError),
+}
+
+impl fmt::Display for LoadProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Row numbers are zero-based; show them as sheet rows
+        let rows = |rows: &[u32]| {
+            rows.iter()
+                .map(|r| (r + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            LoadProblem::Failed(err) => write!(f, "{}", err),
+            LoadProblem::HeaderNotDetected { used_row } => write!(
+                f,
+                "No header row detected; using row {} as the header",
+                used_row + 1
+            ),
+            LoadProblem::BlankHeaders(columns) => {
+                let names: Vec<String> =
+                    columns.iter().map(|&c| coords::column_letter(c)).collect();
+                write!(f, "Columns without a header: {}", names.join(", "))
+            }
+            LoadProblem::DuplicateHeaders(headers) => {
+                write!(f, "Repeated headers: '{}'", headers.join("', '"))
+            }
+            LoadProblem::RaggedRows(r) => {
+                write!(
+                    f,
+                    "Rows with values outside the headed columns: {}",
+                    rows(r)
+                )
+            }
+            LoadProblem::RangeTruncated(region) => {
+                write!(f, "Data continues past the edge of range '{}'", region)
+            }
+            LoadProblem::MergedCellsUnavailable(err) => {
+                write!(f, "Merged cells not expanded: {}", err)
+            }
+        }
+    }
+}
+
+/// The result of `load_partial`: whatever table could be built, and what went wrong
+#[derive(Debug)]
+pub struct PartialLoad {
+    pub data: Option<WorkbookData>,
+    pub problems: Vec<LoadProblem>,
+}
+
+impl PartialLoad {
+    fn failed(err: LoadError) -> Self {
+        Self {
+            data: None,
+            problems: vec![LoadProblem::Failed(err)],
+        }
+    }
+
+    /// Check if the data loaded without any problems
+    pub fn is_clean(&self) -> bool {
+        self.data.is_some() && self.problems.is_empty()
+    }
+}
+
+/// A sheet's cells, name and merged regions
+type SheetContents = (Range<DataType>, Option<String>, Vec<coords::Span>);
+
+/// Check if any cell just below or right of the region has a value
+fn region_truncates(range: &Range<DataType>, region: coords::Span) -> bool {
+    let used = match (range.start(), range.end()) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return false,
+    };
+    // Only the part of the region's edges beside the used range can have values
+    let ((r0, c0), (r1, c1)) = match coords::intersect(region, used) {
+        Some(span) => span,
+        None => return false,
+    };
+    let (below, right) = (
+        (region.1).0.saturating_add(1),
+        (region.1).1.saturating_add(1),
+    );
+
+    let filled = |pos| range.get_value(pos).is_some_and(|v| !cell_is_empty(v));
+    (c0..=c1).any(|c| filled((below, c))) || (r0..=r1).any(|r| filled((r, right)))
+}
+
+/// Get the first row with any value, after applying the region, trimming and transposing
+fn first_filled_row(range: &Range<DataType>, options: &LoadOptions) -> Option<u32> {
+    let region = options.region().ok().flatten();
+    let range = match region {
+        Some(region) => range_in_region(range, region)?,
+        None => range.clone(),
+    };
+    let mut range = trim_range(range)?;
+    if options.transposed {
+        range = transpose_range(&range);
+    }
+
+    first_row_with_values(&range)
+}
+
+impl WorkbookData {
+    /// Load as much of a sheet as possible, listing problems instead of failing
+    ///
+    /// If no header row is detected, the first non-empty row is used. Without a configured
+    /// sheet name, the first sheet with any data is loaded.
+    pub fn load_partial<P: AsRef<Path>>(path: P, options: &LoadOptions) -> PartialLoad {
+        let path = path.as_ref();
+        let resolved = match Self::resolve_named_region(path, options) {
+            Ok(resolved) => resolved,
+            Err(err) => return PartialLoad::failed(err),
+        };
+        let options = resolved.as_ref().unwrap_or(options);
+
+        if let Err(err) = options.region() {
+            return PartialLoad::failed(err);
+        }
+
+        let filename = path.to_string_lossy().to_string();
+        let mut problems = Vec::new();
+
+        let (range, sheet_name, merged) = match Self::read_sheet(path, options, &mut problems) {
+            Ok(Some(found)) => found,
+            Ok(None) => {
+                return PartialLoad::failed(match &options.sheet_name {
+                    Some(sheet_name) => LoadError::EmptySheet {
+                        filename,
+                        sheet_name: sheet_name.clone(),
+                    },
+                    None => LoadError::Empty { filename },
+                })
+            }
+            Err(err) => return PartialLoad::failed(err),
+        };
+
+        if let Some(region) = options.region().ok().flatten() {
+            if region_truncates(&range, region) {
+                problems.push(LoadProblem::RangeTruncated(
+                    options.range.clone().unwrap_or_default(),
+                ));
+            }
+        }
+
+        let data = match Self::from_range_inner(range.clone(), options, &merged) {
+            Some(data) => Some(data),
+            None => first_filled_row(&range, options).and_then(|used_row| {
+                problems.push(LoadProblem::HeaderNotDetected { used_row });
+                let options = options.clone().header_row(used_row);
+                Self::from_range_inner(range, &options, &merged)
+            }),
+        };
+
+        let mut data = match data {
+            Some(data) => data,
+            None => {
+                problems.push(LoadProblem::Failed(LoadError::NoHeader));
+                return PartialLoad {
+                    data: None,
+                    problems,
+                };
+            }
+        };
+        data.sheet_name = sheet_name;
+
+        problems.extend(data.header_problems());
+
+        // Ragged rows are already reported, so only act on a policy that keeps the data usable
+        if options.ragged_rows == RaggedRowPolicy::ExtraColumns {
+            let _ = data.apply_ragged_policy(RaggedRowPolicy::ExtraColumns);
+        }
+
+        PartialLoad {
+            data: Some(data),
+            problems,
+        }
+    }
+
+    /// Read the configured sheet, or the first one with data
+    fn read_sheet(
+        path: &Path,
+        options: &LoadOptions,
+        problems: &mut Vec<LoadProblem>,
+    ) -> Result<Option<SheetContents>, LoadError> {
+        #[cfg(feature = "csv")]
+        if let Some(delimiter) = crate::delimited::delimiter(path) {
+            let range = crate::delimited::read_range(path, delimiter, options)?;
+            let sheet_name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+            return Ok(Some((range, sheet_name, Vec::new())));
+        }
+
+        let mut workbook = open_workbook_auto(path)?;
+
+        let sheet_names = match &options.sheet_name {
+            Some(sheet_name) => vec![sheet_name.clone()],
+            None => workbook.sheet_names().to_owned(),
+        };
+
+        for sheet_name in sheet_names {
+            let range = match workbook.worksheet_range(&sheet_name) {
+                Some(range) => range?,
+                None => continue,
+            };
+
+            if trim_range(range.clone()).is_none() {
+                continue;
+            }
+
+            let merged = if options.expand_merged_cells && xlsx::is_xlsx_path(path) {
+                xlsx::XlsxPackage::open(path)
+                    .and_then(|mut p| p.merged_regions(&sheet_name))
+                    .unwrap_or_else(|err| {
+                        problems.push(LoadProblem::MergedCellsUnavailable(err));
+                        Vec::new()
+                    })
+            } else {
+                Vec::new()
+            };
+
+            return Ok(Some((range, Some(sheet_name), merged)));
+        }
+
+        Ok(None)
+    }
+
+    /// List blank and repeated headers, and rows with values under blank headers
+    fn header_problems(&self) -> Vec<LoadProblem> {
+        let mut problems = Vec::new();
+
+        let blank: Vec<u32> = self
+            .columns
+            .iter()
+            .filter(|c| c.name.is_empty())
+            .map(|c| c.index)
+            .collect();
+
+        let mut duplicates: Vec<String> = Vec::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            let repeated = self.columns[..i].iter().any(|c| c.name == column.name);
+            if repeated && !column.name.is_empty() && !duplicates.contains(&column.name) {
+                duplicates.push(column.name.clone());
+            }
+        }
+
+        let ragged = self.ragged_rows();
+
+        if !blank.is_empty() {
+            problems.push(LoadProblem::BlankHeaders(blank));
+        }
+        if !duplicates.is_empty() {
+            problems.push(LoadProblem::DuplicateHeaders(duplicates));
+        }
+        if !ragged.is_empty() {
+            problems.push(LoadProblem::RaggedRows(ragged));
+        }
+
+        problems
+    }
+}
+
+/// Load as much of a sheet as possible, as `WorkbookData::load_partial`
+pub fn load_partial<P: AsRef<Path>>(path: P, options: &LoadOptions) -> PartialLoad {
+    WorkbookData::load_partial(path, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestXlsx;
+
+    fn template() -> TestXlsx {
+        TestXlsx::new()
+            .sheet("Notes", &[&["Read me first"]])
+            .sheet(
+                "Data",
+                &[
+                    &["Import sheet"],
+                    &[],
+                    &["", "Id", "Name"],
+                    &["", "1", "Ann"],
+                    &["", "2", "Bob", "", "", "note"],
+                ],
+            )
+            .defined_name("ImportArea", "Data!$B$3:$C$5")
+            .defined_name("Columns", "Data!$B:$C")
+            .table("People", "B3:C5")
+    }
+
+    #[test]
+    fn loads_defined_names_and_tables() {
+        let path = template().write();
+
+        for options in [
+            LoadOptions::new().defined_name("ImportArea"),
+            LoadOptions::new().defined_name("columns"),
+            LoadOptions::new().table("People"),
+        ] {
+            let loaded = load_partial(&path, &options);
+            assert!(loaded.problems.is_empty(), "{:?}", loaded.problems);
+            let data = loaded.data.unwrap();
+            assert_eq!(data.sheet_name(), Some("Data"));
+            assert_eq!(data.headers().collect::<Vec<_>>(), ["Id", "Name"]);
+            assert_eq!(data.get(4, "Name").as_deref(), Some("Bob"));
+        }
+    }
+
+    #[test]
+    fn reports_unknown_names_as_failures() {
+        let path = template().write();
+
+        for options in [
+            LoadOptions::new().defined_name("Missing"),
+            LoadOptions::new().table("Missing"),
+        ] {
+            let loaded = load_partial(&path, &options);
+            assert!(loaded.data.is_none());
+            assert!(matches!(
+                loaded.problems.as_slice(),
+                [LoadProblem::Failed(LoadError::UnknownDefinedName { .. })]
+                    | [LoadProblem::Failed(LoadError::UnknownTable { .. })]
+            ));
+        }
+    }
+
+    #[test]
+    fn reports_truncated_ranges_and_missing_headers() {
+        let path = template().write();
+
+        let loaded = load_partial(&path, &LoadOptions::new().sheet_name("Data").range("B3:C4"));
+        assert!(matches!(
+            loaded.problems.as_slice(),
+            [LoadProblem::RangeTruncated(range)] if range == "B3:C4"
+        ));
+        assert_eq!(loaded.data.unwrap().iter_rows().count(), 1);
+
+        let loaded = load_partial(&path, &LoadOptions::new().sheet_name("Data"));
+        assert!(loaded
+            .problems
+            .iter()
+            .any(|p| matches!(p, LoadProblem::HeaderNotDetected { used_row: 0 })));
+        assert!(loaded.data.is_some());
+    }
+
+    #[test]
+    fn reports_blank_and_duplicate_headers() {
+        let path = TestXlsx::new()
+            .sheet("Data", &[&["Id", "", "Id"], &["1", "x", "2"]])
+            .write();
+
+        let loaded = load_partial(&path, &LoadOptions::new().header_row(0));
+        assert!(loaded
+            .problems
+            .iter()
+            .any(|p| matches!(p, LoadProblem::BlankHeaders(columns) if columns == &[1])));
+        assert!(loaded
+            .problems
+            .iter()
+            .any(|p| matches!(p, LoadProblem::DuplicateHeaders(names) if names == &["Id"])));
+    }
+}
@@ -0,0 +1,262 @@
+//! Heuristics for spotting columns that hold personal data
+
+use crate::{cell_is_empty, cell_to_str, WorkbookData};
+use std::fmt;
+
+/// A kind of personal data recognized by `PiiScanner`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PiiKind {
+    Email,
+    Phone,
+    /// US Social Security or UK National Insurance numbers
+    NationalId,
+    /// Card numbers passing the Luhn checksum
+    CreditCard,
+}
+
+impl fmt::Display for PiiKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PiiKind::Email => "email addresses",
+            PiiKind::Phone => "phone numbers",
+            PiiKind::NationalId => "national ID numbers",
+            PiiKind::CreditCard => "credit card numbers",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A column whose values look like personal data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiFinding {
+    pub column: String,
+    /// Where the column is in the sheet, like `"Column AJ"`
+    pub position: String,
+    pub kind: PiiKind,
+    /// Number of sampled values recognized as `kind`
+    pub matches: usize,
+    /// Number of non-empty values sampled
+    pub sampled: usize,
+}
+
+impl fmt::Display for PiiFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} / '{}' looks like {} ({} of {} values)",
+            self.position, self.column, self.kind, self.matches, self.sampled
+        )
+    }
+}
+
+/// Scans columns for values that look like personal data
+///
+/// Each column is classified by its most common kind of match, and flagged if at least
+/// `min_ratio` of its sampled non-empty values match.
+#[derive(Debug, Clone)]
+pub struct PiiScanner {
+    min_ratio: f64,
+    sample_rows: usize,
+}
+
+impl Default for PiiScanner {
+    fn default() -> Self {
+        Self {
+            min_ratio: 0.5,
+            sample_rows: 1000,
+        }
+    }
+}
+
+impl PiiScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fraction of sampled values that must match to flag a column (default 0.5)
+    pub fn min_ratio(mut self, ratio: f64) -> Self {
+        self.min_ratio = ratio;
+        self
+    }
+
+    /// Only look at this many data rows from the top of each column (default 1000)
+    pub fn sample_rows(mut self, rows: usize) -> Self {
+        self.sample_rows = rows;
+        self
+    }
+
+    pub fn scan(&self, data: &WorkbookData) -> Vec<PiiFinding> {
+        let mut findings = Vec::new();
+
+        for column in &data.columns {
+            let mut counts = [0usize; 4];
+            let mut sampled = 0;
+
+            for row in data.rows().take(self.sample_rows) {
+                let value = match data.range.get_value((row.number(), column.index)) {
+                    Some(value) if !cell_is_empty(value) => cell_to_str(value),
+                    _ => continue,
+                };
+
+                sampled += 1;
+                if let Some(kind) = classify(value.trim()) {
+                    counts[kind as usize] += 1;
+                }
+            }
+
+            let best = KINDS
+                .iter()
+                .zip(counts)
+                .filter(|&(_, n)| n > 0)
+                .max_by_key(|&(_, n)| n);
+
+            if let Some((&kind, matches)) = best {
+                if matches as f64 >= self.min_ratio * sampled as f64 {
+                    findings.push(PiiFinding {
+                        column: column.name.clone(),
+                        position: data.column_position(&column.name),
+                        kind,
+                        matches,
+                        sampled,
+                    });
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+const KINDS: [PiiKind; 4] = [
+    PiiKind::Email,
+    PiiKind::Phone,
+    PiiKind::NationalId,
+    PiiKind::CreditCard,
+];
+
+/// Recognize a single value, checking the most specific formats first
+fn classify(value: &str) -> Option<PiiKind> {
+    if is_email(value) {
+        Some(PiiKind::Email)
+    } else if is_credit_card(value) {
+        Some(PiiKind::CreditCard)
+    } else if is_ssn(value) || is_nino(value) {
+        Some(PiiKind::NationalId)
+    } else if is_phone(value) {
+        Some(PiiKind::Phone)
+    } else {
+        None
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    let (local, domain) = match value.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._%+-'".contains(c));
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|l| {
+            !l.is_empty()
+                && !l.starts_with('-')
+                && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+
+    local_ok && domain_ok
+}
+
+/// Get the digits of a value made only of digits and the given separators
+fn digits_with_separators(value: &str, separators: &str) -> Option<Vec<u32>> {
+    value
+        .chars()
+        .filter(|c| !separators.contains(*c))
+        .map(|c| c.to_digit(10))
+        .collect()
+}
+
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn is_credit_card(value: &str) -> bool {
+    match digits_with_separators(value, " -") {
+        Some(digits) => (13..=19).contains(&digits.len()) && luhn_valid(&digits),
+        None => false,
+    }
+}
+
+/// US Social Security number, `123-45-6789`, excluding never-issued ranges
+fn is_ssn(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    if parts.len() != 3
+        || [3, 2, 4] != [parts[0].len(), parts[1].len(), parts[2].len()]
+        || !parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit()))
+    {
+        return false;
+    }
+
+    let area: u32 = parts[0].parse().unwrap_or(0);
+    area != 0 && area != 666 && area < 900 && parts[1] != "00" && parts[2] != "0000"
+}
+
+/// UK National Insurance number, like `AB 12 34 56 C`
+fn is_nino(value: &str) -> bool {
+    let compact: Vec<char> = value
+        .chars()
+        .filter(|c| *c != ' ')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    compact.len() == 9
+        && compact[..2]
+            .iter()
+            .all(|c| c.is_ascii_alphabetic() && !"DFIQUV".contains(*c))
+        && compact[2..8].iter().all(|c| c.is_ascii_digit())
+        && ('A'..='D').contains(&compact[8])
+}
+
+/// A 10 to 15 digit number written with a leading `+` or phone-style separators
+fn is_phone(value: &str) -> bool {
+    let digits = match digits_with_separators(value.trim_start_matches('+'), " -().") {
+        Some(digits) => digits,
+        None => return false,
+    };
+
+    let formatted = value.starts_with('+') || value.contains(|c| " -()".contains(c));
+    formatted && (10..=15).contains(&digits.len())
+}
+
+impl WorkbookData {
+    /// Find columns that look like personal data, using the default `PiiScanner`
+    pub fn scan_pii(&self) -> Vec<PiiFinding> {
+        PiiScanner::default().scan(self)
+    }
+
+    /// Redact every column found by `scanner`, returning the findings
+    pub fn redact_pii(&mut self, scanner: &PiiScanner) -> Vec<PiiFinding> {
+        let findings = scanner.scan(self);
+        for finding in &findings {
+            self.set_redacted(&finding.column, true);
+        }
+        findings
+    }
+}
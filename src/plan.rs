@@ -0,0 +1,49 @@
+//! Dry runs of the functions that write files and databases
+
+use std::path::PathBuf;
+
+/// What a write would change, as returned by the `plan_` counterparts of the write functions
+///
+/// A plan is made by doing every step of the write except the last: rows are matched to the
+/// header, cells are converted and the sheet is patched, so a plan is only returned if the
+/// write itself would succeed, but no file or database is changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WritePlan {
+    /// The file that would be written, or `None` for an in-memory SQLite database
+    pub path: Option<PathBuf>,
+    /// Each sheet (or SQLite table) that would be written
+    pub sheets: Vec<SheetPlan>,
+}
+
+/// The writes a `WritePlan` would make to one sheet or table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetPlan {
+    pub name: String,
+    /// Data rows that would be written, added or changed, not counting the header
+    pub rows: usize,
+    /// Cells that would be written, including the header and cells that are cleared
+    pub cells: usize,
+}
+
+impl WritePlan {
+    pub(crate) fn new<P: Into<PathBuf>>(
+        path: Option<P>,
+        name: &str,
+        rows: usize,
+        cells: usize,
+    ) -> Self {
+        Self {
+            path: path.map(Into::into),
+            sheets: vec![SheetPlan {
+                name: name.to_owned(),
+                rows,
+                cells,
+            }],
+        }
+    }
+
+    /// Get the number of data rows over every sheet
+    pub fn rows(&self) -> usize {
+        self.sheets.iter().map(|s| s.rows).sum()
+    }
+}
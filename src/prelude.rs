@@ -0,0 +1,13 @@
+//! The types most programs need, to import with `use excelerator::prelude::*`
+//!
+//! Everything here is kept source-compatible across minor releases: items are only added,
+//! and the error and violation enums are `#[non_exhaustive]` so new variants are not breaking.
+//! Feature-specific types (caching, dates, writing, exports) stay at the crate root.
+
+pub use crate::{
+    from_file_bytes, from_glob, from_path, from_path_with_defined_name, from_path_with_options,
+    from_path_with_sheet_name, from_path_with_table, from_paths, BoolOptions, ColumnId, ColumnType,
+    Constraint, DataError, DataType, ExportOptions, Filter, FromRow, LoadError, LoadOptions,
+    Localize, NumberLocale, OwnedRow, RowData, Schema, ValidationReport, Violation, WorkbookBatch,
+    WorkbookData,
+};
@@ -0,0 +1,115 @@
+//! Custom document properties, the key-value pairs set in File > Properties > Custom
+
+use crate::xlsx::{self, XlsxPackage};
+use crate::{DataType, LoadError};
+use quick_xml::events::Event;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// Package path of the custom properties part
+const CUSTOM_PROPERTIES_PART: &str = "docProps/custom.xml";
+
+/// A named custom document property
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomProperty {
+    pub name: String,
+    /// Numbers and booleans are typed; text, dates and other kinds are strings
+    pub value: DataType,
+}
+
+/// Convert the text of a `vt:` value element by its local name
+fn property_value(kind: &[u8], text: &str) -> DataType {
+    let text = text.trim();
+
+    let parsed = match kind {
+        b"i1" | b"i2" | b"i4" | b"i8" | b"int" | b"ui1" | b"ui2" | b"ui4" | b"ui8" | b"uint" => {
+            text.parse().ok().map(DataType::Int)
+        }
+        b"r4" | b"r8" | b"decimal" => text.parse().ok().map(DataType::Float),
+        b"bool" => match text {
+            "true" | "1" => Some(DataType::Bool(true)),
+            "false" | "0" => Some(DataType::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| DataType::String(text.to_owned()))
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// Read the custom document properties in document order
+    pub fn custom_properties(&mut self) -> Result<Vec<CustomProperty>, LoadError> {
+        let xml = match self.read_part(CUSTOM_PROPERTIES_PART)? {
+            Some(xml) => xml,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = quick_xml::Reader::from_reader(xml.as_slice());
+        let mut buf = Vec::new();
+        let mut properties = Vec::new();
+
+        // The property being read, and the kind and text of its value element
+        let mut name: Option<String> = None;
+        let mut value: Option<(Vec<u8>, String)> = None;
+
+        loop {
+            match reader.read_event(&mut buf)? {
+                Event::Start(ref e) if e.local_name() == b"property" => {
+                    name = xlsx::attr(e, b"name");
+                }
+                Event::Start(ref e) if name.is_some() && value.is_none() => {
+                    value = Some((e.local_name().to_vec(), String::new()));
+                }
+                Event::Empty(ref e) if e.local_name() != b"property" => {
+                    if let Some(name) = &name {
+                        properties.push(CustomProperty {
+                            name: name.clone(),
+                            value: property_value(e.local_name(), ""),
+                        });
+                    }
+                }
+                Event::Text(ref t) => {
+                    if let Some((_, text)) = &mut value {
+                        text.push_str(&String::from_utf8_lossy(&t.unescaped()?));
+                    }
+                }
+                Event::End(ref e) if e.local_name() == b"property" => name = None,
+                Event::End(_) => {
+                    if let (Some(name), Some((kind, text))) = (&name, value.take()) {
+                        properties.push(CustomProperty {
+                            name: name.clone(),
+                            value: property_value(&kind, &text),
+                        });
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(properties)
+    }
+
+    /// Get one custom document property by name
+    pub fn custom_property(&mut self, name: &str) -> Result<Option<DataType>, LoadError> {
+        Ok(self
+            .custom_properties()?
+            .into_iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value))
+    }
+}
+
+/// Read the custom document properties of a workbook file
+///
+/// Only xlsx packages are read; other formats have no properties.
+pub fn custom_properties<P: AsRef<Path>>(path: P) -> Result<Vec<CustomProperty>, LoadError> {
+    let path = path.as_ref();
+    if !xlsx::is_xlsx_path(path) {
+        return Ok(Vec::new());
+    }
+
+    XlsxPackage::open(path)?.custom_properties()
+}
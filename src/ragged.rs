@@ -0,0 +1,67 @@
+use crate::options::RaggedRowPolicy;
+use crate::{cell_is_empty, LoadError, WorkbookData};
+
+/// Prefix of the headers generated by `RaggedRowPolicy::ExtraColumns`
+const EXTRA_PREFIX: &str = "_extra_";
+
+impl WorkbookData {
+    /// List the data rows with values in columns that have no header
+    pub fn ragged_rows(&self) -> Vec<u32> {
+        let blank: Vec<u32> = self
+            .columns
+            .iter()
+            .filter(|c| c.name.is_empty())
+            .map(|c| c.index)
+            .collect();
+
+        if blank.is_empty() {
+            return Vec::new();
+        }
+
+        (self.first_row..=self.last_row)
+            .filter(|&row| {
+                blank.iter().any(|&col| {
+                    self.range
+                        .get_value((row, col))
+                        .is_some_and(|v| !cell_is_empty(v))
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn apply_ragged_policy(&mut self, policy: RaggedRowPolicy) -> Result<(), LoadError> {
+        match policy {
+            RaggedRowPolicy::Ignore => Ok(()),
+            RaggedRowPolicy::Error => {
+                let rows = self.ragged_rows();
+                if rows.is_empty() {
+                    Ok(())
+                } else {
+                    Err(LoadError::RaggedRows(rows))
+                }
+            }
+            RaggedRowPolicy::ExtraColumns => {
+                self.name_extra_columns();
+                Ok(())
+            }
+        }
+    }
+
+    /// Name each blank-header column that has data `_extra_1`, `_extra_2`, ... in sheet order
+    fn name_extra_columns(&mut self) {
+        let mut n = 0;
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            if column.name.is_empty() && column.populated {
+                n += 1;
+                column.name = format!("{}{}", EXTRA_PREFIX, n);
+                self.header.entry(column.name.clone()).or_insert(i);
+            }
+        }
+
+        // The blank header now only refers to the remaining unpopulated columns, if any
+        match self.columns.iter().position(|c| c.name.is_empty()) {
+            Some(i) => self.header.insert(String::new(), i),
+            None => self.header.remove(""),
+        };
+    }
+}
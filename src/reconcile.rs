@@ -0,0 +1,305 @@
+//! Reconciling headers that drifted between files, e.g. "Cust ID" -> "Customer ID"
+//!
+//! Columns that never appear in the same file and have similar names are suggested as the same
+//! column. Suggestions must be accepted before they take effect.
+
+use crate::{ConcatRows, LoadError, LoadOptions, WorkbookData};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Lowercase alphanumeric words of a header
+fn tokens(header: &str) -> Vec<String> {
+    header
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+/// Score how alike two headers are, from 0 (unrelated) to 1 (same words)
+pub fn header_similarity(a: &str, b: &str) -> f64 {
+    let (ta, tb) = (tokens(a), tokens(b));
+    let (ca, cb): (Vec<char>, Vec<char>) =
+        (ta.concat().chars().collect(), tb.concat().chars().collect());
+
+    if ca.is_empty() || cb.is_empty() {
+        return 0.;
+    }
+    if ca == cb {
+        return 1.;
+    }
+
+    let edit = 1. - levenshtein(&ca, &cb) as f64 / ca.len().max(cb.len()) as f64;
+
+    // Abbreviated words, e.g. "Cust ID" and "Customer ID"
+    let abbreviated = ta.len() == tb.len()
+        && ta
+            .iter()
+            .zip(&tb)
+            .all(|(x, y)| x.starts_with(y.as_str()) || y.starts_with(x.as_str()));
+
+    if abbreviated {
+        edit.max(0.9)
+    } else {
+        edit
+    }
+}
+
+/// Follow chains of merges to the earliest column
+fn canonical<'a>(merges: &HashMap<&str, &'a str>, mut header: &'a str) -> String {
+    for _ in 0..=merges.len() {
+        match merges.get(header) {
+            Some(column) => header = column,
+            None => break,
+        }
+    }
+    header.to_owned()
+}
+
+/// A proposed merge of one column into another
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderSuggestion {
+    /// The later-seen header
+    pub header: String,
+    /// The earlier-seen column it would be merged into
+    pub column: String,
+    pub score: f64,
+    pub accepted: bool,
+}
+
+/// Headers of several files, with suggested merges awaiting confirmation
+#[derive(Debug, Clone)]
+pub struct Reconciliation {
+    paths: Vec<PathBuf>,
+    options: LoadOptions,
+    file_headers: Vec<Vec<String>>,
+    suggestions: Vec<HeaderSuggestion>,
+}
+
+impl Reconciliation {
+    /// Load every file's headers and suggest merges for columns scoring at least `threshold`
+    pub fn from_paths<P: AsRef<Path>>(
+        paths: &[P],
+        options: &LoadOptions,
+        threshold: f64,
+    ) -> Result<Self, LoadError> {
+        let paths: Vec<PathBuf> = paths.iter().map(|p| p.as_ref().to_owned()).collect();
+
+        let mut file_headers = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let data = WorkbookData::from_path_with_options(path, options)?;
+            file_headers.push(data.headers().map(String::from).collect());
+        }
+
+        let mut reconciliation = Self::from_headers(file_headers, threshold);
+        reconciliation.paths = paths;
+        reconciliation.options = options.clone();
+        Ok(reconciliation)
+    }
+
+    /// Suggest merges between the given per-file headers
+    pub fn from_headers(file_headers: Vec<Vec<String>>, threshold: f64) -> Self {
+        let mut reconciliation = Self {
+            paths: Vec::new(),
+            options: LoadOptions::default(),
+            file_headers,
+            suggestions: Vec::new(),
+        };
+
+        let columns = reconciliation.all_headers();
+        let together = |a: &str, b: &str| {
+            reconciliation
+                .file_headers
+                .iter()
+                .any(|h| h.iter().any(|x| x == a) && h.iter().any(|x| x == b))
+        };
+
+        let mut suggestions = Vec::new();
+        for (i, header) in columns.iter().enumerate() {
+            let best = columns[..i]
+                .iter()
+                .filter(|c| !together(c, header))
+                .map(|c| (c, header_similarity(c, header)))
+                .filter(|&(_, score)| score >= threshold)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((column, score)) = best {
+                suggestions.push(HeaderSuggestion {
+                    header: header.clone(),
+                    column: column.clone(),
+                    score,
+                    accepted: false,
+                });
+            }
+        }
+
+        reconciliation.suggestions = suggestions;
+        reconciliation
+    }
+
+    /// Every distinct header, in first-seen order
+    fn all_headers(&self) -> Vec<String> {
+        let mut headers: Vec<String> = Vec::new();
+        for header in self.file_headers.iter().flatten() {
+            if !headers.contains(header) {
+                headers.push(header.clone());
+            }
+        }
+        headers
+    }
+
+    pub fn suggestions(&self) -> &[HeaderSuggestion] {
+        &self.suggestions
+    }
+
+    /// Accept or reject the suggestion at `index`
+    pub fn confirm(&mut self, index: usize, accepted: bool) {
+        if let Some(suggestion) = self.suggestions.get_mut(index) {
+            suggestion.accepted = accepted;
+        }
+    }
+
+    /// Accept every suggestion scoring at least `min_score`
+    pub fn accept_all(&mut self, min_score: f64) {
+        for suggestion in &mut self.suggestions {
+            if suggestion.score >= min_score {
+                suggestion.accepted = true;
+            }
+        }
+    }
+
+    /// Apply the accepted suggestions, producing the merged columns and per-file mappings
+    pub fn resolve(&self) -> ReconciledHeaders {
+        let merges: HashMap<&str, &str> = self
+            .suggestions
+            .iter()
+            .filter(|s| s.accepted)
+            .map(|s| (s.header.as_str(), s.column.as_str()))
+            .collect();
+
+        let canonical = |header: &str| canonical(&merges, header);
+
+        let columns: Vec<String> = self
+            .all_headers()
+            .into_iter()
+            .filter(|h| !merges.contains_key(h.as_str()))
+            .collect();
+
+        let files = self
+            .file_headers
+            .iter()
+            .enumerate()
+            .map(|(i, headers)| {
+                let renamed: Vec<(String, String)> = headers
+                    .iter()
+                    .map(|h| (h.clone(), canonical(h)))
+                    .filter(|(h, c)| h != c)
+                    .collect();
+
+                let missing = columns
+                    .iter()
+                    .filter(|c| !headers.iter().any(|h| canonical(h) == **c))
+                    .cloned()
+                    .collect();
+
+                FileMapping {
+                    path: self.paths.get(i).cloned(),
+                    renamed,
+                    missing,
+                }
+            })
+            .collect();
+
+        let aliases = columns
+            .iter()
+            .map(|c| {
+                merges
+                    .keys()
+                    .filter(|h| canonical(h) == *c)
+                    .map(|h| h.to_string())
+                    .collect()
+            })
+            .collect();
+
+        ReconciledHeaders {
+            columns,
+            files,
+            aliases,
+            paths: self.paths.clone(),
+            options: self.options.clone(),
+        }
+    }
+}
+
+/// How one file's headers map onto the merged columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMapping {
+    /// The file, if the reconciliation was loaded from paths
+    pub path: Option<PathBuf>,
+    /// Headers in this file that were merged into another column, with that column
+    pub renamed: Vec<(String, String)>,
+    /// Merged columns this file has no values for
+    pub missing: Vec<String>,
+}
+
+/// The merged schema of several files after reconciliation
+#[derive(Debug, Clone)]
+pub struct ReconciledHeaders {
+    pub columns: Vec<String>,
+    pub files: Vec<FileMapping>,
+    aliases: Vec<Vec<String>>,
+    paths: Vec<PathBuf>,
+    options: LoadOptions,
+}
+
+impl ReconciledHeaders {
+    /// Iterate the rows of the reconciled files with merged columns, as `concat_streaming`
+    pub fn concat_streaming(&self) -> ConcatRows {
+        ConcatRows::with_aliases(
+            self.paths.clone(),
+            self.options.clone(),
+            self.columns.clone(),
+            self.aliases.clone(),
+        )
+    }
+}
+
+impl fmt::Display for ReconciledHeaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Columns: '{}'", self.columns.join("', '"))?;
+
+        for (i, file) in self.files.iter().enumerate() {
+            match &file.path {
+                Some(path) => write!(f, "\n{}:", path.display())?,
+                None => write!(f, "\nFile {}:", i + 1)?,
+            }
+
+            for (header, column) in &file.renamed {
+                write!(f, "\n  '{}' -> '{}'", header, column)?;
+            }
+
+            if !file.missing.is_empty() {
+                write!(f, "\n  Missing: '{}'", file.missing.join("', '"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
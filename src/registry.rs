@@ -0,0 +1,147 @@
+//! Named reference workbooks loaded once and shared across a process
+
+use crate::{LoadError, LoadOptions, WorkbookData};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::SystemTime;
+
+struct Entry {
+    path: PathBuf,
+    options: LoadOptions,
+    loaded: Option<Loaded>,
+}
+
+struct Loaded {
+    modified: Option<SystemTime>,
+    data: Arc<WorkbookData>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Entry {
+    fn get(&mut self) -> Result<Arc<WorkbookData>, LoadError> {
+        let modified = modified(&self.path);
+
+        if let Some(loaded) = &self.loaded {
+            if loaded.modified == modified {
+                return Ok(loaded.data.clone());
+            }
+        }
+
+        let data = Arc::new(WorkbookData::from_path_with_options(
+            &self.path,
+            &self.options,
+        )?);
+
+        self.loaded = Some(Loaded {
+            modified,
+            data: data.clone(),
+        });
+
+        Ok(data)
+    }
+}
+
+/// Reference workbooks registered by name, loaded on first use and shared as `Arc`s
+///
+/// A workbook is reloaded when its file's modification time changes, or after `invalidate`.
+/// Callers holding an earlier `Arc` keep their copy.
+#[derive(Default)]
+pub struct Registry {
+    entries: RwLock<HashMap<String, Arc<Mutex<Entry>>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the registry shared by the whole process
+    pub fn global() -> &'static Registry {
+        static GLOBAL: OnceLock<Registry> = OnceLock::new();
+        GLOBAL.get_or_init(Registry::new)
+    }
+
+    /// Register (or replace) a workbook under `name`; it is not loaded until first requested
+    pub fn register<P: AsRef<Path>>(&self, name: &str, path: P) {
+        self.register_with_options(name, path, LoadOptions::default());
+    }
+
+    pub fn register_with_options<P: AsRef<Path>>(&self, name: &str, path: P, options: LoadOptions) {
+        let entry = Entry {
+            path: path.as_ref().to_owned(),
+            options,
+            loaded: None,
+        };
+
+        self.entries
+            .write()
+            .expect("registry lock poisoned")
+            .insert(name.to_owned(), Arc::new(Mutex::new(entry)));
+    }
+
+    /// Get a registered workbook, loading it if needed
+    ///
+    /// Concurrent requests for the same workbook wait for a single load.
+    pub fn get(&self, name: &str) -> Result<Arc<WorkbookData>, LoadError> {
+        let entry = self
+            .entries
+            .read()
+            .expect("registry lock poisoned")
+            .get(name)
+            .cloned()
+            .ok_or_else(|| LoadError::NotRegistered(name.to_owned()))?;
+
+        let mut entry = entry.lock().expect("registry entry lock poisoned");
+        entry.get()
+    }
+
+    /// Drop the loaded copy of a workbook so the next `get` reloads it
+    ///
+    /// Returns `false` if no workbook is registered under `name`.
+    pub fn invalidate(&self, name: &str) -> bool {
+        let entry = self
+            .entries
+            .read()
+            .expect("registry lock poisoned")
+            .get(name)
+            .cloned();
+
+        match entry {
+            Some(entry) => {
+                entry.lock().expect("registry entry lock poisoned").loaded = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn invalidate_all(&self) {
+        let entries = self.entries.read().expect("registry lock poisoned");
+        for entry in entries.values() {
+            entry.lock().expect("registry entry lock poisoned").loaded = None;
+        }
+    }
+
+    /// Remove a workbook from the registry, returning whether it was registered
+    pub fn unregister(&self, name: &str) -> bool {
+        self.entries
+            .write()
+            .expect("registry lock poisoned")
+            .remove(name)
+            .is_some()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.entries
+            .read()
+            .expect("registry lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,120 @@
+//! Re-reading a loaded file only when it has changed, for long-running services
+
+use crate::{LoadError, LoadOptions, WorkbookData};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Where data was loaded from, and what the file looked like at the time
+#[derive(Clone)]
+pub(crate) struct SourceFile {
+    path: PathBuf,
+    options: LoadOptions,
+    modified: Option<SystemTime>,
+    len: Option<u64>,
+}
+
+impl SourceFile {
+    /// Record a file's state; call before loading, so a write during the load counts as a change
+    pub(crate) fn new(path: &Path, options: &LoadOptions) -> Self {
+        let (modified, len) = file_state(path);
+        Self {
+            path: path.to_owned(),
+            options: options.clone(),
+            modified,
+            len,
+        }
+    }
+}
+
+fn file_state(path: &Path) -> (Option<SystemTime>, Option<u64>) {
+    match fs::metadata(path) {
+        Ok(metadata) => (metadata.modified().ok(), Some(metadata.len())),
+        Err(_) => (None, None),
+    }
+}
+
+impl WorkbookData {
+    /// Get the path this data was loaded from, if loaded with `from_path` or similar
+    pub fn source_path(&self) -> Option<&Path> {
+        self.source.as_ref().map(|s| s.path.as_path())
+    }
+
+    /// Check whether the source file has changed since this data was loaded
+    ///
+    /// Compares the file's modification time and size. With the `manifest` feature, a file whose
+    /// time changed is also hashed, so one rewritten with the same content is not stale, as long
+    /// as `fingerprint` was read before the change. Data not loaded from a file is never stale;
+    /// a source file that has been removed always is.
+    pub fn is_stale(&self) -> bool {
+        let source = match &self.source {
+            Some(source) => source,
+            None => return false,
+        };
+
+        let (modified, len) = file_state(&source.path);
+        if modified == source.modified && len == source.len {
+            return false;
+        }
+        len.is_none() || !self.same_content(&source.path)
+    }
+
+    #[cfg(feature = "manifest")]
+    fn same_content(&self, path: &Path) -> bool {
+        let fingerprint = self.fingerprint.get().cloned().flatten();
+        match (fingerprint, crate::manifest::hash_file(path)) {
+            (Some(fingerprint), Ok(file)) => fingerprint == file.sha256,
+            _ => false,
+        }
+    }
+
+    #[cfg(not(feature = "manifest"))]
+    fn same_content(&self, _path: &Path) -> bool {
+        false
+    }
+
+    /// Hash the source file for `fingerprint`, unless it has changed since loading
+    #[cfg(feature = "manifest")]
+    pub(crate) fn source_fingerprint(&self) -> Option<String> {
+        let source = self.source.as_ref()?;
+        if file_state(&source.path) != (source.modified, source.len) {
+            return None;
+        }
+        let file = crate::manifest::hash_file(&source.path).ok()?;
+        Some(file.sha256)
+    }
+
+    #[cfg(not(feature = "manifest"))]
+    pub(crate) fn source_fingerprint(&self) -> Option<String> {
+        None
+    }
+
+    /// Re-load from the source file with the original options if it has changed, returning
+    /// whether it was re-loaded
+    ///
+    /// On an error, such as the file being removed or mid-write, the current data is kept and
+    /// a later call tries again. Data not loaded from a file is left as is.
+    pub fn reload(&mut self) -> Result<bool, LoadError> {
+        let source = match &self.source {
+            Some(source) => source.clone(),
+            None => return Ok(false),
+        };
+
+        let (modified, len) = file_state(&source.path);
+        if modified == source.modified && len == source.len {
+            return Ok(false);
+        }
+        if len.is_some() && self.same_content(&source.path) {
+            // Rewritten with the same content; remember the new time so the next check is cheap
+            self.source = Some(SourceFile {
+                modified,
+                len,
+                ..source
+            });
+            return Ok(false);
+        }
+
+        *self = Self::from_path_with_options(&source.path, &source.options)?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{cell_to_str, Column, DataError, DataType, RowData, WorkbookData};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+/// Round to a number of decimal places, as the value would be written in decimal
+///
+/// Going through the decimal digits avoids the error of scaling by a power of ten, so
+/// `0.30000000000000004` rounds to exactly `0.3`. Halves round away from zero like Excel's
+/// `ROUND`, so `0.125` rounds to `0.13`.
+pub fn round_to(value: f64, places: u32) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+
+    format_fixed(value, places as usize)
+        .parse()
+        .unwrap_or(value)
+}
+
+/// The shortest decimal digits that read back as `value`, without leading zeros, and how
+/// many of them come before the decimal point (negative for leading zeros after it)
+fn decimal_digits(value: f64) -> (Vec<u8>, i32) {
+    let text = value.abs().to_string();
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let mut digits: Vec<u8> = whole.bytes().chain(fraction.bytes()).collect();
+    let mut point = whole.len() as i32;
+
+    let zeros = digits.iter().take_while(|&&d| d == b'0').count();
+    digits.drain(..zeros);
+    point -= zeros as i32;
+    if digits.is_empty() {
+        point = 0;
+    }
+    (digits, point)
+}
+
+/// Keep the first `len` digits, rounding half away from zero
+///
+/// Returns `true` if rounding up carried into a new leading digit, like `999` to `1000`.
+fn round_digits(digits: &mut Vec<u8>, len: i32) -> bool {
+    if len < 0 {
+        digits.clear();
+        return false;
+    }
+    let len = len as usize;
+    if len >= digits.len() {
+        return false;
+    }
+
+    let round_up = digits[len] >= b'5';
+    digits.truncate(len);
+    if !round_up {
+        return false;
+    }
+
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return false;
+        }
+    }
+    digits.insert(0, b'1');
+    true
+}
+
+/// Write a finite number with a fixed number of decimal places, rounding half away from zero
+///
+/// Unlike `format!("{:.2}", n)`, which rounds the binary value half to even, this rounds the
+/// decimal digits as a spreadsheet does: `2.5` shows as `3` and `0.125` as `0.13`.
+pub(crate) fn format_fixed(value: f64, places: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let (mut digits, mut point) = decimal_digits(value);
+    if round_digits(&mut digits, point + places as i32) {
+        point += 1;
+    }
+    let digit = |i: i32| match usize::try_from(i) {
+        Ok(i) => char::from(*digits.get(i).unwrap_or(&b'0')),
+        Err(_) => '0',
+    };
+
+    let mut text = String::new();
+    if value.is_sign_negative() && !digits.is_empty() {
+        text.push('-');
+    }
+    if point > 0 {
+        text.extend((0..point).map(digit));
+    } else {
+        text.push('0');
+    }
+    if places > 0 {
+        text.push('.');
+        text.extend((point..point + places as i32).map(digit));
+    }
+    text
+}
+
+/// Write a finite number in scientific notation with a fixed number of decimal places in
+/// the mantissa, rounding half away from zero
+///
+/// Returns the mantissa, like `-1.23`, and the exponent.
+pub(crate) fn format_exponent(value: f64, places: usize) -> (String, i32) {
+    if !value.is_finite() {
+        return (value.to_string(), 0);
+    }
+    let (mut digits, point) = decimal_digits(value);
+    let mut exponent = point - 1;
+    if digits.is_empty() {
+        exponent = 0;
+    } else if round_digits(&mut digits, 1 + places as i32) {
+        exponent += 1;
+    }
+    digits.resize(1 + places, b'0');
+
+    let mut mantissa = String::new();
+    if value.is_sign_negative() && digits[0] != b'0' {
+        mantissa.push('-');
+    }
+    mantissa.push(char::from(digits[0]));
+    if places > 0 {
+        mantissa.push('.');
+        mantissa.extend(digits[1..].iter().map(|&d| char::from(d)));
+    }
+    (mantissa, exponent)
+}
+
+/// Get a value rounded to `places` decimal places, if it is a float and `places` is set
+pub(crate) fn round_cell(places: Option<u32>, value: &DataType) -> Option<DataType> {
+    match (places, value) {
+        (Some(places), DataType::Float(f)) => Some(DataType::Float(round_to(*f, places))),
+        _ => None,
+    }
+}
+
+impl Column {
+    /// Get the value with this column's rounding applied, if it changes anything
+    pub(crate) fn round(&self, value: &DataType) -> Option<DataType> {
+        round_cell(self.round_places, value)
+    }
+
+    /// Get a cell as a string, with this column's rounding applied
+    pub(crate) fn cell_str<'v>(&self, value: &'v DataType) -> Cow<'v, str> {
+        match self.round(value) {
+            Some(rounded) => Cow::Owned(rounded.to_string()),
+            None => cell_to_str(value),
+        }
+    }
+}
+
+impl WorkbookData {
+    /// Round a column's floating-point values to this many decimal places wherever they are
+    /// read, including `get`, `Display` and exports
+    ///
+    /// Returns `false` if no column has this header.
+    pub fn set_rounding(&mut self, column_header: &str, places: u32) -> bool {
+        let mut found = false;
+        for column in self.columns.iter_mut().filter(|c| c.name == column_header) {
+            column.round_places = Some(places);
+            found = true;
+        }
+        found
+    }
+
+    pub fn rounding(&self, column_header: &str) -> Option<u32> {
+        self.columns[self.column_id(column_header)?.0].round_places
+    }
+}
+
+impl<'a> RowData<'a> {
+    /// Get a number, with the column's rounding (if any) applied
+    pub fn get_f64(&self, column_header: &str) -> Result<f64, DataError> {
+        self.parse(column_header)
+    }
+
+    /// Get a number rounded to this many decimal places
+    pub fn get_f64_rounded(&self, column_header: &str, places: u32) -> Result<f64, DataError> {
+        self.get_f64(column_header).map(|f| round_to(f, places))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_halves_away_from_zero() {
+        assert_eq!(round_to(0.125, 2), 0.13);
+        assert_eq!(round_to(-0.125, 2), -0.13);
+        assert_eq!(round_to(2.5, 0), 3.0);
+        assert_eq!(round_to(0.1 + 0.2, 2), 0.3);
+        assert_eq!(round_to(1.005, 2), 1.01);
+    }
+
+    #[test]
+    fn formats_fixed_and_exponent() {
+        assert_eq!(format_fixed(2.5, 0), "3");
+        assert_eq!(format_fixed(999.95, 1), "1000.0");
+        assert_eq!(format_fixed(0.004, 2), "0.00");
+        assert_eq!(format_fixed(-0.004, 2), "0.00");
+        assert_eq!(format_fixed(-0.005, 2), "-0.01");
+        assert_eq!(format_fixed(1e-7, 3), "0.000");
+        assert_eq!(format_fixed(1234.5, 0), "1235");
+        assert_eq!(format_exponent(12345.0, 2), ("1.23".to_owned(), 4));
+        assert_eq!(format_exponent(-99_950.0, 2), ("-1.00".to_owned(), 5));
+        assert_eq!(format_exponent(0.000_125, 1), ("1.3".to_owned(), -4));
+        assert_eq!(format_exponent(0.0, 2), ("0.00".to_owned(), 0));
+        assert_eq!(format_exponent(7.0, 0), ("7".to_owned(), 0));
+    }
+}
@@ -0,0 +1,407 @@
+use crate::stats::Timer;
+use crate::validate::RowChecker;
+use crate::{cell_is_empty, Constraint, DataType, RowData, RowsIterator, WorkbookData};
+use std::fmt;
+
+/// Maximum number of offending rows listed per column in a `SchemaMismatch` message
+pub(crate) const MAX_LISTED_ROWS: usize = 5;
+
+/// The expected kind of value in a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Bool,
+    DateTime,
+    /// Any value (every cell can be read as a string)
+    String,
+}
+
+impl ColumnType {
+    /// Get the natural type of a cell value, or `None` for empty cells and error values
+    pub fn of(value: &DataType) -> Option<ColumnType> {
+        match value {
+            DataType::Int(_) => Some(ColumnType::Integer),
+            DataType::Float(f) if f.fract() == 0. => Some(ColumnType::Integer),
+            DataType::Float(_) => Some(ColumnType::Float),
+            DataType::Bool(_) => Some(ColumnType::Bool),
+            DataType::DateTime(_) => Some(ColumnType::DateTime),
+            DataType::String(s) if s.is_empty() => None,
+            DataType::String(_) => Some(ColumnType::String),
+            DataType::Error(_) | DataType::Empty => None,
+        }
+    }
+
+    /// Check if a non-empty cell value can be read as this type
+    ///
+    /// Numbers stored as text are accepted for numeric types, and whole floats for integers,
+    /// since xlsx files store every number as a float.
+    pub fn accepts(self, value: &DataType) -> bool {
+        match (self, value) {
+            (_, DataType::Error(_)) => false,
+            (ColumnType::String, _) => true,
+            (ColumnType::Integer, DataType::Int(_)) => true,
+            (ColumnType::Integer, DataType::Float(f)) => f.fract() == 0.,
+            (ColumnType::Integer, DataType::String(s)) => s.trim().parse::<i64>().is_ok(),
+            (ColumnType::Float, DataType::Int(_)) | (ColumnType::Float, DataType::Float(_)) => true,
+            (ColumnType::Float, DataType::String(s)) => s.trim().parse::<f64>().is_ok(),
+            (ColumnType::Bool, DataType::Bool(_)) => true,
+            (ColumnType::Bool, DataType::String(s)) => s.trim().parse::<bool>().is_ok(),
+            (ColumnType::DateTime, DataType::DateTime(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColumnType::Integer => "integer",
+            ColumnType::Float => "number",
+            ColumnType::Bool => "boolean",
+            ColumnType::DateTime => "date/time",
+            ColumnType::String => "text",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SchemaColumn {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub required: bool,
+    pub constraints: Vec<Constraint>,
+}
+
+/// The set of columns a sheet is expected to have
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    pub(crate) columns: Vec<SchemaColumn>,
+    allow_extra_columns: bool,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a required column
+    pub fn column(mut self, name: &str, column_type: ColumnType) -> Self {
+        self.columns.push(SchemaColumn {
+            name: name.to_owned(),
+            column_type,
+            required: true,
+            constraints: Vec::new(),
+        });
+        self
+    }
+
+    /// Add a column that is type-checked if present, but may be missing
+    pub fn optional_column(mut self, name: &str, column_type: ColumnType) -> Self {
+        self.columns.push(SchemaColumn {
+            name: name.to_owned(),
+            column_type,
+            required: false,
+            constraints: Vec::new(),
+        });
+        self
+    }
+
+    /// Accept columns that are not in the schema instead of reporting them
+    pub fn allow_extra_columns(mut self, allow: bool) -> Self {
+        self.allow_extra_columns = allow;
+        self
+    }
+
+    pub fn columns(&self) -> &[SchemaColumn] {
+        &self.columns
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SchemaColumn> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// List the headers in `data` that are not in this schema, unless extra columns are allowed
+    pub(crate) fn unexpected_columns(&self, data: &WorkbookData) -> Vec<String> {
+        let mut unexpected: Vec<String> = Vec::new();
+
+        if !self.allow_extra_columns {
+            // Blank header cells are not columns anyone asked for, so don't report them
+            for header in data.headers().filter(|h| !h.is_empty()) {
+                if self.get(header).is_none() && !unexpected.iter().any(|h| h == header) {
+                    unexpected.push(header.to_owned());
+                }
+            }
+        }
+
+        unexpected
+    }
+}
+
+/// Cells in one column whose values do not match the expected type
+#[derive(Debug, Clone)]
+pub struct TypeConflict {
+    pub column: String,
+    /// Where the column is in the sheet, like `"Column AJ"`
+    pub position: String,
+    pub expected: ColumnType,
+    /// Row numbers of every offending cell
+    pub rows: Vec<u32>,
+    /// The first offending value
+    pub example: String,
+}
+
+impl fmt::Display for TypeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Row numbers are zero-based; show them as sheet rows
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .take(MAX_LISTED_ROWS)
+            .map(|r| (r + 1).to_string())
+            .collect();
+
+        write!(
+            f,
+            "{} / '{}' expects {} values, but found '{}' (rows {}",
+            self.position,
+            self.column,
+            self.expected,
+            self.example,
+            rows.join(", ")
+        )?;
+
+        if self.rows.len() > MAX_LISTED_ROWS {
+            write!(f, " and {} more", self.rows.len() - MAX_LISTED_ROWS)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+/// Every way a sheet deviates from a `Schema`
+#[derive(Debug, Clone, Default, thiserror::Error)]
+pub struct SchemaMismatch {
+    pub missing: Vec<String>,
+    pub unexpected: Vec<String>,
+    pub type_conflicts: Vec<TypeConflict>,
+}
+
+impl SchemaMismatch {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty() && self.type_conflicts.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Data does not match the expected schema")?;
+
+        if !self.missing.is_empty() {
+            write!(f, "\n  Missing columns: '{}'", self.missing.join("', '"))?;
+        }
+
+        if !self.unexpected.is_empty() {
+            write!(
+                f,
+                "\n  Unexpected columns: '{}'",
+                self.unexpected.join("', '")
+            )?;
+        }
+
+        for conflict in &self.type_conflicts {
+            write!(f, "\n  {}", conflict)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl WorkbookData {
+    /// Check the headers and cell types against a schema, collecting every mismatch
+    pub fn expect_schema(&self, schema: &Schema) -> Result<(), SchemaMismatch> {
+        let start = Timer::start();
+        let mut mismatch = SchemaMismatch::default();
+        let mut cells = 0;
+
+        for column in schema.columns() {
+            let id = match self.column_id(&column.name) {
+                Some(id) => id,
+                None => {
+                    if column.required {
+                        mismatch.missing.push(column.name.clone());
+                    }
+                    continue;
+                }
+            };
+
+            let column_start = Timer::start();
+            let index = self.columns[id.0].index;
+            let mut conflict: Option<TypeConflict> = None;
+
+            for row in self.first_row..=self.last_row {
+                let value = match self.range.get_value((row, index)) {
+                    Some(value) if !cell_is_empty(value) => value,
+                    _ => continue,
+                };
+
+                if !column.column_type.accepts(value) {
+                    conflict
+                        .get_or_insert_with(|| TypeConflict {
+                            column: column.name.clone(),
+                            position: self.column_position(&column.name),
+                            expected: column.column_type,
+                            rows: Vec::new(),
+                            example: self.cell_text(row, &self.columns[id.0], value).into_owned(),
+                        })
+                        .rows
+                        .push(row);
+                }
+            }
+
+            let column_cells = u64::from((self.last_row + 1).saturating_sub(self.first_row));
+            self.columns[id.0]
+                .counters
+                .record_pass(column_cells, column_start);
+            cells += column_cells;
+
+            mismatch.type_conflicts.extend(conflict);
+        }
+
+        self.counters.record_pass(cells, start);
+
+        mismatch.unexpected = schema.unexpected_columns(self);
+
+        if mismatch.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatch)
+        }
+    }
+}
+
+/// A way a single row deviates from a `Schema`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Violation {
+    /// A required column is not in the sheet
+    MissingColumn(String),
+    TypeMismatch {
+        column: String,
+        /// Where the column is in the sheet, like `"Column AJ"`
+        position: String,
+        expected: ColumnType,
+        value: String,
+    },
+    /// A cell in a `not_empty` column is empty
+    Empty { column: String, position: String },
+    /// A value breaks a column constraint
+    Constraint {
+        column: String,
+        position: String,
+        constraint: Constraint,
+        value: String,
+    },
+    /// A value in a `unique` column already appeared in an earlier row
+    Duplicate {
+        column: String,
+        position: String,
+        value: String,
+        first_row: u32,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::MissingColumn(column) => write!(f, "missing column '{}'", column),
+            Violation::TypeMismatch {
+                column,
+                position,
+                expected,
+                value,
+            } => write!(
+                f,
+                "{} / '{}' expects {} values, but found '{}'",
+                position, column, expected, value
+            ),
+            Violation::Empty { column, position } => {
+                write!(f, "{} / '{}' must not be empty", position, column)
+            }
+            Violation::Constraint {
+                column,
+                position,
+                constraint,
+                value,
+            } => write!(
+                f,
+                "{} / '{}' value '{}' {}",
+                position, column, value, constraint
+            ),
+            // Row numbers are zero-based; show them as sheet rows
+            Violation::Duplicate {
+                column,
+                position,
+                value,
+                first_row,
+            } => write!(
+                f,
+                "{} / '{}' value '{}' already appears in row {}",
+                position,
+                column,
+                value,
+                first_row + 1
+            ),
+        }
+    }
+}
+
+/// Every way a single row deviates from a `Schema`
+#[derive(Debug, Clone, thiserror::Error)]
+pub struct Violations {
+    pub row: u32,
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for Violations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let violations: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        // Row numbers are zero-based; show them as sheet rows
+        write!(f, "Row {}: {}", self.row + 1, violations.join("; "))
+    }
+}
+
+impl<'a> RowsIterator<'a> {
+    /// Check each row against a schema as it is yielded
+    pub fn validated(self, schema: &Schema) -> Validated<'a, '_> {
+        Validated {
+            checker: RowChecker::new(schema, self.source),
+            inner: self,
+        }
+    }
+}
+
+/// Rows iterator adapter returned by `RowsIterator::validated`
+pub struct Validated<'a, 's> {
+    inner: RowsIterator<'a>,
+    checker: RowChecker<'s>,
+}
+
+impl<'a, 's> Iterator for Validated<'a, 's> {
+    type Item = Result<RowData<'a>, Violations>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.inner.next()?;
+        let violations = self.checker.check(&row, true);
+
+        Some(if violations.is_empty() {
+            Ok(row)
+        } else {
+            Err(Violations {
+                row: row.row_number,
+                violations,
+            })
+        })
+    }
+}
@@ -0,0 +1,75 @@
+//! The sheets of a workbook, with their visibility
+
+use crate::xlsx::{self, XlsxPackage};
+use crate::LoadError;
+use calamine::{open_workbook_auto, Reader};
+use std::path::Path;
+
+/// Whether a sheet's tab is shown in the workbook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SheetVisibility {
+    #[default]
+    Visible,
+    /// Hidden, but can be unhidden from the spreadsheet's menus
+    Hidden,
+    /// Hidden so it can only be unhidden with macros, typical of lookup and config sheets
+    VeryHidden,
+}
+
+impl SheetVisibility {
+    /// Read the `state` attribute of a workbook's `sheet` element
+    pub(crate) fn from_state(state: Option<&str>) -> Self {
+        match state {
+            Some("hidden") => SheetVisibility::Hidden,
+            Some("veryHidden") => SheetVisibility::VeryHidden,
+            _ => SheetVisibility::Visible,
+        }
+    }
+
+    pub fn is_visible(self) -> bool {
+        self == SheetVisibility::Visible
+    }
+}
+
+/// A sheet of a workbook, from `list_sheets`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetInfo {
+    pub name: String,
+    /// Only xlsx files record visibility; sheets of other formats are always `Visible`
+    pub visibility: SheetVisibility,
+}
+
+/// List the sheets of a workbook in tab order, including hidden ones
+///
+/// A delimited file is a single sheet named after the file.
+pub fn list_sheets<P: AsRef<Path>>(path: P) -> Result<Vec<SheetInfo>, LoadError> {
+    let path = path.as_ref();
+
+    #[cfg(feature = "csv")]
+    if crate::delimited::delimiter(path).is_some() {
+        return Ok(vec![SheetInfo {
+            name: crate::delimited::sheet_name(path),
+            visibility: SheetVisibility::Visible,
+        }]);
+    }
+
+    if xlsx::is_xlsx_path(path) {
+        return Ok(XlsxPackage::open(path)?
+            .sheets()?
+            .into_iter()
+            .map(|s| SheetInfo {
+                name: s.name,
+                visibility: s.visibility,
+            })
+            .collect());
+    }
+
+    Ok(open_workbook_auto(path)?
+        .sheet_names()
+        .iter()
+        .map(|name| SheetInfo {
+            name: name.clone(),
+            visibility: SheetVisibility::Visible,
+        })
+        .collect())
+}
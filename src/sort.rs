@@ -0,0 +1,86 @@
+use crate::{cell_is_empty, cell_to_str, ColumnId, DataError, DataType, RowData, WorkbookData};
+use std::cmp::Ordering;
+
+/// Direction of one sort key for `WorkbookData::sorted_rows`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Rank of a cell's type in Excel's ascending sort: numbers and dates, text, booleans, errors
+fn type_rank(value: &DataType) -> u8 {
+    match value {
+        DataType::Int(_) | DataType::Float(_) | DataType::DateTime(_) => 0,
+        DataType::String(_) => 1,
+        DataType::Bool(_) => 2,
+        DataType::Error(_) => 3,
+        DataType::Empty => 4,
+    }
+}
+
+fn number(value: &DataType) -> f64 {
+    match value {
+        DataType::Int(i) => *i as f64,
+        DataType::Float(f) | DataType::DateTime(f) => *f,
+        _ => 0.,
+    }
+}
+
+/// Compare two non-empty cells of the same sort key
+fn compare_cells(a: &DataType, b: &DataType) -> Ordering {
+    type_rank(a).cmp(&type_rank(b)).then_with(|| match (a, b) {
+        (DataType::Int(x), DataType::Int(y)) => x.cmp(y),
+        (DataType::String(x), DataType::String(y)) => {
+            // Excel sorts text case-insensitively
+            x.to_lowercase()
+                .cmp(&y.to_lowercase())
+                .then_with(|| x.cmp(y))
+        }
+        (DataType::Bool(x), DataType::Bool(y)) => x.cmp(y),
+        (DataType::Error(_), DataType::Error(_)) => cell_to_str(a).cmp(&cell_to_str(b)),
+        _ => number(a).total_cmp(&number(b)),
+    })
+}
+
+impl WorkbookData {
+    /// Get the rows sorted by each key in turn, like `[("Date", Order::Asc), ("Amount",
+    /// Order::Desc)]`
+    ///
+    /// Cells compare by value as Excel sorts them: numbers and dates numerically, then text
+    /// case-insensitively, then booleans and errors. Empty cells come last in either direction.
+    /// The sort is stable, so rows with equal keys keep their sheet order.
+    pub fn sorted_rows(&self, keys: &[(&str, Order)]) -> Result<Vec<RowData<'_>>, DataError> {
+        let keys = keys
+            .iter()
+            .map(|&(column_header, order)| {
+                self.column_id(column_header)
+                    .map(|id| (id, order))
+                    .ok_or_else(|| DataError::NoValue(column_header.into()))
+            })
+            .collect::<Result<Vec<(ColumnId, Order)>, _>>()?;
+
+        let mut rows: Vec<RowData<'_>> = self.rows().collect();
+        rows.sort_by(|a, b| {
+            keys.iter().fold(Ordering::Equal, |ordering, &(id, order)| {
+                ordering.then_with(|| {
+                    let a = self
+                        .get_cell_by_id(a.number(), id)
+                        .filter(|v| !cell_is_empty(v));
+                    let b = self
+                        .get_cell_by_id(b.number(), id)
+                        .filter(|v| !cell_is_empty(v));
+                    match (a, b) {
+                        (Some(a), Some(b)) if order == Order::Asc => compare_cells(a, b),
+                        (Some(a), Some(b)) => compare_cells(b, a),
+                        (Some(_), None) => Ordering::Less,
+                        (None, Some(_)) => Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    }
+                })
+            })
+        });
+        Ok(rows)
+    }
+}
@@ -0,0 +1,184 @@
+//! Export to SQLite tables
+
+use crate::{coords, ColumnValues, CompactColumn, CompactData, WorkbookData, WritePlan};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+
+/// Days from the Excel epoch (1899-12-30) to the Unix epoch
+const EXCEL_UNIX_EPOCH_DAYS: f64 = 25569.;
+const SECONDS_PER_DAY: f64 = 86_400.;
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn sql_type(column: &CompactColumn) -> &'static str {
+    match column.values() {
+        ColumnValues::Int(_) | ColumnValues::Bool(_) => "INTEGER",
+        ColumnValues::Float(_) => "REAL",
+        ColumnValues::DateTime(_) | ColumnValues::Str { .. } => "TEXT",
+    }
+}
+
+fn sql_value(column: &CompactColumn, index: usize) -> Value {
+    if !column.is_present(index) {
+        return Value::Null;
+    }
+
+    match column.values() {
+        ColumnValues::Int(v) => Value::Integer(v[index]),
+        ColumnValues::Bool(v) => Value::Integer(i64::from(v[index])),
+        ColumnValues::Float(v) => Value::Real(v[index]),
+        // Converted to text by the insert statement
+        ColumnValues::DateTime(v) => {
+            Value::Real((v[index] - EXCEL_UNIX_EPOCH_DAYS) * SECONDS_PER_DAY)
+        }
+        ColumnValues::Str { .. } => column
+            .get_str(index)
+            .map_or(Value::Null, |s| Value::Text(s.into_owned())),
+    }
+}
+
+impl WorkbookData {
+    /// Create a table from the headers and insert every data row, returning the row count
+    ///
+    /// Column types are inferred as in `compact`: integers and booleans become `INTEGER`,
+    /// floats `REAL`, and dates `TEXT` in SQLite's `YYYY-MM-DD HH:MM:SS` format. Empty cells are
+    /// `NULL`. Blank headers are named after their column letter. All rows are inserted in one
+    /// transaction, and the table must not already exist.
+    pub fn to_sqlite(&self, conn: &Connection, table_name: &str) -> rusqlite::Result<usize> {
+        let compact = self.compact();
+        let (names, create) = self.create_table(&compact, table_name);
+
+        let placeholders: Vec<&str> = compact
+            .columns()
+            .iter()
+            .map(|column| match column.values() {
+                ColumnValues::DateTime(_) => "datetime(?, 'unixepoch')",
+                _ => "?",
+            })
+            .collect();
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(&create, [])?;
+
+        {
+            let mut insert = tx.prepare(&format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                quote_identifier(table_name),
+                names.join(", "),
+                placeholders.join(", ")
+            ))?;
+
+            for index in 0..compact.len() {
+                let values = compact.columns().iter().map(|c| sql_value(c, index));
+                insert.execute(params_from_iter(values))?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(compact.len())
+    }
+
+    /// Do a dry run of `to_sqlite`, checking that the table can be created without changing
+    /// the database
+    pub fn plan_to_sqlite(
+        &self,
+        conn: &Connection,
+        table_name: &str,
+    ) -> rusqlite::Result<WritePlan> {
+        let compact = self.compact();
+        let (names, create) = self.create_table(&compact, table_name);
+
+        // Preparing the statement checks the names and that the table does not exist yet
+        conn.prepare(&create)?;
+
+        let cells = names.len()
+            + compact
+                .columns()
+                .iter()
+                .map(|c| (0..compact.len()).filter(|&i| c.is_present(i)).count())
+                .sum::<usize>();
+        let path = conn.path().filter(|p| !p.is_empty());
+        Ok(WritePlan::new(path, table_name, compact.len(), cells))
+    }
+
+    /// Get the quoted column names and the `CREATE TABLE` statement for `to_sqlite`
+    fn create_table(&self, compact: &CompactData, table_name: &str) -> (Vec<String>, String) {
+        let names: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| {
+                if c.name.is_empty() {
+                    quote_identifier(&coords::column_letter(c.index))
+                } else {
+                    quote_identifier(&c.name)
+                }
+            })
+            .collect();
+
+        let definitions: Vec<String> = names
+            .iter()
+            .zip(compact.columns())
+            .map(|(name, column)| format!("{} {}", name, sql_type(column)))
+            .collect();
+
+        let create = format!(
+            "CREATE TABLE {} ({})",
+            quote_identifier(table_name),
+            definitions.join(", ")
+        );
+        (names, create)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_range, DataType, LoadOptions, Range, WorkbookData};
+    use rusqlite::Connection;
+
+    fn orders() -> WorkbookData {
+        let mut range = Range::new((0, 0), (2, 1));
+        range.set_value((0, 0), DataType::String("Item".into()));
+        range.set_value((0, 1), DataType::String("Qty".into()));
+        range.set_value((1, 0), DataType::String("Pen".into()));
+        range.set_value((1, 1), DataType::Int(3));
+        range.set_value((2, 0), DataType::String("Ink".into()));
+        from_range(range, &LoadOptions::new()).unwrap()
+    }
+
+    fn tables(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn inserts_typed_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(orders().to_sqlite(&conn, "orders").unwrap(), 2);
+
+        let qty: Vec<Option<i64>> = conn
+            .prepare("SELECT Qty FROM orders ORDER BY rowid")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(qty, [Some(3), None]);
+        assert!(orders().to_sqlite(&conn, "orders").is_err());
+    }
+
+    #[test]
+    fn plans_a_table_without_creating_it() {
+        let data = orders();
+        let conn = Connection::open_in_memory().unwrap();
+
+        let plan = data.plan_to_sqlite(&conn, "orders").unwrap();
+        assert_eq!(plan.path, None);
+        assert_eq!((plan.rows(), plan.sheets[0].cells), (2, 5));
+        assert_eq!(tables(&conn), 0);
+
+        data.to_sqlite(&conn, "orders").unwrap();
+        assert!(data.plan_to_sqlite(&conn, "orders").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Counters gathered while reading and converting data, for finding slow or messy columns
+
+use crate::WorkbookData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Running totals, updated through `&self` so read-only operations can record into them
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    cells: AtomicU64,
+    nanos: AtomicU64,
+    parsed: AtomicU64,
+    parse_failures: AtomicU64,
+}
+
+/// The start of a timed pass
+///
+/// `wasm32-unknown-unknown` has no clock (`Instant::now` panics there), so passes are counted
+/// but not timed.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timer(Option<Instant>);
+
+impl Timer {
+    pub fn start() -> Self {
+        if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+            Self(None)
+        } else {
+            Self(Some(Instant::now()))
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.map_or(Duration::ZERO, |start| start.elapsed())
+    }
+}
+
+impl Counters {
+    pub fn record_pass(&self, cells: u64, start: Timer) {
+        let nanos = start.elapsed().as_nanos().min(u128::from(u64::MAX)) as u64;
+        self.cells.fetch_add(cells, Ordering::Relaxed);
+        self.nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    pub fn record_parse(&self, ok: bool) {
+        let counter = if ok {
+            &self.parsed
+        } else {
+            &self.parse_failures
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        for counter in [&self.cells, &self.nanos, &self.parsed, &self.parse_failures] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn load(&self) -> (u64, Duration, u64, u64) {
+        (
+            self.cells.load(Ordering::Relaxed),
+            Duration::from_nanos(self.nanos.load(Ordering::Relaxed)),
+            self.parsed.load(Ordering::Relaxed),
+            self.parse_failures.load(Ordering::Relaxed),
+        )
+    }
+}
+
+fn per_sec(cells: u64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.
+    } else {
+        cells as f64 / elapsed.as_secs_f64()
+    }
+}
+
+/// Statistics for one column
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnImportStats {
+    pub name: String,
+    /// Cells processed by column-at-a-time operations (`compact`, `expect_schema`)
+    pub cells: u64,
+    pub elapsed: Duration,
+    /// Successful typed reads (`RowData::parse` and friends)
+    pub parsed: u64,
+    pub parse_failures: u64,
+}
+
+impl ColumnImportStats {
+    pub fn cells_per_sec(&self) -> f64 {
+        per_sec(self.cells, self.elapsed)
+    }
+}
+
+/// Statistics gathered since loading (or the last `reset_stats`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportStats {
+    /// Cells processed by full-table operations (conversions, exports and schema checks)
+    pub cells: u64,
+    pub elapsed: Duration,
+    pub parsed: u64,
+    pub parse_failures: u64,
+    pub columns: Vec<ColumnImportStats>,
+}
+
+impl ImportStats {
+    pub fn cells_per_sec(&self) -> f64 {
+        per_sec(self.cells, self.elapsed)
+    }
+
+    pub fn column(&self, column_header: &str) -> Option<&ColumnImportStats> {
+        self.columns.iter().find(|c| c.name == column_header)
+    }
+
+    /// Get the column with the most parse failures, if any failed
+    pub fn most_failures(&self) -> Option<&ColumnImportStats> {
+        self.columns
+            .iter()
+            .filter(|c| c.parse_failures > 0)
+            .max_by_key(|c| c.parse_failures)
+    }
+
+    /// Get the column with the lowest throughput, among those with timed cells
+    pub fn slowest(&self) -> Option<&ColumnImportStats> {
+        self.columns
+            .iter()
+            .filter(|c| c.cells > 0)
+            .min_by(|a, b| a.cells_per_sec().total_cmp(&b.cells_per_sec()))
+    }
+}
+
+impl WorkbookData {
+    pub fn stats(&self) -> ImportStats {
+        let columns: Vec<ColumnImportStats> = self
+            .columns
+            .iter()
+            .map(|c| {
+                let (cells, elapsed, parsed, parse_failures) = c.counters.load();
+                ColumnImportStats {
+                    name: c.name.clone(),
+                    cells,
+                    elapsed,
+                    parsed,
+                    parse_failures,
+                }
+            })
+            .collect();
+
+        let (cells, elapsed, _, _) = self.counters.load();
+
+        ImportStats {
+            cells,
+            elapsed,
+            parsed: columns.iter().map(|c| c.parsed).sum(),
+            parse_failures: columns.iter().map(|c| c.parse_failures).sum(),
+            columns,
+        }
+    }
+
+    pub fn reset_stats(&self) {
+        self.counters.reset();
+        for column in &self.columns {
+            column.counters.reset();
+        }
+    }
+
+    /// Count a typed read of a column
+    pub(crate) fn record_parse(&self, column_header: &str, ok: bool) {
+        if let Some(id) = self.column_id(column_header) {
+            self.columns[id.0].counters.record_parse(ok);
+        }
+    }
+
+    /// Number of cells in the data rows, for recording full-table passes
+    pub(crate) fn data_cells(&self) -> u64 {
+        let rows = (self.last_row + 1).saturating_sub(self.first_row);
+        u64::from(rows) * self.columns.len() as u64
+    }
+}
@@ -0,0 +1,929 @@
+//! Reading huge sheets row by row in bounded memory, instead of loading the whole range
+
+use crate::coords::{self, Span};
+use crate::xlsx::{self, XlsxPackage};
+use crate::{
+    cell_is_empty, cell_to_str, trace, CellErrorKind, DataType, HeaderDetector, HeuristicDetector,
+    LoadError, LoadOptions, OwnedRow, Range, WorkbookData,
+};
+use crate::{numfmt, stringify};
+use calamine::Cell;
+use quick_xml::events::Event;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Rows buffered from the top of a sheet to find the header, unless `max_header_scan_rows`
+/// is set
+const HEADER_SCAN_ROWS: usize = 100;
+
+/// Relationship type of the workbook's shared strings part
+pub(crate) const SHARED_STRINGS_REL: &str = "/sharedStrings";
+
+/// A row's non-empty cells as (absolute column, value), and its absolute row number
+type SparseRow = (u32, Vec<(u32, DataType)>);
+
+/// A cell as written in the sheet XML, before shared strings are looked up
+pub(crate) struct RawCell {
+    pub position: (u32, u32),
+    /// The `t` attribute
+    pub kind: Option<String>,
+    pub value: String,
+}
+
+impl RawCell {
+    pub fn to_data_type(&self, strings: &SharedStrings) -> Result<DataType, LoadError> {
+        let value = self.value.trim();
+        Ok(match self.kind.as_deref() {
+            Some("s") => match value.parse::<usize>() {
+                Ok(i) => strings.get(i)?.map_or(DataType::Empty, DataType::String),
+                Err(_) => DataType::Empty,
+            },
+            Some("inlineStr") | Some("str") | Some("d") => DataType::String(self.value.clone()),
+            Some("b") => DataType::Bool(value == "1"),
+            Some("e") => CellErrorKind::from_code(value).map_or_else(
+                || DataType::String(value.to_owned()),
+                |e| DataType::Error(e.into()),
+            ),
+            _ => value.parse().map_or(DataType::Empty, DataType::Float),
+        })
+    }
+}
+
+/// Collects the text of `<t>` and `<v>` elements, leaving out phonetic guides (`<rPh>`)
+#[derive(Default)]
+struct TextState {
+    value: String,
+    in_text: bool,
+    in_phonetic: bool,
+}
+
+impl TextState {
+    fn read(&mut self, event: &Event) -> Result<(), LoadError> {
+        match event {
+            Event::Start(e) if e.local_name() == b"rPh" => self.in_phonetic = true,
+            Event::End(e) if e.local_name() == b"rPh" => self.in_phonetic = false,
+            Event::Start(e) if matches!(e.local_name(), b"t" | b"v") => self.in_text = true,
+            Event::End(e) if matches!(e.local_name(), b"t" | b"v") => self.in_text = false,
+            Event::Text(t) if self.in_text && !self.in_phonetic => {
+                self.value
+                    .push_str(&String::from_utf8_lossy(&t.unescaped()?));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Reads a worksheet's XML one row at a time
+pub(crate) struct SheetReader<B: BufRead> {
+    reader: quick_xml::Reader<B>,
+    buf: Vec<u8>,
+    /// The extent the sheet declares, once read (it comes before the cells)
+    pub dimension: Option<Span>,
+    /// The last row started; rows and cells may leave out their `r` attribute, meaning the
+    /// one after the previous
+    row: Option<u32>,
+    done: bool,
+}
+
+impl<B: BufRead> SheetReader<B> {
+    pub fn new(xml: B) -> Self {
+        Self {
+            reader: quick_xml::Reader::from_reader(xml),
+            buf: Vec::new(),
+            dimension: None,
+            row: None,
+            done: false,
+        }
+    }
+
+    /// Advance to the row a `row` element starts, from its `r` attribute if it has one
+    fn start_row(&mut self, r: Option<String>) -> u32 {
+        let row = r
+            .and_then(|r| r.parse::<u32>().ok())
+            .map_or_else(|| self.row.map_or(0, |r| r + 1), |r| r.saturating_sub(1));
+        self.row = Some(row);
+        row
+    }
+
+    /// Read the next row that has cells, as its absolute row number and cells
+    pub fn next_row(&mut self) -> Result<Option<(u32, Vec<RawCell>)>, LoadError> {
+        let mut cells = Vec::new();
+        let mut current = None;
+        let mut next_col = 0;
+        let mut cell: Option<RawCell> = None;
+        let mut text = TextState::default();
+
+        while !self.done {
+            self.buf.clear();
+            match self.reader.read_event(&mut self.buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) if e.local_name() == b"dimension" => {
+                    self.dimension = xlsx::attr(e, b"ref")
+                        .as_deref()
+                        .and_then(coords::parse_range);
+                }
+                Event::Start(ref e) if e.local_name() == b"row" => {
+                    let r = xlsx::attr(e, b"r");
+                    current = Some(self.start_row(r));
+                    next_col = 0;
+                }
+                Event::Empty(ref e) if e.local_name() == b"row" => {
+                    let r = xlsx::attr(e, b"r");
+                    self.start_row(r);
+                }
+                Event::Start(ref e) if e.local_name() == b"c" => {
+                    let position = xlsx::attr(e, b"r")
+                        .as_deref()
+                        .and_then(coords::parse_cell)
+                        .unwrap_or((current.unwrap_or(0), next_col));
+                    next_col = position.1 + 1;
+                    text.value.clear();
+                    cell = Some(RawCell {
+                        position,
+                        kind: xlsx::attr(e, b"t"),
+                        value: String::new(),
+                    });
+                }
+                Event::Empty(ref e) if e.local_name() == b"c" => {
+                    next_col = xlsx::attr(e, b"r")
+                        .as_deref()
+                        .and_then(coords::parse_cell)
+                        .map_or(next_col, |(_, col)| col)
+                        + 1;
+                }
+                Event::End(ref e) if e.local_name() == b"c" => {
+                    if let Some(mut cell) = cell.take() {
+                        cell.value = std::mem::take(&mut text.value);
+                        cells.push(cell);
+                    }
+                }
+                Event::End(ref e) if e.local_name() == b"row" => {
+                    if let (Some(row), false) = (current.take(), cells.is_empty()) {
+                        return Ok(Some((row, cells)));
+                    }
+                }
+                Event::End(ref e) if e.local_name() == b"sheetData" => self.done = true,
+                Event::Eof => self.done = true,
+                ref event => text.read(event)?,
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A file in the system temporary directory, removed when dropped
+struct TempFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl TempFile {
+    fn create() -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        // The clock, temporary directory and process id below all panic there
+        if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "no temporary files on this platform",
+            ));
+        }
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "excelerator-{}-{}-{}.tmp",
+                std::process::id(),
+                nanos,
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            match OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(file) => return Ok(Self { path, file }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Strings past the memory limit, as offsets into a temporary file
+struct Spill {
+    file: TempFile,
+    /// Start of each string, followed by the end of the last one
+    offsets: Vec<u64>,
+}
+
+/// A workbook's shared strings, kept in memory up to a limit and in a temporary file past it
+pub(crate) struct SharedStrings {
+    memory: Vec<String>,
+    spill: Option<Spill>,
+}
+
+impl SharedStrings {
+    pub fn empty() -> Self {
+        Self {
+            memory: Vec::new(),
+            spill: None,
+        }
+    }
+
+    /// Read the first `count` strings, keeping at most `memory_limit` bytes of them in memory
+    pub fn read<B: BufRead>(
+        xml: B,
+        count: usize,
+        memory_limit: Option<usize>,
+    ) -> Result<Self, LoadError> {
+        let mut reader = quick_xml::Reader::from_reader(xml);
+        let mut buf = Vec::new();
+        let mut text = TextState::default();
+        let mut strings = Self::empty();
+        let mut in_memory = 0;
+        let mut writer: Option<BufWriter<File>> = None;
+        let mut spill_file = None;
+        let mut offsets = Vec::new();
+        let mut read = 0;
+
+        while read < count {
+            buf.clear();
+            match reader.read_event(&mut buf)? {
+                Event::Start(ref e) if e.local_name() == b"si" => text.value.clear(),
+                Event::End(ref e) if e.local_name() == b"si" => {
+                    let value = std::mem::take(&mut text.value);
+                    read += 1;
+
+                    if writer.is_none() && memory_limit.is_none_or(|l| in_memory < l) {
+                        in_memory += value.len();
+                        strings.memory.push(value);
+                        continue;
+                    }
+
+                    let out = match &mut writer {
+                        Some(out) => out,
+                        None => {
+                            let file = TempFile::create()?;
+                            offsets.push(0);
+                            let out = writer.insert(BufWriter::new(file.file.try_clone()?));
+                            spill_file = Some(file);
+                            out
+                        }
+                    };
+                    out.write_all(value.as_bytes())?;
+                    offsets.push(offsets.last().copied().unwrap_or(0) + value.len() as u64);
+                }
+                Event::Eof => break,
+                ref event => text.read(event)?,
+            }
+        }
+
+        if let Some(mut out) = writer {
+            out.flush()?;
+        }
+        strings.spill = spill_file.map(|file| Spill { file, offsets });
+        Ok(strings)
+    }
+
+    pub fn get(&self, index: usize) -> Result<Option<String>, LoadError> {
+        if let Some(value) = self.memory.get(index) {
+            return Ok(Some(value.clone()));
+        }
+
+        let spill = match &self.spill {
+            Some(spill) => spill,
+            None => return Ok(None),
+        };
+        let i = index - self.memory.len();
+        let (start, end) = match (spill.offsets.get(i), spill.offsets.get(i + 1)) {
+            (Some(&start), Some(&end)) => (start, end),
+            _ => return Ok(None),
+        };
+
+        let mut file = &spill.file.file;
+        file.seek(SeekFrom::Start(start))?;
+        let mut bytes = vec![0; (end - start) as usize];
+        file.read_exact(&mut bytes)?;
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+/// Where `RowStream` reads rows from
+enum RowSource {
+    Xlsx {
+        sheet: SheetReader<BufReader<Box<dyn Read + Send>>>,
+        strings: Arc<SharedStrings>,
+    },
+    #[cfg(feature = "csv")]
+    Delimited {
+        records: csv::StringRecordsIntoIter<File>,
+        row: u32,
+    },
+}
+
+impl RowSource {
+    fn next_row(&mut self) -> Result<Option<SparseRow>, LoadError> {
+        match self {
+            RowSource::Xlsx { sheet, strings } => {
+                let (row, raw) = match sheet.next_row()? {
+                    Some(row) => row,
+                    None => return Ok(None),
+                };
+                let mut cells = Vec::with_capacity(raw.len());
+                for cell in raw {
+                    let value = cell.to_data_type(strings)?;
+                    if !cell_is_empty(&value) {
+                        cells.push((cell.position.1, value));
+                    }
+                }
+                Ok(Some((row, cells)))
+            }
+            #[cfg(feature = "csv")]
+            RowSource::Delimited { records, row } => {
+                let record = match records.next() {
+                    Some(record) => record?,
+                    None => return Ok(None),
+                };
+                let cells = record
+                    .iter()
+                    .enumerate()
+                    .map(|(c, field)| (c as u32, crate::delimited::parse_field(field)))
+                    .filter(|(_, value)| !cell_is_empty(value))
+                    .collect();
+                *row += 1;
+                Ok(Some((*row - 1, cells)))
+            }
+        }
+    }
+}
+
+/// Rows of a sheet read one at a time, from `stream_rows`
+pub struct RowStream {
+    headers: Vec<String>,
+    /// Absolute column of each header
+    columns: Vec<u32>,
+    /// Decimal places each column is rounded to, from `LoadOptions::round`
+    round_places: Vec<Option<u32>>,
+    source: Option<RowSource>,
+    /// Rows read while looking for the header, or every row after a full load
+    buffered: VecDeque<SparseRow>,
+    /// Last absolute row to read, if loading a range
+    last_row: Option<u32>,
+    /// Whether `columns` are all the sheet's columns as far as the header scan saw, so a later
+    /// row with values outside them is an error instead of being cut to a range
+    check_width: bool,
+    options: LoadOptions,
+    rows_read: usize,
+    done: bool,
+}
+
+impl RowStream {
+    /// Get the column headers in sheet order
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Check for a value outside the columns found while scanning for the header, which a
+    /// full load would have given a column of its own
+    fn too_wide(&self, row: u32, cells: &[(u32, DataType)]) -> Option<LoadError> {
+        if !self.check_width {
+            return None;
+        }
+        let (first, last) = (*self.columns.first()?, *self.columns.last()?);
+        let (col, _) = cells
+            .iter()
+            .find(|(c, v)| (*c < first || *c > last) && !cell_is_empty(v))?;
+        Some(LoadError::StreamTooWide {
+            row,
+            column: coords::column_letter(*col),
+        })
+    }
+
+    fn next_sparse(&mut self) -> Result<Option<SparseRow>, LoadError> {
+        if let Some(row) = self.buffered.pop_front() {
+            return Ok(Some(row));
+        }
+        match &mut self.source {
+            Some(source) => source.next_row(),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Iterator for RowStream {
+    type Item = Result<OwnedRow, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if let Err(err) = self.options.check_cancelled() {
+                self.done = true;
+                return Some(Err(err));
+            }
+
+            let (row, cells) = match self.next_sparse() {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            };
+            if self.last_row.is_some_and(|last| row > last) {
+                break;
+            }
+            if let Some(err) = self.too_wide(row, &cells) {
+                self.done = true;
+                return Some(Err(err));
+            }
+
+            let mut any = false;
+            let number_text = self.options.number_text;
+            let values =
+                self.headers
+                    .iter()
+                    .zip(&self.columns)
+                    .zip(&self.round_places)
+                    .map(|((header, col), places)| {
+                        let value = cells.iter().find(|(c, _)| c == col).map_or_else(
+                            String::new,
+                            |(_, v)| {
+                                stringify::policy_text(number_text, *places, v, |n| {
+                                    numfmt::format_with(None, n)
+                                })
+                                .into_owned()
+                            },
+                        );
+                        any |= !value.is_empty();
+                        (header.clone(), value)
+                    })
+                    .collect();
+
+            // Rows without values in any column are skipped, as sparse sheets leave them out
+            if any {
+                self.rows_read += 1;
+                if let Some(progress) = &self.options.progress {
+                    progress.report_row(self.rows_read, None);
+                }
+                return Some(Ok(OwnedRow::new(row, values)));
+            }
+        }
+
+        self.done = true;
+        None
+    }
+}
+
+/// Find the sheet and region `options` select with a defined name or table, if any
+fn named_region(
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<(Option<String>, Option<Span>), LoadError> {
+    let resolved = WorkbookData::resolve_named_region(path, options)?;
+    let options = resolved.as_ref().unwrap_or(options);
+    Ok((options.sheet_name.clone(), options.region()?))
+}
+
+/// Read a sheet one row at a time, keeping memory use bounded for sheets too large to load
+///
+/// The header is found the same way as for `WorkbookData::from_path_with_options`, but only
+/// from the rows at the top of the sheet (up to `max_header_scan_rows`, or 100), or taken from
+/// `header_row` however far down it is. The columns are those with values in the rows scanned:
+/// a header further down fails with `LoadError::NoHeader`, and a later row with a value to the
+/// left or right of them fails with `LoadError::StreamTooWide`, as a full load could find a
+/// different header or more columns. Reading a `range` has neither limit. Rows come back as `OwnedRow`s with values written
+/// like `RowData::get`, with the `round` and `number_text` options applied, except that xlsx
+/// dates are left as serial numbers and `NumberText::Formatted` uses the `General` format,
+/// as number formats are not read. Rows without any values are skipped.
+///
+/// xlsx sheets and delimited files are streamed. The xlsx shared strings table is kept in
+/// memory up to `LoadOptions::memory_limit` and written to a temporary file past it. Other
+/// formats, and options that need the whole sheet (`transposed`, `header_rows`,
+/// `expand_merged_cells`, `fill_down`), fall back to a full load.
+pub fn stream_rows<P: AsRef<Path>>(path: P, options: &LoadOptions) -> Result<RowStream, LoadError> {
+    let path = path.as_ref();
+    let _span = trace::enter_span!("stream", path = %path.display());
+    let filename = path.to_string_lossy().to_string();
+    options.check_cancelled()?;
+
+    let (sheet_name, region) = named_region(path, options)?;
+    let needs_full_load = options.transposed
+        || options.header_rows > 1
+        || options.expand_merged_cells
+        || !options.fill_down.is_empty();
+
+    #[cfg(feature = "csv")]
+    if let (Some(delimiter), false) = (crate::delimited::delimiter(path), needs_full_load) {
+        if let Some(requested) = sheet_name {
+            if requested != crate::delimited::sheet_name(path) {
+                return Err(LoadError::EmptySheet {
+                    filename,
+                    sheet_name: requested,
+                });
+            }
+        }
+
+        let records = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)?
+            .into_records();
+        let source = RowSource::Delimited { records, row: 0 };
+        return RowStream::start(source, region, options).map_err(|err| match err {
+            LoadError::NoHeader => LoadError::Empty { filename },
+            err => err,
+        });
+    }
+
+    if !xlsx::is_xlsx_path(path) || needs_full_load {
+        return RowStream::loaded(path, options);
+    }
+
+    let mut package = XlsxPackage::open(path)?;
+    let sheets: Vec<_> = package
+        .sheets()?
+        .into_iter()
+        .filter(|s| match &sheet_name {
+            Some(name) => s.name == *name,
+            None => !options.skip_hidden_sheets || s.visibility.is_visible(),
+        })
+        .collect();
+
+    let strings_path = package
+        .relationships("xl/workbook.xml")?
+        .into_iter()
+        .find(|r| r.kind.ends_with(SHARED_STRINGS_REL))
+        .map(|r| r.target);
+    let strings = match strings_path {
+        Some(strings_path) => match package.part_reader(&strings_path)? {
+            Some(xml) => SharedStrings::read(xml, usize::MAX, options.memory_limit)?,
+            None => SharedStrings::empty(),
+        },
+        None => SharedStrings::empty(),
+    };
+    let strings = Arc::new(strings);
+
+    // Like a full load, use the first sheet with a header unless one is named
+    for sheet in sheets {
+        options.check_cancelled()?;
+
+        let xml = xlsx::open_part_stream(path, &sheet.path)?.ok_or_else(|| {
+            LoadError::InvalidPackage(format!("missing worksheet part {}", sheet.path))
+        })?;
+        let source = RowSource::Xlsx {
+            sheet: SheetReader::new(BufReader::new(xml)),
+            strings: Arc::clone(&strings),
+        };
+        match RowStream::start(source, region, options) {
+            Err(LoadError::NoHeader) => {
+                trace::debug!(sheet = %sheet.name, "skipping sheet with no header");
+                continue;
+            }
+            result => {
+                trace::debug!(sheet = %sheet.name, "streaming sheet");
+                return result;
+            }
+        }
+    }
+
+    Err(match sheet_name {
+        Some(sheet_name) => LoadError::EmptySheet {
+            filename,
+            sheet_name,
+        },
+        None => LoadError::Empty { filename },
+    })
+}
+
+impl RowStream {
+    /// Find the header in the first rows of `source`, and keep the rows read past it
+    ///
+    /// Fails with `LoadError::NoHeader` if there is no data or no header is found.
+    fn start(
+        mut source: RowSource,
+        region: Option<Span>,
+        options: &LoadOptions,
+    ) -> Result<Self, LoadError> {
+        let scan_rows = options.max_header_scan_rows.unwrap_or(HEADER_SCAN_ROWS);
+        let in_columns =
+            |col: u32| region.is_none_or(|((_, c0), (_, c1))| (c0..=c1).contains(&col));
+
+        // A given header row may lie past the rows scanned from the top, so scan from it
+        let region_start = region.map_or(0, |((r0, _), _)| r0);
+        let first_row = match options.header_row {
+            Some(row) if !options.headerless => row.max(region_start),
+            _ => region_start,
+        };
+
+        let mut buffered = VecDeque::new();
+        while buffered.len() < scan_rows {
+            let (row, cells) = match source.next_row()? {
+                Some(row) => row,
+                None => break,
+            };
+            if row < first_row {
+                continue;
+            }
+            if region.is_some_and(|(_, (r1, _))| row > r1) {
+                break;
+            }
+            let cells: Vec<_> = cells.into_iter().filter(|(c, _)| in_columns(*c)).collect();
+            if !cells.is_empty() {
+                buffered.push_back((row, cells));
+            }
+        }
+
+        let preview = Range::from_sparse(
+            buffered
+                .iter()
+                .flat_map(|(row, cells)| {
+                    cells
+                        .iter()
+                        .map(move |(col, value)| Cell::new((*row, *col), value.clone()))
+                })
+                .collect(),
+        );
+        let ((_, first_col), (_, last_col)) = match (preview.start(), preview.end()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(LoadError::NoHeader),
+        };
+        let (first_col, last_col) = match region {
+            Some(((_, c0), (_, c1))) => (c0, c1),
+            None => (first_col, last_col),
+        };
+        let columns: Vec<u32> = (first_col..=last_col).collect();
+
+        let headers: Vec<String> = if options.headerless {
+            columns.iter().map(|&c| coords::column_letter(c)).collect()
+        } else {
+            let header_row = match (options.header_row, region) {
+                (Some(row), _) => row,
+                // The region's first row with values, as a whole-column region starts at row 1
+                (None, Some(_)) => buffered
+                    .front()
+                    .map(|(row, _)| *row)
+                    .ok_or(LoadError::NoHeader)?,
+                (None, None) => match &options.header_detector {
+                    Some(detector) => detector.detect(&preview),
+                    None => HeuristicDetector::from_options(options).detect(&preview),
+                }
+                .ok_or(LoadError::NoHeader)?,
+            };
+            trace::debug!(header_row, "streaming below header row");
+            let headers = columns
+                .iter()
+                .map(|&c| {
+                    preview
+                        .get_value((header_row, c))
+                        .map_or_else(String::new, |v| cell_to_str(v).into_owned())
+                })
+                .collect();
+            buffered.retain(|(row, _)| *row > header_row);
+            headers
+        };
+
+        let round_places = headers
+            .iter()
+            .map(|header| {
+                // Like `set_rounding`, a later setting for a column replaces an earlier one
+                options
+                    .rounding
+                    .iter()
+                    .rev()
+                    .find(|(column_header, _)| column_header == header)
+                    .map(|(_, places)| *places)
+            })
+            .collect();
+
+        Ok(Self {
+            headers,
+            columns,
+            round_places,
+            source: Some(source),
+            buffered,
+            last_row: region.map(|(_, (r1, _))| r1),
+            check_width: region.is_none(),
+            options: options.clone(),
+            rows_read: 0,
+            done: false,
+        })
+    }
+
+    /// Load the whole sheet and hand out its rows, for formats and options that cannot stream
+    fn loaded(path: &Path, options: &LoadOptions) -> Result<Self, LoadError> {
+        let data = WorkbookData::from_path_with_options(path, options)?;
+        let headers: Vec<String> = data.headers().map(String::from).collect();
+        let columns = (0..headers.len() as u32).collect();
+        // Values are already written with the loaded columns' rounding and number text
+        let round_places = vec![None; headers.len()];
+
+        let buffered = data
+            .rows()
+            .map(|row| {
+                let cells = (0..headers.len() as u32)
+                    .filter_map(|i| Some((i, DataType::String(row.get_index(i)?.into_owned()))))
+                    .collect();
+                (row.number(), cells)
+            })
+            .collect();
+
+        Ok(Self {
+            headers,
+            columns,
+            round_places,
+            source: None,
+            buffered,
+            last_row: None,
+            check_width: false,
+            options: options.clone(),
+            rows_read: 0,
+            done: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestXlsx;
+    use crate::NumberText;
+
+    fn collect(stream: RowStream) -> Vec<(u32, Vec<String>)> {
+        stream
+            .map(|row| {
+                let row = row.unwrap();
+                let values = row.cells().iter().map(|(_, v)| v.clone()).collect();
+                (row.number(), values)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spills_shared_strings_past_the_memory_limit() {
+        let xml = br#"<sst><si><t>alpha</t></si><si><t>beta</t></si><si><r><t>gam</t></r><r><t>ma</t></r></si><si><t>delta</t></si></sst>"#;
+        let strings = SharedStrings::read(&xml[..], usize::MAX, Some(5)).unwrap();
+        assert_eq!(strings.memory, ["alpha"]);
+        assert!(strings.spill.is_some());
+
+        let read: Vec<_> = (0..5).map(|i| strings.get(i).unwrap()).collect();
+        let expected = ["alpha", "beta", "gamma", "delta"].map(|s| Some(s.to_owned()));
+        assert_eq!(read[..4], expected);
+        assert_eq!(read[4], None);
+    }
+
+    #[test]
+    fn streams_with_spilled_strings() {
+        let path = TestXlsx::new()
+            .sheet(
+                "Data",
+                &[&["Name", "City"], &["Ann", "Oslo"], &["Bob", "Lima"]],
+            )
+            .write();
+        let options = LoadOptions::new().memory_limit(1);
+        let stream = stream_rows(&path, &options).unwrap();
+        assert_eq!(stream.headers(), ["Name", "City"]);
+        assert_eq!(
+            collect(stream),
+            [
+                (1, vec!["Ann".into(), "Oslo".into()]),
+                (2, vec!["Bob".into(), "Lima".into()])
+            ]
+        );
+    }
+
+    #[test]
+    fn places_rows_and_cells_without_references() {
+        let path = TestXlsx::new()
+            .sheet(
+                "Data",
+                &[
+                    &["Name", "Note", "Age"],
+                    &["Ann", "", "30"],
+                    &["", "", "41"],
+                ],
+            )
+            .without_refs()
+            .write();
+        let stream = stream_rows(&path, &LoadOptions::new()).unwrap();
+        assert_eq!(stream.headers(), ["Name", "Note", "Age"]);
+        assert_eq!(
+            collect(stream),
+            [
+                (1, vec!["Ann".into(), String::new(), "30".into()]),
+                (2, vec![String::new(), String::new(), "41".into()])
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_the_header_like_a_full_load() {
+        let path = TestXlsx::new()
+            .sheet(
+                "Data",
+                &[
+                    &["Sales report", "", ""],
+                    &["", "", ""],
+                    &["Region", "Month", "Total"],
+                    &["North", "Jan", "10"],
+                ],
+            )
+            .write();
+        let data = WorkbookData::from_path(&path).unwrap();
+        let stream = stream_rows(&path, &LoadOptions::new()).unwrap();
+
+        assert_eq!(stream.headers(), data.headers().collect::<Vec<_>>());
+        assert_eq!(
+            collect(stream),
+            [(3, vec!["North".into(), "Jan".into(), "10".into()])]
+        );
+    }
+
+    #[test]
+    fn fails_on_values_past_the_scanned_columns() {
+        let path = TestXlsx::new()
+            .sheet(
+                "Data",
+                &[&["Name", "Age"], &["Ann", "30"], &["Bob", "41", "late"]],
+            )
+            .write();
+        let options = LoadOptions::new().max_header_scan_rows(2);
+        let rows: Vec<_> = stream_rows(&path, &options).unwrap().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(matches!(
+            &rows[1],
+            Err(LoadError::StreamTooWide { row: 2, column }) if column == "C"
+        ));
+
+        // A range picks the columns, so values outside it are left out
+        let stream = stream_rows(&path, &options.range("A:B")).unwrap();
+        assert_eq!(collect(stream).len(), 2);
+    }
+
+    #[test]
+    fn reads_a_header_row_past_the_scanned_rows() {
+        let mut rows: Vec<&[&str]> = vec![&["note"]; 5];
+        rows.push(&["Name", "Age"]);
+        rows.push(&["Ann", "30"]);
+        let path = TestXlsx::new().sheet("Data", &rows).write();
+
+        let options = LoadOptions::new().header_row(5).max_header_scan_rows(2);
+        let stream = stream_rows(&path, &options).unwrap();
+        assert_eq!(stream.headers(), ["Name", "Age"]);
+        assert_eq!(collect(stream), [(6, vec!["Ann".into(), "30".into()])]);
+    }
+
+    #[test]
+    fn applies_rounding_and_number_text() {
+        let path = TestXlsx::new()
+            .sheet("Data", &[&["Price", "Qty"], &["2.345", "3"]])
+            .write();
+        let options = LoadOptions::new()
+            .round("Price", 2)
+            .number_text(NumberText::Fixed(1));
+        let stream = stream_rows(&path, &options).unwrap();
+        assert_eq!(collect(stream), [(1, vec!["2.4".into(), "3.0".into()])]);
+    }
+
+    #[test]
+    fn falls_back_to_a_full_load() {
+        let path = TestXlsx::new()
+            .sheet("Data", &[&["Q1", ""], &["Sales", "Cost"], &["10", "4"]])
+            .write();
+        let options = LoadOptions::new().header_row(1).header_rows(2);
+        let stream = stream_rows(&path, &options).unwrap();
+        assert!(stream.source.is_none());
+        assert_eq!(stream.headers(), ["Q1 / Sales", "Q1 / Cost"]);
+        assert_eq!(collect(stream), [(2, vec!["10".into(), "4".into()])]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn streams_delimited_files() {
+        use crate::testing::TempPath;
+
+        let path = TempPath::with_text("csv", "Name,Age\nAnn,30\n,\nBob,41\n");
+        let stream = stream_rows(&path, &LoadOptions::new()).unwrap();
+        assert!(matches!(stream.source, Some(RowSource::Delimited { .. })));
+        assert_eq!(
+            collect(stream),
+            [
+                (1, vec!["Ann".into(), "30".into()]),
+                (3, vec!["Bob".into(), "41".into()])
+            ]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! How numeric cells are turned into text, so a value compares the same wherever it is read
+
+use crate::rounding;
+use crate::{cell_to_str, Column, DataType, WorkbookData};
+use std::borrow::Cow;
+
+/// Significant digits Excel keeps; digits past these are floating-point noise
+const SIGNIFICANT_DIGITS: usize = 15;
+
+/// How numbers are written by `get`, `Display`, exports and the values in error messages
+///
+/// Typed reads like `RowData::parse` and `get_f64` see the exact value under every policy.
+/// Column rounding is applied first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberText {
+    /// The shortest text that reads back as the same number, like `0.30000000000000004`
+    #[default]
+    Shortest,
+    /// Whole numbers without a decimal point and others to the 15 significant digits Excel
+    /// keeps, so a cell showing `1` or `0.3` reads as `"1"` or `"0.3"` however it is stored
+    IntegerPreserving,
+    /// This many decimal places, for integers too, like `"1.00"`
+    Fixed(u32),
+    /// As the spreadsheet displays the cell, as `get_formatted` does; load with
+    /// `LoadOptions::number_formats` to use the cells' own formats
+    Formatted,
+}
+
+/// Write a number without noise past Excel's precision, and whole numbers as integers
+fn integer_preserving(f: f64) -> String {
+    if !f.is_finite() {
+        return f.to_string();
+    }
+
+    let text = format!("{:.*e}", SIGNIFICANT_DIGITS - 1, f);
+    let n: f64 = text.parse().unwrap_or(f);
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        (n as i64).to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+fn fixed(value: &DataType, places: u32) -> Option<String> {
+    match value {
+        DataType::Int(i) if places == 0 => Some(i.to_string()),
+        DataType::Int(i) => Some(format!("{}.{}", i, "0".repeat(places as usize))),
+        DataType::Float(f) if f.is_finite() => Some(rounding::format_fixed(*f, places as usize)),
+        _ => None,
+    }
+}
+
+/// Write a cell as text, rounded to `round_places` and then under a number text policy
+///
+/// `format` shows a number as the spreadsheet displays it, for `NumberText::Formatted`.
+pub(crate) fn policy_text<'v>(
+    policy: NumberText,
+    round_places: Option<u32>,
+    value: &'v DataType,
+    format: impl FnOnce(&DataType) -> String,
+) -> Cow<'v, str> {
+    let rounded = rounding::round_cell(round_places, value);
+    let number = rounded.as_ref().unwrap_or(value);
+
+    let text = match (policy, number) {
+        (NumberText::Shortest, _) => None,
+        (NumberText::IntegerPreserving, DataType::Float(f)) => Some(integer_preserving(*f)),
+        (NumberText::IntegerPreserving, _) => None,
+        (NumberText::Fixed(places), number) => fixed(number, places),
+        (NumberText::Formatted, DataType::Int(_) | DataType::Float(_) | DataType::DateTime(_)) => {
+            Some(format(number))
+        }
+        (NumberText::Formatted, _) => None,
+    };
+
+    match (text, rounded) {
+        (Some(text), _) => Cow::Owned(text),
+        (None, Some(rounded)) => Cow::Owned(rounded.to_string()),
+        (None, None) => cell_to_str(value),
+    }
+}
+
+impl WorkbookData {
+    /// Set how numbers are written as text; see `NumberText`
+    pub fn set_number_text(&mut self, policy: NumberText) {
+        self.number_text = policy;
+    }
+
+    pub fn number_text(&self) -> NumberText {
+        self.number_text
+    }
+
+    /// Get a cell of a column as text, with the column's rounding and the number text policy
+    pub(crate) fn cell_text<'v>(
+        &self,
+        row_number: u32,
+        column: &Column,
+        value: &'v DataType,
+    ) -> Cow<'v, str> {
+        policy_text(self.number_text, column.round_places, value, |number| {
+            self.format_cell(row_number, column.index, number)
+        })
+    }
+
+    /// Get a cell as text for an error message about the named column
+    pub(crate) fn error_text(
+        &self,
+        row_number: u32,
+        column_header: &str,
+        value: &DataType,
+    ) -> String {
+        match self.column_id(column_header) {
+            Some(id) => self
+                .cell_text(row_number, &self.columns[id.0], value)
+                .into_owned(),
+            None => cell_to_str(value).into_owned(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{cell_is_empty, DataError, WorkbookData};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Number of most frequent values listed in a `ColumnStats` message
+const MAX_LISTED_VALUES: usize = 5;
+
+/// Range and average of a column whose values are all numbers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub sum: f64,
+}
+
+/// Summary of one column's values, from `WorkbookData::column_stats`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub column: String,
+    /// Where the column is in the sheet, like `"Column AJ"`
+    pub position: String,
+    /// Number of non-empty values
+    pub count: usize,
+    /// Number of empty cells
+    pub empty: usize,
+    /// Number of different non-empty values
+    pub distinct: usize,
+    /// Set if every non-empty value is a number (or a number stored as text)
+    pub numeric: Option<NumericStats>,
+    /// For non-numeric columns, each distinct value with its count, most frequent first
+    pub value_counts: Vec<(String, usize)>,
+}
+
+impl fmt::Display for ColumnStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} / '{}': {} values, {} empty, {} distinct",
+            self.position, self.column, self.count, self.empty, self.distinct
+        )?;
+
+        if let Some(n) = &self.numeric {
+            write!(f, ", min {}, max {}, mean {}", n.min, n.max, n.mean)?;
+        } else if !self.value_counts.is_empty() {
+            let top: Vec<String> = self
+                .value_counts
+                .iter()
+                .take(MAX_LISTED_VALUES)
+                .map(|(value, count)| format!("'{}' x{}", value, count))
+                .collect();
+            write!(f, ", most common {}", top.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl WorkbookData {
+    /// Summarize a column: value counts, and min/max/mean if every value is a number
+    ///
+    /// Values are read as `get` returns them, so column rounding applies.
+    pub fn column_stats(&self, column_header: &str) -> Result<ColumnStats, DataError> {
+        let id = self
+            .column_id(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))?;
+        let column = &self.columns[id.0];
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut numbers = Vec::new();
+        let mut all_numeric = true;
+        let mut empty = 0;
+
+        for row in self.first_row..=self.last_row {
+            let value = match self.range.get_value((row, column.index)) {
+                Some(value) if !cell_is_empty(value) => self.cell_text(row, column, value),
+                _ => {
+                    empty += 1;
+                    continue;
+                }
+            };
+
+            if all_numeric {
+                match value.trim().parse::<f64>() {
+                    Ok(n) => numbers.push(n),
+                    Err(_) => all_numeric = false,
+                }
+            }
+
+            *counts.entry(value.into_owned()).or_default() += 1;
+        }
+
+        let count = counts.values().sum();
+
+        let numeric = if all_numeric && !numbers.is_empty() {
+            let sum: f64 = numbers.iter().sum();
+            Some(NumericStats {
+                min: numbers.iter().copied().fold(f64::INFINITY, f64::min),
+                max: numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                mean: sum / numbers.len() as f64,
+                sum,
+            })
+        } else {
+            None
+        };
+
+        let distinct = counts.len();
+
+        let value_counts = if numeric.is_some() {
+            Vec::new()
+        } else {
+            let mut value_counts: Vec<(String, usize)> = counts.into_iter().collect();
+            value_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            value_counts
+        };
+
+        Ok(ColumnStats {
+            column: column_header.into(),
+            position: self.column_position(column_header),
+            count,
+            empty,
+            distinct,
+            numeric,
+            value_counts,
+        })
+    }
+}
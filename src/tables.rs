@@ -0,0 +1,174 @@
+//! Excel Tables (list objects), the structured ranges users create with Format as Table
+
+use crate::coords::{self, Span};
+use crate::xlsx::{self, XlsxPackage};
+use crate::{trace, LoadError, LoadOptions, WorkbookData};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// Relationship type of a sheet's table parts
+const TABLE_REL: &str = "/table";
+
+/// An Excel Table declared on a sheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcelTable {
+    /// The name shown in Excel and used in structured references, like `tblOrders`
+    pub name: String,
+    pub sheet_name: String,
+    /// Absolute extent, including the header and totals rows
+    pub region: Span,
+    /// 1 for a table with a header row, 0 without one
+    pub header_rows: u32,
+    pub totals_rows: u32,
+    /// Column names as declared by the table, which match its header cells
+    pub columns: Vec<String>,
+}
+
+impl ExcelTable {
+    /// The header and data rows, without any totals rows
+    pub(crate) fn data_region(&self) -> Span {
+        let ((top, left), (bottom, right)) = self.region;
+        let bottom = bottom.saturating_sub(self.totals_rows).max(top);
+        ((top, left), (bottom, right))
+    }
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// List the tables of every sheet, in sheet order
+    pub fn tables(&mut self) -> Result<Vec<ExcelTable>, LoadError> {
+        Ok(self
+            .table_parts()?
+            .into_iter()
+            .map(|(_, table)| table)
+            .collect())
+    }
+
+    /// List the tables of every sheet with the package paths of their parts
+    pub(crate) fn table_parts(&mut self) -> Result<Vec<(String, ExcelTable)>, LoadError> {
+        let mut tables = Vec::new();
+
+        for sheet in self.sheets()? {
+            let parts: Vec<String> = self
+                .relationships(&sheet.path)?
+                .into_iter()
+                .filter(|r| r.kind.ends_with(TABLE_REL))
+                .map(|r| r.target)
+                .collect();
+
+            for part in parts {
+                let xml = self.read_part(&part)?.unwrap_or_default();
+                if let Some(table) = parse_table(&xml, &sheet.name)? {
+                    tables.push((part, table));
+                }
+            }
+        }
+
+        Ok(tables)
+    }
+}
+
+/// Read a table part, skipping tables with no valid `ref`
+fn parse_table(xml: &[u8], sheet_name: &str) -> Result<Option<ExcelTable>, LoadError> {
+    let mut table = None;
+    let mut columns = Vec::new();
+
+    xlsx::for_each_element(xml, |e| match e.local_name() {
+        b"table" => {
+            let region = xlsx::attr(e, b"ref")
+                .as_deref()
+                .and_then(coords::parse_range);
+            let name = xlsx::attr(e, b"displayName").or_else(|| xlsx::attr(e, b"name"));
+            let count = |key: &[u8], default| {
+                xlsx::attr(e, key)
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(default)
+            };
+
+            if let (Some(region), Some(name)) = (region, name) {
+                table = Some(ExcelTable {
+                    name,
+                    sheet_name: sheet_name.to_owned(),
+                    region,
+                    header_rows: count(b"headerRowCount", 1),
+                    totals_rows: count(b"totalsRowCount", 0),
+                    columns: Vec::new(),
+                });
+            }
+        }
+        b"tableColumn" => columns.push(xlsx::attr(e, b"name").unwrap_or_default()),
+        _ => {}
+    })?;
+
+    Ok(table.map(|table| ExcelTable { columns, ..table }))
+}
+
+/// List the Excel Tables of a workbook
+///
+/// Only xlsx files declare tables; other formats have none.
+pub fn list_tables<P: AsRef<Path>>(path: P) -> Result<Vec<ExcelTable>, LoadError> {
+    let path = path.as_ref();
+
+    if !xlsx::is_xlsx_path(path) {
+        return Ok(Vec::new());
+    }
+    XlsxPackage::open(path)?.tables()
+}
+
+/// Find a table by name, case-insensitively like Excel (table names are unique in a workbook)
+pub(crate) fn find_table(path: &Path, table_name: &str) -> Result<ExcelTable, LoadError> {
+    let filename = path.to_string_lossy();
+    find_table_in(list_tables(path)?, &filename, table_name)
+}
+
+/// Find a table among those already read from a workbook
+pub(crate) fn find_table_in(
+    tables: Vec<ExcelTable>,
+    filename: &str,
+    table_name: &str,
+) -> Result<ExcelTable, LoadError> {
+    let table = tables
+        .into_iter()
+        .find(|t| t.name.eq_ignore_ascii_case(table_name))
+        .ok_or_else(|| LoadError::UnknownTable {
+            filename: filename.to_owned(),
+            table_name: table_name.to_owned(),
+        })?;
+
+    if table.header_rows == 0 {
+        return Err(LoadError::NoHeader);
+    }
+    Ok(table)
+}
+
+impl WorkbookData {
+    /// Load an Excel Table by name, using its header row and extent
+    pub fn from_path_with_table<P: AsRef<Path>>(
+        path: P,
+        table_name: &str,
+    ) -> Result<Self, LoadError> {
+        Self::from_path_with_options(path, &LoadOptions::new().table(table_name))
+    }
+
+    /// Load with `options.table` resolved to a sheet name and range
+    /// Get `options` with its table replaced by the table's sheet name and data range
+    pub(crate) fn table_options(
+        path: &Path,
+        table_name: &str,
+        options: &LoadOptions,
+    ) -> Result<LoadOptions, LoadError> {
+        let table = find_table(path, table_name)?;
+        trace::debug!(
+            table = %table.name,
+            sheet = %table.sheet_name,
+            range = %coords::format_range(table.region),
+            "found table"
+        );
+
+        let mut options = options
+            .clone()
+            .sheet_name(&table.sheet_name)
+            .range(&coords::format_range(table.data_region()));
+        options.table = None;
+        Ok(options)
+    }
+}
@@ -0,0 +1,349 @@
+//! Filling a table or named region of a styled template workbook with rows
+
+use crate::coords::{self, Span};
+use crate::xlsx::{self, XlsxPackage};
+use crate::{names, DataType, LoadError, LoadOptions, WorkbookData, WriteError, WritePlan};
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// What a template region name refers to
+enum Region {
+    /// An Excel Table, with the package path of its part
+    Table { part: String, extent: Span },
+    /// A defined name, with the sheet it is on
+    Name { sheet_name: String, extent: Span },
+}
+
+/// Write a copy of a template workbook with a table or defined name filled with rows
+///
+/// `region` names an Excel Table or a defined name whose first row is the header. Rows are
+/// `(header, value)` pairs, as for `append_rows`, written from the first row below the header.
+/// Each cell takes the style of the template's first data row in its column, and the
+/// template's example values are cleared. The table or name is resized to the rows written, so
+/// formulas that refer to it, like `=SUM(Sales[Amount])`, cover the new rows.
+///
+/// Rows are written over whatever is below the region, and tables with a totals row are not
+/// supported. Returns the number of rows written.
+pub fn fill_template<P, Q, I, R, H, V>(
+    template: P,
+    output: Q,
+    region: &str,
+    rows: I,
+) -> Result<usize, WriteError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = (H, V)>,
+    H: AsRef<str>,
+    V: Into<DataType>,
+{
+    let plan = fill(template.as_ref(), output.as_ref(), region, rows, false)?;
+    Ok(plan.rows())
+}
+
+/// Do a dry run of `fill_template`, filling the region without writing `output`
+pub fn plan_fill_template<P, Q, I, R, H, V>(
+    template: P,
+    output: Q,
+    region: &str,
+    rows: I,
+) -> Result<WritePlan, WriteError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = (H, V)>,
+    H: AsRef<str>,
+    V: Into<DataType>,
+{
+    fill(template.as_ref(), output.as_ref(), region, rows, true)
+}
+
+fn fill<I, R, H, V>(
+    template: &Path,
+    output: &Path,
+    region: &str,
+    rows: I,
+    dry_run: bool,
+) -> Result<WritePlan, WriteError>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = (H, V)>,
+    H: AsRef<str>,
+    V: Into<DataType>,
+{
+    if !xlsx::is_xlsx_path(template) {
+        return Err(WriteError::NotEditable(
+            "templates must be xlsx files".into(),
+        ));
+    }
+
+    let mut package = XlsxPackage::open(template)?;
+    let (found, options) = find_region(&mut package, template, region)?;
+    let data = WorkbookData::from_path_with_options(template, &options)?;
+    let sheet_name = data.sheet_name().unwrap_or_default().to_owned();
+
+    let styles = match package.sheet_xml(&sheet_name)? {
+        Some(xml) => row_styles(&xml, data.first_row)?,
+        None => HashMap::new(),
+    };
+
+    let mut editor = data.edit()?;
+
+    // Clear the template's example rows; cells written below replace these edits
+    let headers: Vec<&str> = data.headers().collect();
+    for row_number in data.first_row..=data.last_row {
+        for column_header in &headers {
+            editor.set(row_number, column_header, DataType::Empty)?;
+        }
+    }
+
+    editor.append_from(data.first_row, styles);
+    let mut count = 0;
+    for row in rows {
+        editor.append(row)?;
+        count += 1;
+    }
+
+    // A table needs at least one data row, even if it is left empty
+    let last_row = data.first_row + (count as u32).max(1) - 1;
+    let mut replaced = HashMap::new();
+    match found {
+        Region::Table { part, extent } => {
+            let xml = package.read_part(&part)?.unwrap_or_default();
+            let extent = (extent.0, (last_row, (extent.1).1));
+            replaced.insert(part, Some(resize_table(&xml, extent)?));
+        }
+        Region::Name { sheet_name, extent } => {
+            let xml = package
+                .read_part("xl/workbook.xml")?
+                .ok_or_else(|| LoadError::InvalidPackage("missing xl/workbook.xml".into()))?;
+            let extent = (extent.0, (last_row, (extent.1).1));
+            let reference = sheet_reference(&sheet_name, extent);
+            let xml = set_defined_name(&xml, region, &reference)?;
+            replaced.insert("xl/workbook.xml".to_owned(), Some(xml));
+        }
+    }
+
+    let mut plan = editor.save_with_parts(output, replaced, dry_run)?;
+    plan.sheets[0].rows = count;
+    Ok(plan)
+}
+
+/// Find a table by name, or else a defined name, with the options to load it
+fn find_region(
+    package: &mut XlsxPackage,
+    path: &Path,
+    region: &str,
+) -> Result<(Region, LoadOptions), WriteError> {
+    let table = package
+        .table_parts()?
+        .into_iter()
+        .find(|(_, t)| t.name.eq_ignore_ascii_case(region));
+
+    if let Some((part, table)) = table {
+        if table.totals_rows > 0 {
+            return Err(WriteError::NotEditable(format!(
+                "table '{}' has a totals row",
+                table.name
+            )));
+        }
+        let options = LoadOptions::new().table(&table.name);
+        return Ok((
+            Region::Table {
+                part,
+                extent: table.region,
+            },
+            options,
+        ));
+    }
+
+    match names::resolve(path, region) {
+        Ok((sheet_name, extent)) => Ok((
+            Region::Name { sheet_name, extent },
+            LoadOptions::new().defined_name(region),
+        )),
+        Err(LoadError::UnknownDefinedName { .. }) => {
+            Err(WriteError::UnknownRegion(region.to_owned()))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Read the style of each cell in a row of a sheet, by column number
+fn row_styles(xml: &[u8], row: u32) -> Result<HashMap<u32, String>, LoadError> {
+    let mut styles = HashMap::new();
+
+    xlsx::for_each_element(xml, |e| {
+        if e.local_name() != b"c" {
+            return;
+        }
+        let position = xlsx::attr(e, b"r").as_deref().and_then(coords::parse_cell);
+        if let (Some((r, col)), Some(style)) = (position, xlsx::attr(e, b"s")) {
+            if r == row {
+                styles.insert(col, style);
+            }
+        }
+    })?;
+    Ok(styles)
+}
+
+/// Format an absolute reference to a range on a sheet, like `'Q1 Sales'!$A$1:$C$9`
+fn sheet_reference(sheet_name: &str, ((r0, c0), (r1, c1)): Span) -> String {
+    format!(
+        "'{}'!${}${}:${}${}",
+        sheet_name.replace('\'', "''"),
+        coords::column_letter(c0),
+        u64::from(r0) + 1,
+        coords::column_letter(c1),
+        u64::from(r1) + 1
+    )
+}
+
+/// Set the extent of a table and its filter
+fn resize_table(xml: &[u8], extent: Span) -> Result<Vec<u8>, LoadError> {
+    let reference = coords::format_range(extent);
+    let mut reader = Reader::from_reader(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        match reader.read_event(&mut buf)? {
+            Event::Start(ref e) if is_table_extent(e) => {
+                writer.write_event(Event::Start(with_ref(e, &reference)))?;
+            }
+            Event::Empty(ref e) if is_table_extent(e) => {
+                writer.write_event(Event::Empty(with_ref(e, &reference)))?;
+            }
+            Event::Eof => return Ok(writer.into_inner()),
+            event => writer.write_event(event)?,
+        }
+    }
+}
+
+fn is_table_extent(e: &BytesStart) -> bool {
+    matches!(e.local_name(), b"table" | b"autoFilter")
+}
+
+/// Copy an element with its `ref` attribute replaced
+fn with_ref(e: &BytesStart, reference: &str) -> BytesStart<'static> {
+    let mut element = BytesStart::owned_name(e.name().to_vec());
+    for attribute in e.attributes().flatten() {
+        if attribute.key == b"ref" {
+            element.push_attribute(("ref", reference));
+        } else {
+            element.push_attribute(attribute);
+        }
+    }
+    element
+}
+
+/// Set what a defined name refers to in the workbook part, matching names case-insensitively
+fn set_defined_name(xml: &[u8], name: &str, reference: &str) -> Result<Vec<u8>, LoadError> {
+    let mut reader = Reader::from_reader(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut buf = Vec::new();
+    let mut in_name = false;
+
+    loop {
+        buf.clear();
+        match reader.read_event(&mut buf)? {
+            Event::Start(ref e) if e.local_name() == b"definedName" => {
+                in_name = xlsx::attr(e, b"name").is_some_and(|n| n.eq_ignore_ascii_case(name));
+                writer.write_event(Event::Start(e.to_owned()))?;
+            }
+            Event::Text(_) if in_name => {}
+            Event::End(ref e) if e.local_name() == b"definedName" => {
+                if in_name {
+                    writer.write_event(Event::Text(BytesText::from_plain_str(reference)))?;
+                }
+                in_name = false;
+                writer.write_event(Event::End(e.to_owned()))?;
+            }
+            Event::Eof => return Ok(writer.into_inner()),
+            event => writer.write_event(event)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempPath, TestXlsx};
+
+    fn rows() -> Vec<Vec<(&'static str, &'static str)>> {
+        vec![
+            vec![("Item", "Pen"), ("Qty", "3")],
+            vec![("Item", "Ink"), ("Qty", "1")],
+            vec![("Qty", "7"), ("Item", "Pad")],
+        ]
+    }
+
+    fn template() -> TempPath {
+        TestXlsx::new()
+            .sheet("Report", &[&["Item", "Qty"], &["example", "0"]])
+            .table("Sales", "A1:B2")
+            .defined_name("Lines", "Report!$A$1:$B$2")
+            .write()
+    }
+
+    fn items(data: &WorkbookData) -> Vec<String> {
+        data.rows()
+            .map(|row| format!("{}={}", row.get("Item").unwrap(), row.get("Qty").unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn fills_and_resizes_a_table() {
+        let (template, output) = (template(), TempPath::new("xlsx"));
+        assert_eq!(
+            fill_template(&template, &output, "sales", rows()).unwrap(),
+            3
+        );
+
+        let data = WorkbookData::from_path_with_table(&output, "Sales").unwrap();
+        assert_eq!(items(&data), ["Pen=3", "Ink=1", "Pad=7"]);
+        let table = &crate::list_tables(&output).unwrap()[0];
+        assert_eq!(coords::format_range(table.region), "A1:B4");
+    }
+
+    #[test]
+    fn fills_and_resizes_a_defined_name() {
+        let (template, output) = (template(), TempPath::new("xlsx"));
+        let filled = fill_template(&template, &output, "Lines", rows().into_iter().take(2));
+        assert_eq!(filled.unwrap(), 2);
+
+        let options = LoadOptions::new().defined_name("Lines");
+        let data = WorkbookData::from_path_with_options(&output, &options).unwrap();
+        assert_eq!(items(&data), ["Pen=3", "Ink=1"]);
+    }
+
+    #[test]
+    fn rejects_unknown_regions_and_columns() {
+        let (template, output) = (template(), TempPath::new("xlsx"));
+        assert!(matches!(
+            fill_template(&template, &output, "Missing", rows()),
+            Err(WriteError::UnknownRegion(_))
+        ));
+        let bad = vec![vec![("Colour", "red")]];
+        assert!(fill_template(&template, &output, "Sales", bad).is_err());
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn plans_a_fill_without_writing() {
+        let (template, output) = (template(), TempPath::new("xlsx"));
+
+        let plan = plan_fill_template(&template, &output, "Sales", rows()).unwrap();
+        assert_eq!(plan.path.as_deref(), Some(output.as_ref()));
+        assert_eq!((plan.sheets[0].name.as_str(), plan.rows()), ("Report", 3));
+        assert!(!output.exists());
+
+        let bad = vec![vec![("Colour", "red")]];
+        assert!(plan_fill_template(&template, &output, "Sales", bad).is_err());
+    }
+}
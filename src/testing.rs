@@ -0,0 +1,263 @@
+//! Builders for the workbooks and files unit tests load
+
+use crate::coords;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use zip::write::{FileOptions, ZipWriter};
+
+/// A minimal xlsx package, with strings in a shared strings table as Excel writes them
+///
+/// Cells that parse as numbers are written as numbers, and empty cells are left out.
+#[derive(Default)]
+pub(crate) struct TestXlsx {
+    sheets: Vec<(String, Vec<Vec<String>>)>,
+    defined_names: Vec<(String, String)>,
+    tables: Vec<(String, String, String)>,
+    without_refs: bool,
+}
+
+impl TestXlsx {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn sheet(mut self, name: &str, rows: &[&[&str]]) -> Self {
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|c| c.to_string()).collect())
+            .collect();
+        self.sheets.push((name.to_owned(), rows));
+        self
+    }
+
+    /// Leave out the `r` attributes of rows and cells, which then follow the previous one
+    pub(crate) fn without_refs(mut self) -> Self {
+        self.without_refs = true;
+        self
+    }
+
+    pub(crate) fn defined_name(mut self, name: &str, reference: &str) -> Self {
+        self.defined_names
+            .push((name.to_owned(), reference.to_owned()));
+        self
+    }
+
+    /// Add a table on the sheet added last, with a header row and no totals row
+    pub(crate) fn table(mut self, name: &str, reference: &str) -> Self {
+        let sheet = self.sheets.last().expect("a sheet for the table").0.clone();
+        self.tables
+            .push((sheet, name.to_owned(), reference.to_owned()));
+        self
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut strings: Vec<&str> = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let part = |zip: &mut ZipWriter<_>, name: &str, xml: &str| {
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        };
+
+        let mut types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/sharedStrings.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml"/>"#,
+        );
+        let mut workbook = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut workbook_rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+
+        for (i, (name, rows)) in self.sheets.iter().enumerate() {
+            let n = i + 1;
+            types.push_str(&format!(
+                r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                n
+            ));
+            workbook.push_str(&format!(
+                r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+                escape(name),
+                n,
+                n
+            ));
+            workbook_rels.push_str(&format!(
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+                n, n
+            ));
+
+            let mut xml = String::from(
+                r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetData>"#,
+            );
+            for (r, row) in rows.iter().enumerate() {
+                match self.without_refs {
+                    true => xml.push_str("<row>"),
+                    false => xml.push_str(&format!(r#"<row r="{}">"#, r + 1)),
+                }
+                for (c, value) in row.iter().enumerate() {
+                    let reference = match self.without_refs {
+                        true => String::new(),
+                        false => format!(r#" r="{}""#, coords::format_cell((r as u32, c as u32))),
+                    };
+                    if value.is_empty() {
+                        // Cells without a reference are placed by position, so keep blanks
+                        if self.without_refs {
+                            xml.push_str("<c/>");
+                        }
+                    } else if value.parse::<f64>().is_ok() {
+                        xml.push_str(&format!("<c{}><v>{}</v></c>", reference, value));
+                    } else {
+                        let index = strings.iter().position(|s| s == value).unwrap_or_else(|| {
+                            strings.push(value);
+                            strings.len() - 1
+                        });
+                        xml.push_str(&format!(r#"<c{} t="s"><v>{}</v></c>"#, reference, index));
+                    }
+                }
+                xml.push_str("</row>");
+            }
+            xml.push_str("</sheetData>");
+
+            let tables: Vec<_> = self.tables.iter().filter(|(s, _, _)| s == name).collect();
+            if !tables.is_empty() {
+                xml.push_str(r#"<tableParts>"#);
+                let mut rels = String::from(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                );
+                for (t, (_, table_name, reference)) in tables.iter().enumerate() {
+                    let id = format!("{}_{}", n, t + 1);
+                    xml.push_str(&format!(r#"<tablePart r:id="rIdT{}"/>"#, t + 1));
+                    rels.push_str(&format!(
+                        r#"<Relationship Id="rIdT{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table{}.xml"/>"#,
+                        t + 1,
+                        id
+                    ));
+                    types.push_str(&format!(
+                        r#"<Override PartName="/xl/tables/table{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.table+xml"/>"#,
+                        id
+                    ));
+                    part(
+                        &mut zip,
+                        &format!("xl/tables/table{}.xml", id),
+                        &format!(
+                            r#"<?xml version="1.0" encoding="UTF-8"?><table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="{}" name="{}" displayName="{}" ref="{}" headerRowCount="1" totalsRowCount="0"/>"#,
+                            n * 100 + t,
+                            escape(table_name),
+                            escape(table_name),
+                            reference
+                        ),
+                    );
+                }
+                rels.push_str("</Relationships>");
+                xml.push_str("</tableParts>");
+                part(
+                    &mut zip,
+                    &format!("xl/worksheets/_rels/sheet{}.xml.rels", n),
+                    &rels,
+                );
+            }
+
+            xml.push_str("</worksheet>");
+            part(&mut zip, &format!("xl/worksheets/sheet{}.xml", n), &xml);
+        }
+
+        workbook.push_str("</sheets>");
+        if !self.defined_names.is_empty() {
+            workbook.push_str("<definedNames>");
+            for (name, reference) in &self.defined_names {
+                workbook.push_str(&format!(
+                    r#"<definedName name="{}">{}</definedName>"#,
+                    escape(name),
+                    escape(reference)
+                ));
+            }
+            workbook.push_str("</definedNames>");
+        }
+        workbook.push_str("</workbook>");
+        workbook_rels.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings" Target="sharedStrings.xml"/></Relationships>"#,
+            self.sheets.len() + 1
+        ));
+        types.push_str("</Types>");
+
+        let mut shared = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="{}" uniqueCount="{}">"#,
+            strings.len(),
+            strings.len()
+        );
+        for s in &strings {
+            shared.push_str(&format!("<si><t>{}</t></si>", escape(s)));
+        }
+        shared.push_str("</sst>");
+
+        part(&mut zip, "[Content_Types].xml", &types);
+        part(
+            &mut zip,
+            "_rels/.rels",
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+        );
+        part(&mut zip, "xl/workbook.xml", &workbook);
+        part(&mut zip, "xl/_rels/workbook.xml.rels", &workbook_rels);
+        part(&mut zip, "xl/sharedStrings.xml", &shared);
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    /// Write the package to a temporary `.xlsx` file, removed when the returned path drops
+    pub(crate) fn write(&self) -> TempPath {
+        let path = TempPath::new("xlsx");
+        std::fs::write(&path, self.to_bytes()).unwrap();
+        path
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A unique temporary file path, removed when dropped
+pub(crate) struct TempPath(PathBuf);
+
+impl TempPath {
+    pub(crate) fn new(extension: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "excelerator-test-{}-{}.{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed),
+            extension
+        );
+        Self(std::env::temp_dir().join(name))
+    }
+
+    /// Write `text` to a new temporary file, like a CSV file to load
+    #[cfg(feature = "csv")]
+    pub(crate) fn with_text(extension: &str, text: &str) -> Self {
+        let path = Self::new(extension);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{DataError, DataType, Range, WorkbookData};
+use std::sync::OnceLock;
+
+/// Header of the column holding each value's source column name, from `WorkbookData::unpivot`
+const COLUMN_HEADER: &str = "column";
+/// Header of the column holding the values, from `WorkbookData::unpivot`
+const VALUE_HEADER: &str = "value";
+
+impl WorkbookData {
+    /// Melt a wide layout (like one column per month) into long rows of the id columns, a
+    /// `column` column naming the source column, and a `value` column
+    ///
+    /// Each row becomes one row per value column, empty cells included. An empty
+    /// `value_columns` melts every column that is not an id column. Row numbers in the result
+    /// start at 1, directly below the header.
+    pub fn unpivot(
+        &self,
+        id_columns: &[&str],
+        value_columns: &[&str],
+    ) -> Result<WorkbookData, DataError> {
+        self.unpivot_as(id_columns, value_columns, COLUMN_HEADER, VALUE_HEADER)
+    }
+
+    /// Like `unpivot`, with the given headers for the column name and value columns
+    pub fn unpivot_as(
+        &self,
+        id_columns: &[&str],
+        value_columns: &[&str],
+        column_header: &str,
+        value_header: &str,
+    ) -> Result<WorkbookData, DataError> {
+        let lookup = |h: &&str| {
+            self.header
+                .get(*h)
+                .copied()
+                .ok_or_else(|| DataError::NoValue((*h).into()))
+        };
+        let ids = id_columns
+            .iter()
+            .map(lookup)
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = if value_columns.is_empty() {
+            (0..self.columns.len())
+                .filter(|i| !ids.contains(i))
+                .collect()
+        } else {
+            value_columns
+                .iter()
+                .map(lookup)
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let rows: Vec<u32> = self.rows().map(|row| row.number()).collect();
+        let height = (rows.len() * values.len()) as u32;
+        let (name_col, value_col) = (ids.len() as u32, ids.len() as u32 + 1);
+        let mut range = Range::new((0, 0), (height, value_col));
+
+        for (c, &i) in ids.iter().enumerate() {
+            range.set_value(
+                (0, c as u32),
+                DataType::String(self.columns[i].name.clone()),
+            );
+        }
+        range.set_value((0, name_col), DataType::String(column_header.to_owned()));
+        range.set_value((0, value_col), DataType::String(value_header.to_owned()));
+
+        let mut r = 1;
+        for &row_number in &rows {
+            for &v in &values {
+                for (c, &i) in ids.iter().enumerate() {
+                    if let Some(value) = self.range.get_value((row_number, self.columns[i].index)) {
+                        range.set_value((r, c as u32), value.clone());
+                    }
+                }
+
+                let column = &self.columns[v];
+                range.set_value((r, name_col), DataType::String(column.name.clone()));
+                if let Some(value) = self.range.get_value((row_number, column.index)) {
+                    let value = column.round(value).unwrap_or_else(|| value.clone());
+                    range.set_value((r, value_col), value);
+                }
+                r += 1;
+            }
+        }
+
+        let mut data =
+            Self::with_header(range, 0).expect("unpivoted range always has a header row");
+
+        data.sheet_name = self.sheet_name.clone();
+        data.fingerprint = OnceLock::from(self.fingerprint().map(String::from));
+        for (column, &i) in data.columns.iter_mut().zip(&ids) {
+            column.redacted = self.columns[i].redacted;
+            column.money_scale = self.columns[i].money_scale;
+            column.round_places = self.columns[i].round_places;
+        }
+        if let Some(column) = data.columns.last_mut() {
+            column.redacted = values.iter().any(|&v| self.columns[v].redacted);
+        }
+
+        Ok(data)
+    }
+}
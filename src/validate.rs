@@ -0,0 +1,422 @@
+//! Per-column rules checked on every row, reported with row and column locations
+
+use crate::schema::MAX_LISTED_ROWS;
+use crate::{
+    cell_is_empty, cell_to_str, ColumnId, ColumnType, DataType, RowData, Schema, SchemaColumn,
+    Violation, Violations, WorkbookData,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A rule the values of a column must follow
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Constraint {
+    /// Cells may not be empty
+    NotEmpty,
+    /// Values must match a regular expression (add `^` and `$` to match the whole value)
+    #[cfg(feature = "regex")]
+    Pattern(regex::Regex),
+    /// Values must be numbers within these inclusive bounds
+    Range { min: Option<f64>, max: Option<f64> },
+    /// Values must be one of these strings
+    OneOf(Vec<String>),
+    /// No two rows may have the same value
+    Unique,
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Constraint) -> bool {
+        match (self, other) {
+            (Constraint::NotEmpty, Constraint::NotEmpty) => true,
+            #[cfg(feature = "regex")]
+            (Constraint::Pattern(a), Constraint::Pattern(b)) => a.as_str() == b.as_str(),
+            (
+                Constraint::Range { min, max },
+                Constraint::Range {
+                    min: other_min,
+                    max: other_max,
+                },
+            ) => min == other_min && max == other_max,
+            (Constraint::OneOf(a), Constraint::OneOf(b)) => a == b,
+            (Constraint::Unique, Constraint::Unique) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::NotEmpty => write!(f, "must not be empty"),
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(regex) => write!(f, "must match '{}'", regex.as_str()),
+            Constraint::Range {
+                min: Some(min),
+                max: Some(max),
+            } => write!(f, "must be a number from {} to {}", min, max),
+            Constraint::Range {
+                min: Some(min),
+                max: None,
+            } => write!(f, "must be a number of at least {}", min),
+            Constraint::Range {
+                min: None,
+                max: Some(max),
+            } => write!(f, "must be a number of at most {}", max),
+            Constraint::Range {
+                min: None,
+                max: None,
+            } => write!(f, "must be a number"),
+            Constraint::OneOf(values) => write!(f, "must be one of '{}'", values.join("', '")),
+            Constraint::Unique => write!(f, "must be unique"),
+        }
+    }
+}
+
+/// Read a cell as a number, including numbers stored as text
+fn cell_number(value: &DataType) -> Option<f64> {
+    match value {
+        DataType::Int(i) => Some(*i as f64),
+        DataType::Float(f) => Some(*f),
+        DataType::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+impl Constraint {
+    /// Check a non-empty value against this rule; `Unique` is checked by `RowChecker`
+    fn accepts(&self, value: &DataType) -> bool {
+        match self {
+            Constraint::NotEmpty | Constraint::Unique => true,
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(regex) => regex.is_match(&cell_to_str(value)),
+            Constraint::Range { min, max } => cell_number(value)
+                .is_some_and(|n| min.is_none_or(|min| n >= min) && max.is_none_or(|max| n <= max)),
+            Constraint::OneOf(values) => {
+                let value = cell_to_str(value);
+                values.iter().any(|v| *v == value)
+            }
+        }
+    }
+}
+
+impl Schema {
+    fn constrain(mut self, column_header: &str, constraint: Constraint) -> Self {
+        let index = match self.columns.iter().position(|c| c.name == column_header) {
+            Some(index) => index,
+            None => {
+                self = self.optional_column(column_header, ColumnType::String);
+                self.columns.len() - 1
+            }
+        };
+
+        self.columns[index].constraints.push(constraint);
+        self
+    }
+
+    /// Require a value in every row of a column
+    ///
+    /// Like the other constraints, this adds the column as optional text if it is not in the
+    /// schema yet.
+    pub fn not_empty(self, column_header: &str) -> Self {
+        self.constrain(column_header, Constraint::NotEmpty)
+    }
+
+    /// Require values to match a regular expression
+    #[cfg(feature = "regex")]
+    pub fn pattern(self, column_header: &str, regex: regex::Regex) -> Self {
+        self.constrain(column_header, Constraint::Pattern(regex))
+    }
+
+    /// Require values to be numbers within `min..=max`
+    pub fn range(self, column_header: &str, min: f64, max: f64) -> Self {
+        let range = Constraint::Range {
+            min: Some(min),
+            max: Some(max),
+        };
+        self.constrain(column_header, range)
+    }
+
+    pub fn at_least(self, column_header: &str, min: f64) -> Self {
+        let range = Constraint::Range {
+            min: Some(min),
+            max: None,
+        };
+        self.constrain(column_header, range)
+    }
+
+    pub fn at_most(self, column_header: &str, max: f64) -> Self {
+        let range = Constraint::Range {
+            min: None,
+            max: Some(max),
+        };
+        self.constrain(column_header, range)
+    }
+
+    /// Require values to be one of `values`
+    pub fn one_of(self, column_header: &str, values: &[&str]) -> Self {
+        let values = values.iter().map(|&v| v.to_owned()).collect();
+        self.constrain(column_header, Constraint::OneOf(values))
+    }
+
+    /// Require every non-empty value of a column to be different
+    pub fn unique(self, column_header: &str) -> Self {
+        self.constrain(column_header, Constraint::Unique)
+    }
+
+    /// Check every row for missing columns, type mismatches and broken constraints
+    pub fn validate(&self, data: &WorkbookData) -> ValidationReport {
+        let mut checker = RowChecker::new(self, data);
+
+        let missing = self
+            .columns
+            .iter()
+            .zip(&checker.columns)
+            .filter(|(column, (_, id))| column.required && id.is_none())
+            .map(|(column, _)| column.name.clone())
+            .collect();
+
+        let rows = data
+            .rows()
+            .filter_map(|row| {
+                let violations = checker.check(&row, false);
+                (!violations.is_empty()).then(|| Violations {
+                    row: row.number(),
+                    violations,
+                })
+            })
+            .collect();
+
+        ValidationReport {
+            missing,
+            unexpected: self.unexpected_columns(data),
+            rows,
+        }
+    }
+}
+
+/// Checks rows against a schema, remembering values seen in `unique` columns
+pub(crate) struct RowChecker<'s> {
+    columns: Vec<(&'s SchemaColumn, Option<ColumnId>)>,
+    /// Row of the first occurrence of each value, per column
+    seen: Vec<HashMap<String, u32>>,
+}
+
+impl<'s> RowChecker<'s> {
+    pub fn new(schema: &'s Schema, data: &WorkbookData) -> Self {
+        let columns: Vec<_> = schema
+            .columns()
+            .iter()
+            .map(|c| (c, data.column_id(&c.name)))
+            .collect();
+
+        Self {
+            seen: columns.iter().map(|_| HashMap::new()).collect(),
+            columns,
+        }
+    }
+
+    /// List the ways a row breaks the schema, optionally including missing required columns
+    pub fn check(&mut self, row: &RowData, report_missing: bool) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for ((column, id), seen) in self.columns.iter().zip(&mut self.seen) {
+            let id = match id {
+                Some(id) => *id,
+                None => {
+                    if report_missing && column.required {
+                        violations.push(Violation::MissingColumn(column.name.clone()));
+                    }
+                    continue;
+                }
+            };
+
+            let position = || row.source.column_position(&column.name);
+
+            let value = match row.source.get_cell_by_id(row.row_number, id) {
+                Some(value) if !cell_is_empty(value) => value,
+                _ => {
+                    if column.constraints.contains(&Constraint::NotEmpty) {
+                        violations.push(Violation::Empty {
+                            column: column.name.clone(),
+                            position: position(),
+                        });
+                    }
+                    continue;
+                }
+            };
+
+            if !column.column_type.accepts(value) {
+                violations.push(Violation::TypeMismatch {
+                    column: column.name.clone(),
+                    position: position(),
+                    expected: column.column_type,
+                    value: row.source.error_text(row.row_number, &column.name, value),
+                });
+                continue;
+            }
+
+            for constraint in &column.constraints {
+                if !constraint.accepts(value) {
+                    violations.push(Violation::Constraint {
+                        column: column.name.clone(),
+                        position: position(),
+                        constraint: constraint.clone(),
+                        value: row.source.error_text(row.row_number, &column.name, value),
+                    });
+                }
+            }
+
+            if column.constraints.contains(&Constraint::Unique) {
+                let key = cell_to_str(value).into_owned();
+                match seen.get(&key) {
+                    Some(&first_row) => violations.push(Violation::Duplicate {
+                        column: column.name.clone(),
+                        position: position(),
+                        value: key,
+                        first_row,
+                    }),
+                    None => {
+                        seen.insert(key, row.row_number);
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+/// Every way a sheet breaks a `Schema`, from `Schema::validate`
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Required columns that are not in the sheet
+    pub missing: Vec<String>,
+    /// Columns not in the schema, unless it allows extra columns
+    pub unexpected: Vec<String>,
+    /// Rows with at least one violation, in sheet order
+    pub rows: Vec<Violations>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty() && self.rows.is_empty()
+    }
+
+    /// Number of cell violations across all rows
+    pub fn violation_count(&self) -> usize {
+        self.rows.iter().map(|r| r.violations.len()).sum()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "Data is valid");
+        }
+
+        write!(f, "{} rows break the schema", self.rows.len())?;
+
+        if !self.missing.is_empty() {
+            write!(f, "\n  Missing columns: '{}'", self.missing.join("', '"))?;
+        }
+
+        if !self.unexpected.is_empty() {
+            write!(
+                f,
+                "\n  Unexpected columns: '{}'",
+                self.unexpected.join("', '")
+            )?;
+        }
+
+        for row in self.rows.iter().take(MAX_LISTED_ROWS) {
+            write!(f, "\n  {}", row)?;
+        }
+
+        if self.rows.len() > MAX_LISTED_ROWS {
+            write!(f, "\n  and {} more rows", self.rows.len() - MAX_LISTED_ROWS)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_range, LoadOptions, Range};
+
+    fn orders() -> WorkbookData {
+        let rows = [
+            ["Id", "Qty", "Status", "Note"],
+            ["A1", "3", "open", ""],
+            ["A2", "-1", "lost", ""],
+            ["A1", "", "open", ""],
+            ["A3", "x", "closed", ""],
+        ];
+        let mut range = Range::new((0, 0), (4, 3));
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                let value = match cell.parse::<i64>() {
+                    Ok(n) => DataType::Int(n),
+                    Err(_) if cell.is_empty() => DataType::Empty,
+                    Err(_) => DataType::String(cell.to_string()),
+                };
+                range.set_value((r as u32, c as u32), value);
+            }
+        }
+        from_range(range, &LoadOptions::new()).unwrap()
+    }
+
+    #[test]
+    fn reports_every_violation_by_row() {
+        let schema = Schema::new()
+            .column("Id", ColumnType::String)
+            .column("Qty", ColumnType::Integer)
+            .column("Status", ColumnType::String)
+            .column("Due", ColumnType::DateTime)
+            .unique("Id")
+            .not_empty("Qty")
+            .at_least("Qty", 0.)
+            .one_of("Status", &["open", "closed"]);
+        let report = schema.validate(&orders());
+
+        assert!(!report.is_valid());
+        assert_eq!(report.missing, ["Due"]);
+        assert_eq!(report.unexpected, ["Note"]);
+        let rows: Vec<u32> = report.rows.iter().map(|r| r.row).collect();
+        assert_eq!(rows, [2, 3, 4]);
+        assert_eq!(report.violation_count(), 5);
+
+        assert!(matches!(
+            &report.rows[0].violations[..],
+            [Violation::Constraint { .. }, Violation::Constraint { .. }]
+        ));
+        assert!(matches!(
+            &report.rows[1].violations[..],
+            [
+                Violation::Duplicate { first_row: 1, .. },
+                Violation::Empty { .. }
+            ]
+        ));
+        assert!(matches!(
+            &report.rows[2].violations[..],
+            [Violation::TypeMismatch { .. }]
+        ));
+        assert_eq!(
+            report.rows[0].to_string(),
+            "Row 3: Column B / 'Qty' value '-1' must be a number of at least 0; \
+             Column C / 'Status' value 'lost' must be one of 'open', 'closed'"
+        );
+    }
+
+    #[test]
+    fn passes_valid_data() {
+        let schema = Schema::new()
+            .column("Id", ColumnType::String)
+            .allow_extra_columns(true);
+        let report = schema.validate(&orders());
+        assert!(report.is_valid());
+        assert_eq!(report.to_string(), "Data is valid");
+    }
+}
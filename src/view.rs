@@ -0,0 +1,254 @@
+//! Renamed and projected views of loaded data, for code written against canonical column names
+
+use crate::{ColumnId, DataError, OwnedRow, RowData, RowsIterator, WorkbookData};
+use std::borrow::Cow;
+use std::str::FromStr;
+
+/// Some columns of a `WorkbookData`, possibly under other names, from
+/// `WorkbookData::with_renamed_columns` or `WorkbookData::select`
+///
+/// Views borrow the data; nothing is copied.
+#[derive(Clone)]
+pub struct ColumnView<'a> {
+    source: &'a WorkbookData,
+    /// Each column's name in the view, with its column in the source
+    columns: Vec<(String, ColumnId)>,
+}
+
+impl WorkbookData {
+    /// View every column, with the columns named by the keys of `renames` given the
+    /// corresponding new names, like `[("Cust ID", "customer_id")]`
+    ///
+    /// Fails with `DataError::NoValue` if a column to rename does not exist.
+    pub fn with_renamed_columns<I, K, V>(&self, renames: I) -> Result<ColumnView<'_>, DataError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        self.view().with_renamed_columns(renames)
+    }
+
+    /// View only the given columns, in the given order
+    ///
+    /// Fails with `DataError::NoValue` if a column does not exist.
+    pub fn select(&self, column_headers: &[&str]) -> Result<ColumnView<'_>, DataError> {
+        self.view().select(column_headers)
+    }
+
+    /// View every column under its own name
+    fn view(&self) -> ColumnView<'_> {
+        ColumnView {
+            source: self,
+            columns: self
+                .headers()
+                .enumerate()
+                .map(|(i, h)| (h.to_owned(), ColumnId(i)))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> ColumnView<'a> {
+    fn lookup(&self, column_header: &str) -> Result<usize, DataError> {
+        self.columns
+            .iter()
+            .position(|(name, _)| name == column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))
+    }
+
+    /// Rename columns of this view, by their names in the view
+    pub fn with_renamed_columns<I, K, V>(mut self, renames: I) -> Result<Self, DataError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut renamed = Vec::new();
+        for (from, to) in renames {
+            renamed.push((self.lookup(from.as_ref())?, to.into()));
+        }
+
+        for (i, name) in renamed {
+            self.columns[i].0 = name;
+        }
+        Ok(self)
+    }
+
+    /// Keep only the given columns of this view, by their names in the view
+    pub fn select(self, column_headers: &[&str]) -> Result<Self, DataError> {
+        let columns = column_headers
+            .iter()
+            .map(|h| Ok(self.columns[self.lookup(h)?].clone()))
+            .collect::<Result<_, DataError>>()?;
+
+        Ok(Self { columns, ..self })
+    }
+
+    /// Get the data this is a view of
+    pub fn source(&self) -> &'a WorkbookData {
+        self.source
+    }
+
+    /// Get the column headers of the view, in view order
+    pub fn headers(&self) -> impl Iterator<Item = &str> + '_ {
+        self.columns.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Resolve a view column name to a handle for `WorkbookData::get_by_id` on the source
+    pub fn column_id(&self, column_header: &str) -> Option<ColumnId> {
+        let i = self.lookup(column_header).ok()?;
+        Some(self.columns[i].1)
+    }
+
+    /// Get the header a view column has in the source data
+    pub fn source_header(&self, column_header: &str) -> Option<&'a str> {
+        let source = self.source;
+        self.column_id(column_header)
+            .map(|id| source.columns[id.0].name.as_str())
+    }
+
+    /// Get a cell value as a string by row number and view column name
+    pub fn get(&self, row_number: u32, column_header: &str) -> Option<Cow<'a, str>> {
+        self.source
+            .get_by_id(row_number, self.column_id(column_header)?)
+    }
+
+    pub fn iter_rows(&self) -> ViewRows<'_> {
+        ViewRows {
+            view: self,
+            rows: self.source.iter_rows(),
+        }
+    }
+}
+
+/// Iterator returned by `ColumnView::iter_rows`
+pub struct ViewRows<'v> {
+    view: &'v ColumnView<'v>,
+    rows: RowsIterator<'v>,
+}
+
+impl<'v> Iterator for ViewRows<'v> {
+    type Item = ViewRow<'v>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.rows.next()?;
+        Some(ViewRow {
+            view: self.view,
+            row,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+/// A row seen through a `ColumnView`, read by the view's column names
+pub struct ViewRow<'v> {
+    view: &'v ColumnView<'v>,
+    row: RowData<'v>,
+}
+
+impl<'v> ViewRow<'v> {
+    /// Get the row number of this data in the source workbook
+    pub fn number(&self) -> u32 {
+        self.row.number()
+    }
+
+    fn source_header(&self, column_header: &str) -> Result<&'v str, DataError> {
+        self.view
+            .source_header(column_header)
+            .ok_or_else(|| DataError::NoValue(column_header.into()))
+    }
+
+    /// Get the value in the cell of this row with the matching view column name
+    pub fn get(&self, column_header: &str) -> Result<Cow<'v, str>, DataError> {
+        self.row.get(self.source_header(column_header)?)
+    }
+
+    /// Parse a cell's text as `RowData::parse` does; errors name the column by its source
+    /// header, to point at the sheet
+    pub fn parse<T: FromStr>(&self, column_header: &str) -> Result<T, DataError> {
+        self.row.parse(self.source_header(column_header)?)
+    }
+
+    /// Check if the cell in this row with the matching view column name is empty
+    pub fn is_cell_empty(&self, column_header: &str) -> bool {
+        self.source_header(column_header)
+            .map_or(true, |h| self.row.is_cell_empty(h))
+    }
+
+    /// Iterate over `(view header, value)` pairs in view order
+    pub fn iter(&self) -> impl Iterator<Item = (&'v str, Cow<'v, str>)> + 'v {
+        let (view, row_number) = (self.view, self.row.number());
+        view.columns.iter().map(move |(name, id)| {
+            let value = view.source.get_by_id(row_number, *id).unwrap_or_default();
+            (name.as_str(), value)
+        })
+    }
+
+    /// Copy this row's view header/value pairs out of the source data
+    pub fn to_owned_row(&self) -> OwnedRow {
+        let cells = self
+            .iter()
+            .map(|(h, v)| (h.to_owned(), v.into_owned()))
+            .collect();
+        OwnedRow::new(self.number(), cells)
+    }
+
+    /// Get the row within the source data, read by source headers
+    pub fn row(&self) -> &RowData<'v> {
+        &self.row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_range, DataType, LoadOptions, Range};
+
+    fn customers() -> WorkbookData {
+        let rows = [["Cust ID", "Name", "City"], ["7", "Ann", "Oslo"]];
+        let mut range = Range::new((0, 0), (1, 2));
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                range.set_value((r as u32, c as u32), DataType::String(cell.to_string()));
+            }
+        }
+        from_range(range, &LoadOptions::new()).unwrap()
+    }
+
+    #[test]
+    fn renames_and_selects_columns() {
+        let data = customers();
+        let view = data
+            .with_renamed_columns([("Cust ID", "customer_id")])
+            .unwrap()
+            .select(&["City", "customer_id"])
+            .unwrap();
+
+        assert_eq!(view.headers().collect::<Vec<_>>(), ["City", "customer_id"]);
+        assert_eq!(view.source_header("customer_id"), Some("Cust ID"));
+        assert_eq!(view.get(1, "customer_id").as_deref(), Some("7"));
+        assert_eq!(view.get(1, "Cust ID"), None);
+
+        let row = view.iter_rows().next().unwrap();
+        assert_eq!(row.get("City").unwrap(), "Oslo");
+        assert!(matches!(row.get("Name"), Err(DataError::NoValue(h)) if h == "Name"));
+    }
+
+    #[test]
+    fn rejects_unknown_columns() {
+        let data = customers();
+        let renamed = data.with_renamed_columns([("Customer", "customer_id")]);
+        assert!(matches!(renamed, Err(DataError::NoValue(h)) if h == "Customer"));
+
+        let selected = data.select(&["Name", "Email"]);
+        assert!(matches!(selected, Err(DataError::NoValue(h)) if h == "Email"));
+
+        // Once renamed, a column is only known by its new name
+        let view = data.with_renamed_columns([("Name", "name")]).unwrap();
+        assert!(matches!(view.select(&["Name"]), Err(DataError::NoValue(h)) if h == "Name"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Writing sheets to new xlsx files
+
+use crate::{DataType, WorkbookData, WriteError, WritePlan};
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use std::path::{Path, PathBuf};
+
+const DATE_FORMAT: &str = "yyyy-mm-dd";
+const DATETIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+/// Name rust_xlsxwriter gives the sheet when none is set
+const DEFAULT_SHEET_NAME: &str = "Sheet1";
+
+/// Writes rows to a single-sheet xlsx file
+///
+/// Nothing is written to disk until `save` is called.
+pub struct WorkbookWriter {
+    workbook: Workbook,
+    path: PathBuf,
+    next_row: u32,
+    date_format: Format,
+    datetime_format: Format,
+}
+
+impl WorkbookWriter {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet();
+
+        Self {
+            workbook,
+            path: path.as_ref().to_owned(),
+            next_row: 0,
+            date_format: Format::new().set_num_format(DATE_FORMAT),
+            datetime_format: Format::new().set_num_format(DATETIME_FORMAT),
+        }
+    }
+
+    fn worksheet(&mut self) -> Result<&mut Worksheet, WriteError> {
+        Ok(self.workbook.worksheet_from_index(0)?)
+    }
+
+    pub fn set_sheet_name(&mut self, sheet_name: &str) -> Result<(), WriteError> {
+        self.worksheet()?.set_name(sheet_name)?;
+        Ok(())
+    }
+
+    /// Write a row of header names
+    pub fn write_headers(&mut self, headers: &[&str]) -> Result<(), WriteError> {
+        let row = self.next_row;
+        let worksheet = self.worksheet()?;
+        for (col, header) in headers.iter().enumerate() {
+            worksheet.write_string(row, col as u16, *header)?;
+        }
+        self.next_row += 1;
+        Ok(())
+    }
+
+    /// Write a row of values, keeping their types; empty values leave the cell blank
+    pub fn write_row(&mut self, values: &[DataType]) -> Result<(), WriteError> {
+        let row = self.next_row;
+        for (col, value) in values.iter().enumerate() {
+            self.write_value(row, col as u16, value)?;
+        }
+        self.next_row += 1;
+        Ok(())
+    }
+
+    fn write_value(&mut self, row: u32, col: u16, value: &DataType) -> Result<(), WriteError> {
+        // Borrow the formats separately from the worksheet
+        let worksheet = self.workbook.worksheet_from_index(0)?;
+
+        match value {
+            DataType::Int(i) => worksheet.write_number(row, col, *i as f64)?,
+            DataType::Float(f) => worksheet.write_number(row, col, *f)?,
+            DataType::String(s) => worksheet.write_string(row, col, s)?,
+            DataType::Bool(b) => worksheet.write_boolean(row, col, *b)?,
+            DataType::DateTime(serial) => {
+                let format = if serial.fract() == 0. {
+                    &self.date_format
+                } else {
+                    &self.datetime_format
+                };
+                worksheet.write_number_with_format(row, col, *serial, format)?
+            }
+            // Error values can only come from formulas, so keep their text
+            DataType::Error(e) => worksheet.write_string(row, col, e.to_string())?,
+            DataType::Empty => worksheet,
+        };
+        Ok(())
+    }
+
+    /// Write the file to the path given to `new`
+    pub fn save(&mut self) -> Result<(), WriteError> {
+        self.workbook.save(&self.path)?;
+        Ok(())
+    }
+}
+
+impl WorkbookData {
+    /// Write the header row and data rows to a new xlsx file
+    ///
+    /// Rows above the header and columns outside the data are not written. The sheet keeps its
+    /// name if it was loaded from a workbook.
+    pub fn save_as<P: AsRef<Path>>(&self, path: P) -> Result<(), WriteError> {
+        let (mut writer, _) = self.writer(path.as_ref())?;
+        writer.save()
+    }
+
+    /// Do a dry run of `save_as`, building the workbook without writing the file
+    pub fn plan_save_as<P: AsRef<Path>>(&self, path: P) -> Result<WritePlan, WriteError> {
+        let (_, plan) = self.writer(path.as_ref())?;
+        Ok(plan)
+    }
+
+    /// Build the workbook `save_as` writes, with a plan of what it holds
+    fn writer(&self, path: &Path) -> Result<(WorkbookWriter, WritePlan), WriteError> {
+        let mut writer = WorkbookWriter::new(path);
+
+        if let Some(sheet_name) = &self.sheet_name {
+            writer.set_sheet_name(sheet_name)?;
+        }
+
+        let headers: Vec<&str> = self.headers().collect();
+        writer.write_headers(&headers)?;
+        let mut cells = headers.len();
+
+        let mut values = Vec::with_capacity(self.columns.len());
+        for row in self.first_row..=self.last_row {
+            values.clear();
+            values.extend(self.columns.iter().map(|c| {
+                self.range
+                    .get_value((row, c.index))
+                    .map(|v| c.round(v).unwrap_or_else(|| v.clone()))
+                    .unwrap_or(DataType::Empty)
+            }));
+            cells += values.iter().filter(|v| !v.is_empty()).count();
+            writer.write_row(&values)?;
+        }
+
+        let rows = (self.last_row + 1 - self.first_row) as usize;
+        let sheet_name = self.sheet_name.as_deref().unwrap_or(DEFAULT_SHEET_NAME);
+        let plan = WritePlan::new(Some(path), sheet_name, rows, cells);
+        Ok((writer, plan))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{TempPath, TestXlsx};
+    use crate::WorkbookData;
+
+    #[test]
+    fn plans_a_save_without_writing() {
+        let source = TestXlsx::new()
+            .sheet("Orders", &[&["Item", "Qty"], &["Pen", "3"], &["Ink", ""]])
+            .write();
+        let data = WorkbookData::from_path(&source).unwrap();
+        let output = TempPath::new("xlsx");
+
+        let plan = data.plan_save_as(&output).unwrap();
+        assert_eq!(plan.path.as_deref(), Some(output.as_ref()));
+        assert_eq!(plan.sheets[0].name, "Orders");
+        assert_eq!((plan.rows(), plan.sheets[0].cells), (2, 5));
+        assert!(!output.exists());
+    }
+}
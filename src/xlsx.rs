@@ -0,0 +1,346 @@
+//! Direct access to xlsx package parts that calamine does not expose
+//!
+//! `XlsxPackage` is also public, as an escape hatch for reading parts (like a sheet's XML or
+//! custom XML) that the typed API does not cover.
+
+use crate::coords::{self, Span};
+use crate::{LoadError, SheetVisibility};
+use flate2::read::DeflateDecoder;
+use quick_xml::events::{BytesStart, Event};
+#[cfg(feature = "write")]
+use std::collections::HashMap;
+use std::fs::File;
+#[cfg(feature = "write")]
+use std::io::Write;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use zip::read::ZipFile;
+#[cfg(feature = "write")]
+use zip::write::{FileOptions, ZipWriter};
+use zip::CompressionMethod;
+use zip::ZipArchive;
+
+impl From<zip::result::ZipError> for LoadError {
+    fn from(err: zip::result::ZipError) -> Self {
+        LoadError::InvalidPackage(err.to_string())
+    }
+}
+
+impl From<quick_xml::Error> for LoadError {
+    fn from(err: quick_xml::Error) -> Self {
+        LoadError::InvalidPackage(err.to_string())
+    }
+}
+
+pub(crate) fn is_xlsx_path(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("xlsx") | Some("xlsm") | Some("xlam")
+    )
+}
+
+/// Get an attribute value by its local name (ignoring any namespace prefix)
+pub(crate) fn attr(e: &BytesStart, local_name: &[u8]) -> Option<String> {
+    e.attributes().flatten().find_map(|a| {
+        let key = match a.key.iter().position(|&b| b == b':') {
+            Some(i) => &a.key[i + 1..],
+            None => a.key,
+        };
+        if key == local_name {
+            a.unescaped_value()
+                .ok()
+                .map(|v| String::from_utf8_lossy(&v).into_owned())
+        } else {
+            None
+        }
+    })
+}
+
+/// Resolve a relationship target against the directory of the part that references it
+fn resolve_target(base_dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_owned();
+    }
+
+    let mut parts: Vec<&str> = base_dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            s => parts.push(s),
+        }
+    }
+    parts.join("/")
+}
+
+/// A link from one package part to another, from `XlsxPackage::relationships`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relationship {
+    pub id: String,
+    /// Relationship type URI, like `.../relationships/worksheet`
+    pub kind: String,
+    /// Package path of the target part (or the raw URL for external targets)
+    pub target: String,
+}
+
+/// A worksheet's name and the package path of its XML part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetEntry {
+    pub name: String,
+    pub path: String,
+    pub visibility: SheetVisibility,
+}
+
+/// The zip package of an xlsx file, read part by part
+pub struct XlsxPackage<R = BufReader<File>> {
+    zip: ZipArchive<R>,
+}
+
+impl XlsxPackage<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        let file = File::open(path).map_err(|e| LoadError::InvalidPackage(e.to_string()))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> XlsxPackage<R> {
+    /// Read a package from any seekable source, like a `Cursor` over bytes in memory
+    pub fn new(reader: R) -> Result<Self, LoadError> {
+        Ok(Self {
+            zip: ZipArchive::new(reader)?,
+        })
+    }
+
+    /// List the path of every part in the package, like `xl/worksheets/sheet1.xml`
+    pub fn part_names(&self) -> Vec<String> {
+        self.zip.file_names().map(String::from).collect()
+    }
+
+    /// Read a part by its path inside the package, or `None` if it does not exist
+    pub fn read_part(&mut self, name: &str) -> Result<Option<Vec<u8>>, LoadError> {
+        let mut file = match self.zip.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut buf = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut buf)
+            .map_err(|e| LoadError::InvalidPackage(e.to_string()))?;
+        Ok(Some(buf))
+    }
+
+    /// Open a part for streaming, to read the start of a part too large to load whole
+    pub(crate) fn part_reader(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<BufReader<ZipFile<'_>>>, LoadError> {
+        match self.zip.by_name(name) {
+            Ok(file) => Ok(Some(BufReader::new(file))),
+            Err(zip::result::ZipError::FileNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Find where a part's compressed data is in the file, and how it is compressed
+    fn part_location(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<(u64, u64, CompressionMethod)>, LoadError> {
+        match self.zip.by_name(name) {
+            Ok(file) => Ok(Some((
+                file.data_start(),
+                file.compressed_size(),
+                file.compression(),
+            ))),
+            Err(zip::result::ZipError::FileNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Read a part as text, replacing any invalid UTF-8
+    pub fn read_part_text(&mut self, name: &str) -> Result<Option<String>, LoadError> {
+        Ok(self
+            .read_part(name)?
+            .map(|buf| String::from_utf8_lossy(&buf).into_owned()))
+    }
+
+    /// Read the relationships of a part, with targets resolved to package paths
+    pub fn relationships(&mut self, part: &str) -> Result<Vec<Relationship>, LoadError> {
+        let (dir, file) = match part.rfind('/') {
+            Some(i) => (&part[..i], &part[i + 1..]),
+            None => ("", part),
+        };
+        let rels_path = if dir.is_empty() {
+            format!("_rels/{}.rels", file)
+        } else {
+            format!("{}/_rels/{}.rels", dir, file)
+        };
+
+        let xml = match self.read_part(&rels_path)? {
+            Some(xml) => xml,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut rels = Vec::new();
+        for_each_element(&xml, |e| {
+            if e.local_name() == b"Relationship" {
+                let external = attr(e, b"TargetMode").as_deref() == Some("External");
+                let target = attr(e, b"Target").unwrap_or_default();
+                rels.push(Relationship {
+                    id: attr(e, b"Id").unwrap_or_default(),
+                    kind: attr(e, b"Type").unwrap_or_default(),
+                    target: if external {
+                        target
+                    } else {
+                        resolve_target(dir, &target)
+                    },
+                });
+            }
+        })?;
+        Ok(rels)
+    }
+
+    /// List the worksheets in workbook order with their package paths
+    pub fn sheets(&mut self) -> Result<Vec<SheetEntry>, LoadError> {
+        let xml = self
+            .read_part("xl/workbook.xml")?
+            .ok_or_else(|| LoadError::InvalidPackage("missing xl/workbook.xml".into()))?;
+        let rels = self.relationships("xl/workbook.xml")?;
+
+        let mut sheets = Vec::new();
+        for_each_element(&xml, |e| {
+            if e.local_name() == b"sheet" {
+                let id = attr(e, b"id").unwrap_or_default();
+                if let Some(rel) = rels.iter().find(|r| r.id == id) {
+                    sheets.push(SheetEntry {
+                        name: attr(e, b"name").unwrap_or_default(),
+                        path: rel.target.clone(),
+                        visibility: SheetVisibility::from_state(attr(e, b"state").as_deref()),
+                    });
+                }
+            }
+        })?;
+        Ok(sheets)
+    }
+
+    /// Get the package path of a worksheet's XML part
+    pub fn sheet_path(&mut self, sheet_name: &str) -> Result<Option<String>, LoadError> {
+        Ok(self
+            .sheets()?
+            .into_iter()
+            .find(|s| s.name == sheet_name)
+            .map(|s| s.path))
+    }
+
+    /// Read a worksheet's XML, or `None` if there is no such sheet
+    pub fn sheet_xml(&mut self, sheet_name: &str) -> Result<Option<Vec<u8>>, LoadError> {
+        match self.sheet_path(sheet_name)? {
+            Some(path) => self.read_part(&path),
+            None => Ok(None),
+        }
+    }
+
+    /// List the custom XML data parts (`customXml/item1.xml`, ...), without their properties
+    pub fn custom_xml_parts(&self) -> Vec<String> {
+        self.zip
+            .file_names()
+            .filter(|name| {
+                name.strip_prefix("customXml/item")
+                    .and_then(|rest| rest.strip_suffix(".xml"))
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .map(String::from)
+            .collect()
+    }
+
+    /// Copy the package to `out`, replacing the parts in `replaced` and dropping those mapped
+    /// to `None`
+    ///
+    /// Other parts are copied as they are, without recompressing.
+    #[cfg(feature = "write")]
+    pub(crate) fn rewrite<W: Write + Seek>(
+        &mut self,
+        out: W,
+        replaced: &HashMap<String, Option<Vec<u8>>>,
+    ) -> Result<W, LoadError> {
+        let mut zip = ZipWriter::new(out);
+
+        for i in 0..self.zip.len() {
+            let file = self.zip.by_index_raw(i)?;
+            match replaced.get(file.name()) {
+                Some(Some(content)) => {
+                    zip.start_file(file.name(), FileOptions::default())?;
+                    zip.write_all(content)?;
+                }
+                Some(None) => {}
+                None => zip.raw_copy_file(file)?,
+            }
+        }
+
+        Ok(zip.finish()?)
+    }
+
+    /// Read the merged cell regions of a sheet as absolute `(start, end)` positions
+    pub fn merged_regions(&mut self, sheet_name: &str) -> Result<Vec<Span>, LoadError> {
+        let xml = match self.sheet_path(sheet_name)? {
+            Some(path) => self.read_part(&path)?.unwrap_or_default(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut regions = Vec::new();
+        for_each_element(&xml, |e| {
+            if e.local_name() == b"mergeCell" {
+                if let Some(region) = attr(e, b"ref").as_deref().and_then(coords::parse_range) {
+                    regions.push(region);
+                }
+            }
+        })?;
+        Ok(regions)
+    }
+}
+
+/// Open a part of an xlsx file for streaming through its own file handle, so the reader does
+/// not borrow an `XlsxPackage` and can outlive it
+pub(crate) fn open_part_stream(
+    path: &Path,
+    name: &str,
+) -> Result<Option<Box<dyn Read + Send>>, LoadError> {
+    let (start, size, compression) = match XlsxPackage::open(path)?.part_location(name)? {
+        Some(location) => location,
+        None => return Ok(None),
+    };
+
+    let mut file = File::open(path).map_err(|e| LoadError::InvalidPackage(e.to_string()))?;
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| LoadError::InvalidPackage(e.to_string()))?;
+    let data = BufReader::new(file).take(size);
+
+    match compression {
+        CompressionMethod::Stored => Ok(Some(Box::new(data))),
+        CompressionMethod::Deflated => Ok(Some(Box::new(DeflateDecoder::new(data)))),
+        other => Err(LoadError::InvalidPackage(format!(
+            "unsupported compression {:?} for {}",
+            other, name
+        ))),
+    }
+}
+
+/// Call `f` on every start or empty element in an XML document
+pub(crate) fn for_each_element<F>(xml: &[u8], mut f: F) -> Result<(), LoadError>
+where
+    F: FnMut(&BytesStart),
+{
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(ref e) | Event::Empty(ref e) => f(e),
+            Event::Eof => return Ok(()),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
@@ -0,0 +1,124 @@
+use excelerator::generator::GeneratorConfig;
+use excelerator::{invariants, LoadOptions, WorkbookData};
+use proptest::prelude::*;
+#[cfg(any(feature = "csv", feature = "write"))]
+use std::path::PathBuf;
+
+/// A temporary file path for one test case, removed when dropped
+#[cfg(any(feature = "csv", feature = "write"))]
+struct TempFile(PathBuf);
+
+#[cfg(any(feature = "csv", feature = "write"))]
+impl TempFile {
+    fn new(seed: u64, extension: &str) -> Self {
+        let name = format!(
+            "excelerator-invariants-{}-{}.{}",
+            std::process::id(),
+            seed,
+            extension
+        );
+        TempFile(std::env::temp_dir().join(name))
+    }
+}
+
+#[cfg(any(feature = "csv", feature = "write"))]
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+proptest! {
+    #[test]
+    fn detects_generated_header(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range, &LoadOptions::new()).unwrap();
+
+        prop_assert_eq!(data.header_row(), generated.header_row);
+        prop_assert_eq!(data.headers().collect::<Vec<_>>(), generated.headers);
+    }
+
+    #[test]
+    fn generated_data_holds_invariants(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range, &LoadOptions::new()).unwrap();
+
+        invariants::assert_all(&data);
+    }
+
+    #[test]
+    fn materialize_round_trips(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range, &LoadOptions::new()).unwrap();
+
+        let copy = data.materialize(|_| true);
+
+        invariants::assert_all(&copy);
+        invariants::assert_round_trip(&data, &copy);
+    }
+
+    #[test]
+    fn range_round_trips(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range.clone(), &LoadOptions::new()).unwrap();
+
+        let reloaded = WorkbookData::from_range(data.as_range().clone(), &LoadOptions::new()).unwrap();
+
+        invariants::assert_round_trip(&data, &reloaded);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_export_round_trips(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range, &LoadOptions::new()).unwrap();
+
+        let file = TempFile::new(seed, "csv");
+        let mut csv = Vec::new();
+        data.to_csv(&mut csv).unwrap();
+        std::fs::write(&file.0, csv).unwrap();
+        let reloaded = WorkbookData::from_path_with_options(&file.0, &LoadOptions::new().header_row(0)).unwrap();
+
+        invariants::assert_all(&reloaded);
+        invariants::assert_round_trip(&data, &reloaded);
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn xlsx_export_round_trips(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range, &LoadOptions::new()).unwrap();
+
+        let file = TempFile::new(seed, "xlsx");
+        data.save_as(&file.0).unwrap();
+        let reloaded = WorkbookData::from_path_with_options(&file.0, &LoadOptions::new().header_row(0)).unwrap();
+
+        invariants::assert_all(&reloaded);
+        invariants::assert_round_trip(&data, &reloaded);
+    }
+
+    #[test]
+    fn rows_iterate_from_both_ends(seed in any::<u64>()) {
+        let generated = GeneratorConfig::random(seed).generate();
+        let data = WorkbookData::from_range(generated.range, &LoadOptions::new()).unwrap();
+
+        let forward: Vec<u32> = (&data).into_iter().map(|row| row.number()).collect();
+        let mut backward: Vec<u32> = data.iter_rows().rev().map(|row| row.number()).collect();
+        backward.reverse();
+
+        prop_assert_eq!(data.iter_rows().len(), forward.len());
+        prop_assert_eq!(&forward, &backward);
+
+        let mut rows = data.iter_rows();
+        let mut alternating = Vec::new();
+        while let Some(row) = rows.next() {
+            alternating.push(row.number());
+            prop_assert_eq!(rows.len(), forward.len() - alternating.len());
+            if let Some(row) = rows.next_back() {
+                alternating.push(row.number());
+            }
+        }
+        alternating.sort_unstable();
+        prop_assert_eq!(alternating, forward);
+    }
+}